@@ -84,7 +84,8 @@ fn ensure_repo(state: &mut AppState) -> Result<(), String> {
         .config
         .get_current_workspace()
         .map_err(|e| e.to_string())?;
-    let repo = TaskRepository::new(ws.path.clone()).map_err(|e| e.to_string())?;
+    let mut repo = TaskRepository::new(ws.path.clone()).map_err(|e| e.to_string())?;
+    repo.set_settings_defaults(state.config.defaults.clone());
     state.repo = Some(repo);
     Ok(())
 }
@@ -510,11 +511,11 @@ async fn sync_workspace(
     state: State<'_, Mutex<AppState>>,
 ) -> Result<SyncResult, String> {
     // Step 1: read config
-    let (workspace_path, webdav_url) = {
+    let (workspace_path, webdav_url, defaults) = {
         let s = lock_state(&state)?;
         let ws = s.config.workspaces.get(&workspace_name)
             .ok_or("Workspace not found")?;
-        (ws.path.clone(), ws.webdav_url.clone().ok_or("No WebDAV URL configured")?)
+        (ws.path.clone(), ws.webdav_url.clone().ok_or("No WebDAV URL configured")?, s.config.defaults.clone())
     };
 
     // Step 2: load credentials
@@ -539,6 +540,7 @@ async fn sync_workspace(
     };
     let result = sync::sync_workspace(
         &workspace_path,
+        &defaults,
         &webdav_url,
         &username,
         &password,
@@ -8,6 +8,8 @@ export interface Task {
   created_at: string;
   updated_at: string;
   parent_id: string | null;
+  priority?: "low" | "medium" | "high";
+  tags?: string[];
 }
 
 export interface TaskList {
@@ -0,0 +1,36 @@
+use anyhow::{Context, Result};
+use colored::*;
+use onyx_core::{AllTasksOptions, TaskStatus};
+use crate::output;
+use crate::commands::{display_timezone, get_repository};
+
+pub fn execute(completed: bool, workspace: Option<String>) -> Result<()> {
+    let (repo, _workspace_name) = get_repository(workspace)?;
+
+    let tasks = repo.all_tasks(&AllTasksOptions { include_completed: completed })
+        .context("Failed to get tasks")?;
+
+    output::header(if completed { "All tasks" } else { "All open tasks" });
+    if tasks.is_empty() {
+        output::item("No tasks");
+        return Ok(());
+    }
+
+    let tz = display_timezone()?;
+    for (list_title, task) in &tasks {
+        let checkbox = if task.status == TaskStatus::Completed { "[✓]".green() } else { "[ ]".normal() };
+        let due_str = task.due_date
+            .map(|due| format!(" (due: {})", output::format_due(due, task.has_time, "%Y-%m-%d", &tz)))
+            .unwrap_or_default();
+        output::item(&format!(
+            "{} {}{} {} {}",
+            checkbox,
+            task.title,
+            due_str,
+            format!("({})", list_title).dimmed(),
+            task.id.to_string().dimmed()
+        ));
+    }
+
+    Ok(())
+}
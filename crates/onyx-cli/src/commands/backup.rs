@@ -0,0 +1,55 @@
+use anyhow::{Context, Result};
+use onyx_core::backup::{create_backup, restore_backup};
+use onyx_core::WorkspaceConfig;
+use std::path::PathBuf;
+use crate::output;
+use crate::commands::{load_config, resolve_workspace, save_config, Changed};
+
+fn absolute(path: String) -> Result<PathBuf> {
+    let path = PathBuf::from(path);
+    Ok(if path.is_relative() { std::env::current_dir()?.join(path) } else { path })
+}
+
+pub fn create(output_path: String, exclude: Vec<String>, workspace_name: Option<String>) -> Result<()> {
+    let config = load_config()?;
+
+    let (name, workspace) = resolve_workspace(&config, workspace_name)?;
+
+    let output_path = absolute(output_path)?;
+    let count = create_backup(&workspace.path, &output_path, &exclude)
+        .context("Failed to create backup")?;
+
+    output::success(&format!(
+        "Backed up workspace \"{}\" ({} files) to {}", name, count, output_path.display()
+    ));
+
+    Ok(())
+}
+
+pub fn restore(archive: String, path: String, register_as: Option<String>) -> Result<Changed> {
+    let archive = absolute(archive)?;
+    let path = absolute(path)?;
+
+    let mut config = load_config()?;
+    if let Some(name) = &register_as {
+        if config.get_workspace(name).is_some() {
+            anyhow::bail!("Workspace '{}' already exists", name);
+        }
+    }
+
+    let count = restore_backup(&archive, &path)
+        .context("Failed to restore backup")?;
+    output::success(&format!("Restored {} files to {}", count, path.display()));
+
+    if let Some(name) = register_as {
+        config.add_workspace(name.clone(), WorkspaceConfig::new(path));
+        save_config(&config)?;
+        output::success(&format!("Registered workspace \"{}\"", name));
+        return Ok(Some(name));
+    }
+
+    // Restoring into the emptied folder of a registered workspace brings that one back
+    Ok(config.workspaces.iter()
+        .find(|(_, workspace)| workspace.path == path)
+        .map(|(name, _)| name.clone()))
+}
@@ -0,0 +1,79 @@
+use anyhow::{Context, Result};
+use colored::*;
+use crate::output;
+use crate::commands::{get_repository, Changed};
+
+pub fn execute(check_only: bool, adopt: bool, rename_files: bool, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+
+    let report = repo.verify_and_repair(check_only)
+        .context("Failed to check workspace")?;
+
+    output::header(&format!("Checking workspace \"{}\"", workspace_name));
+
+    let verb = if check_only { "Would remove" } else { "Removed" };
+    let add_verb = if check_only { "Would add" } else { "Added" };
+    for list in &report.lists {
+        for id in &list.removed {
+            output::warning(&format!("{}: {} missing task {} from order", list.title, verb, id.to_string().dimmed()));
+        }
+        for id in &list.added {
+            output::warning(&format!("{}: {} task {} to order", list.title, add_verb, id.to_string().dimmed()));
+        }
+    }
+    for id in &report.removed_lists {
+        output::warning(&format!("{} missing list {} from list order", verb, id.to_string().dimmed()));
+    }
+    for id in &report.added_lists {
+        output::warning(&format!("{} list {} to list order", add_verb, id.to_string().dimmed()));
+    }
+    for dir in &report.adoptable_dirs {
+        if check_only {
+            output::warning(&format!("Would adopt folder \"{}\" as a list", dir));
+        } else {
+            output::success(&format!("Adopted folder \"{}\" as a list", dir));
+        }
+    }
+    for dir in &report.unregistered_dirs {
+        if adopt && !check_only {
+            match repo.adopt_list(dir) {
+                Ok(list) => output::success(&format!("Adopted folder \"{}\" as a list ({} tasks)", dir, list.tasks.len())),
+                Err(e) => output::error(&format!("Could not adopt \"{}\": {}", dir, e)),
+            }
+        } else {
+            output::warning(&format!(
+                "Folder \"{}\" has no .listdata.json and is not shown as a list (use --adopt to add it)", dir
+            ));
+        }
+    }
+
+    let renames = repo.rename_task_files(!rename_files)
+        .context("Failed to check task file names")?;
+    for rename in &renames {
+        let from = rename.from.display();
+        let to = rename.to.display();
+        if rename.blocked {
+            output::warning(&format!("{} should be named {}, but that file already exists", from, to));
+        } else if rename_files {
+            output::success(&format!("Renamed {} to {}", from, to));
+        } else {
+            output::warning(&format!("{} doesn't match filename_scheme (would rename to {})", from, to));
+        }
+    }
+    if !rename_files && renames.iter().any(|r| !r.blocked) {
+        output::info("Run 'onyx doctor --rename-files' to rename them");
+    }
+
+    let repaired = !check_only && !report.is_clean();
+    if report.is_clean() {
+        output::success("Task and list order are consistent");
+    } else if check_only {
+        output::info("Run 'onyx doctor' without --check-only to apply these fixes");
+    } else {
+        output::success("Workspace metadata repaired");
+    }
+
+    let adopted = adopt && !check_only && !report.unregistered_dirs.is_empty();
+    let renamed = rename_files && renames.iter().any(|r| !r.blocked);
+    Ok((repaired || adopted || renamed).then_some(workspace_name))
+}
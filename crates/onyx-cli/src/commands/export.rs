@@ -0,0 +1,41 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use onyx_core::export::{export_json, export_ndjson};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use crate::output;
+use crate::commands::get_repository;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// A single JSON array of tasks
+    Json,
+    /// One JSON object per line, streamed; suited to large workspaces, grep and jq
+    Ndjson,
+}
+
+/// Export the whole workspace, or only the list named by `list` (by name or @index).
+pub fn execute(format: ExportFormat, list: Option<String>, output_path: Option<String>, workspace: Option<String>) -> Result<()> {
+    let (repo, _workspace_name) = get_repository(workspace)?;
+    // Resolve the list before creating the output file, so a typo leaves nothing behind
+    let list_id = list.map(|spec| repo.resolve_list(&spec).map(|list| list.id)).transpose()?;
+
+    let out: Box<dyn Write> = match &output_path {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).with_context(|| format!("Failed to create {}", path))?,
+        )),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    let count = match format {
+        ExportFormat::Json => export_json(&repo, list_id, out),
+        ExportFormat::Ndjson => export_ndjson(&repo, list_id, out),
+    }
+    .context("Failed to export tasks")?;
+
+    // Stdout carries the export itself, so only report when writing to a file
+    if let Some(path) = output_path {
+        output::success(&format!("Exported {} task{} to {}", count, if count == 1 { "" } else { "s" }, path));
+    }
+
+    Ok(())
+}
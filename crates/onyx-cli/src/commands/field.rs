@@ -0,0 +1,82 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use crate::output;
+use crate::commands::{get_repository, Changed};
+use crate::commands::task::{find_task, remember_task_list};
+
+/// A field name as typed, trimmed. Names are free-form but can't be blank or contain
+/// line breaks or other control characters, which wouldn't survive the frontmatter.
+fn field_key(key: &str) -> Result<String> {
+    let key = key.trim();
+    if key.is_empty() {
+        anyhow::bail!("A field name can't be blank");
+    }
+    if key.chars().any(char::is_control) {
+        anyhow::bail!("A field name can't contain line breaks or other control characters");
+    }
+    Ok(key.to_string())
+}
+
+/// Set a custom field on a task, replacing any earlier value.
+pub fn set(task_id_str: String, key: String, value: String, workspace: Option<String>) -> Result<Changed> {
+    let key = field_key(&key)?;
+    if value.is_empty() {
+        anyhow::bail!("A field's value can't be empty; use 'onyx field unset' to remove it");
+    }
+
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+    let (list, mut task) = find_task(&repo, &workspace_name, &task_id_str)?;
+    task.fields.insert(key.clone(), value.clone());
+    task.updated_at = Utc::now();
+    repo.update_task(list.id, task.clone())
+        .context("Failed to update task")?;
+    remember_task_list(&workspace_name, list.id)?;
+
+    output::success(&format!("Set {} = {} on \"{}\"", key, value, task.title));
+    Ok(Some(workspace_name))
+}
+
+/// Print a field's value on its own, so scripts can capture it.
+pub fn get(task_id_str: String, key: String, workspace: Option<String>) -> Result<()> {
+    let key = field_key(&key)?;
+    let (repo, workspace_name) = get_repository(workspace)?;
+    let (_list, task) = find_task(&repo, &workspace_name, &task_id_str)?;
+
+    match task.fields.get(&key) {
+        Some(value) => println!("{}", value),
+        None => anyhow::bail!("Task \"{}\" has no field \"{}\"", task.title, key),
+    }
+    Ok(())
+}
+
+/// Remove a field from a task; nothing is written when it isn't set.
+pub fn unset(task_id_str: String, key: String, workspace: Option<String>) -> Result<Changed> {
+    let key = field_key(&key)?;
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+    let (list, mut task) = find_task(&repo, &workspace_name, &task_id_str)?;
+
+    if task.fields.remove(&key).is_none() {
+        output::info(&format!("Task \"{}\" has no field \"{}\"", task.title, key));
+        return Ok(None);
+    }
+    task.updated_at = Utc::now();
+    repo.update_task(list.id, task.clone())
+        .context("Failed to update task")?;
+    remember_task_list(&workspace_name, list.id)?;
+
+    output::success(&format!("Removed {} from \"{}\"", key, task.title));
+    Ok(Some(workspace_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_key_is_trimmed_and_checked() {
+        assert_eq!(field_key("  issue ").unwrap(), "issue");
+        assert_eq!(field_key("GitHub issue").unwrap(), "GitHub issue");
+        assert!(field_key("   ").is_err());
+        assert!(field_key("bad\nkey").is_err());
+    }
+}
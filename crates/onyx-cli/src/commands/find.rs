@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::io::Write;
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use colored::*;
+use onyx_core::{QuerySort, TaskQuery, TaskStatus};
+use onyx_core::config::DateOnlyDue;
+use onyx_core::models::Priority;
+use crate::output;
+use crate::commands::{display_timezone, get_repository};
+use crate::commands::task::parse_due_date;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatusFilter {
+    Open,
+    Done,
+}
+
+impl From<StatusFilter> for TaskStatus {
+    fn from(status: StatusFilter) -> Self {
+        match status {
+            StatusFilter::Open => TaskStatus::Backlog,
+            StatusFilter::Done => TaskStatus::Completed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortBy {
+    /// List order, then task order within each list
+    List,
+    /// Earliest due first
+    Due,
+    /// Highest priority first
+    Priority,
+    /// Oldest first
+    Created,
+    /// Most recently updated first
+    Updated,
+    /// Alphabetical
+    Title,
+}
+
+impl From<SortBy> for QuerySort {
+    fn from(sort: SortBy) -> Self {
+        match sort {
+            SortBy::List => QuerySort::ListOrder,
+            SortBy::Due => QuerySort::Due,
+            SortBy::Priority => QuerySort::Priority,
+            SortBy::Created => QuerySort::Created,
+            SortBy::Updated => QuerySort::Updated,
+            SortBy::Title => QuerySort::Title,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct FindArgs {
+    /// Only open or only completed tasks
+    #[arg(short, long, value_enum)]
+    status: Option<StatusFilter>,
+    /// Tag the task must have (repeatable; all must match)
+    #[arg(short, long = "tag")]
+    tags: Vec<String>,
+    /// Only tasks with this priority (low, medium, high)
+    #[arg(short, long)]
+    priority: Option<Priority>,
+    /// Only tasks assigned to this person
+    #[arg(short, long)]
+    assignee: Option<String>,
+    /// Only tasks due before this date (YYYY-MM-DD excludes that day) or datetime
+    #[arg(long)]
+    due_before: Option<String>,
+    /// Only tasks due after this date (YYYY-MM-DD excludes that day) or datetime
+    #[arg(long)]
+    due_after: Option<String>,
+    /// Text to look for in the title or description
+    #[arg(short = 'q', long)]
+    text: Option<String>,
+    /// Only search this list (name or @index)
+    #[arg(short, long)]
+    list: Option<String>,
+    /// How to order the results
+    #[arg(long, value_enum, default_value_t = SortBy::List)]
+    sort: SortBy,
+    /// Only print the number of matching tasks
+    #[arg(long)]
+    count: bool,
+    /// Only print the IDs of matching tasks, one per line (for `onyx delete -` and the like)
+    #[arg(long, conflicts_with = "count")]
+    ids: bool,
+    /// Workspace to use
+    #[arg(short, long)]
+    workspace: Option<String>,
+}
+
+pub fn execute(args: FindArgs) -> Result<()> {
+    let (repo, _workspace_name) = get_repository(args.workspace)?;
+    let tz = display_timezone()?;
+
+    // Date-only bounds exclude the named day: "before" the start of it, "after" the end of it.
+    let due_before = args.due_before
+        .map(|s| parse_due_date(&s, DateOnlyDue::StartOfDay, &tz).map(|(d, _)| d))
+        .transpose()?;
+    let due_after = args.due_after
+        .map(|s| parse_due_date(&s, DateOnlyDue::EndOfDay, &tz).map(|(d, _)| d))
+        .transpose()?;
+    let list_id = args.list
+        .map(|spec| repo.resolve_list(&spec).map(|list| list.id))
+        .transpose()?;
+
+    let query = TaskQuery {
+        status: args.status.map(TaskStatus::from),
+        tags: args.tags,
+        priority: args.priority,
+        assignee: args.assignee,
+        due_before,
+        due_after,
+        text: args.text,
+        list_id,
+        sort: args.sort.into(),
+    };
+
+    let results = repo.query(&query)
+        .context("Failed to search tasks")?;
+    if args.count {
+        println!("{}", results.len());
+        return Ok(());
+    }
+    if args.ids {
+        let mut out = std::io::stdout().lock();
+        for (_, task) in &results {
+            match writeln!(out, "{}", task.id) {
+                Ok(()) => {}
+                // The reading end (`head`, say) has all it wants
+                Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        return Ok(());
+    }
+    let list_titles: HashMap<_, _> = repo.get_lists()
+        .context("Failed to get lists")?
+        .into_iter()
+        .map(|list| (list.id, list.title))
+        .collect();
+
+    output::header(&format!("{} matching task{}", results.len(), if results.len() == 1 { "" } else { "s" }));
+    for (list_id, task) in &results {
+        let checkbox = if task.status == TaskStatus::Completed { "[✓]".green() } else { "[ ]".normal() };
+        let due_str = task.due_date
+            .map(|d| format!(" (due: {})", d.with_timezone(&tz).format("%Y-%m-%d")))
+            .unwrap_or_default();
+        let list_title = list_titles.get(list_id).map(String::as_str).unwrap_or("?");
+        output::item(&format!(
+            "{} {}{} {} {}",
+            checkbox,
+            task.title,
+            due_str,
+            format!("({})", list_title).dimmed(),
+            task.id.to_string().dimmed()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Build the search index that `find --text` reads, or rebuild it from scratch.
+pub fn reindex(workspace: Option<String>) -> Result<()> {
+    let (mut repo, _workspace_name) = get_repository(workspace)?;
+    let count = repo.reindex()
+        .context("Failed to build the search index")?;
+    output::success(&format!("Indexed {} task{}", count, if count == 1 { "" } else { "s" }));
+    Ok(())
+}
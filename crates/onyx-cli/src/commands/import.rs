@@ -0,0 +1,275 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use onyx_core::Task;
+use onyx_core::export::{import_records, read_json, read_ndjson, ExistingList, IdCollision, ImportOptions};
+use onyx_core::storage::parse_task_file;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use crate::output;
+use crate::commands::{get_repository, Changed};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportFormat {
+    /// A folder of markdown files, one task per file
+    MarkdownDir,
+    /// A JSON array written by `onyx export --format json`
+    Json,
+    /// Newline-delimited JSON written by `onyx export --format ndjson`
+    Ndjson,
+}
+
+/// `--on-collision` choices for `--preserve-ids`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OnCollision {
+    /// Abort without importing anything
+    Error,
+    /// Give the clashing task a new ID
+    Rename,
+}
+
+impl From<OnCollision> for IdCollision {
+    fn from(choice: OnCollision) -> Self {
+        match choice {
+            OnCollision::Error => IdCollision::Error,
+            OnCollision::Rename => IdCollision::Rename,
+        }
+    }
+}
+
+/// `--on-existing` choices for lists that are already in the workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OnExisting {
+    /// Add the imported tasks to the list
+    Merge,
+    /// Leave the list alone and skip its tasks
+    Skip,
+    /// Delete the list's tasks, then import
+    Replace,
+}
+
+impl From<OnExisting> for ExistingList {
+    fn from(choice: OnExisting) -> Self {
+        match choice {
+            OnExisting::Merge => ExistingList::Merge,
+            OnExisting::Skip => ExistingList::Skip,
+            OnExisting::Replace => ExistingList::Replace,
+        }
+    }
+}
+
+pub fn execute(
+    format: ImportFormat,
+    source: String,
+    list_name: Option<String>,
+    preserve_ids: bool,
+    on_collision: OnCollision,
+    on_existing: OnExisting,
+    workspace: Option<String>,
+) -> Result<Changed> {
+    match format {
+        ImportFormat::MarkdownDir => {
+            if preserve_ids {
+                anyhow::bail!("--preserve-ids only applies to json and ndjson imports");
+            }
+            if on_existing != OnExisting::Merge {
+                anyhow::bail!("--on-existing only applies to json and ndjson imports");
+            }
+            import_markdown_dir(PathBuf::from(source), list_name, workspace)
+        }
+        ImportFormat::Json | ImportFormat::Ndjson => {
+            import_export_file(format, PathBuf::from(source), list_name, preserve_ids, on_collision, on_existing, workspace)
+        }
+    }
+}
+
+/// Re-import an `onyx export` file. Tasks return to lists with the same ID or title (created
+/// if missing) unless `list_name` sends them all to one list; lists that already exist are
+/// merged into, skipped or emptied first per `on_existing`. Tasks get fresh IDs unless
+/// `preserve_ids` is set.
+fn import_export_file(
+    format: ImportFormat,
+    path: PathBuf,
+    list_name: Option<String>,
+    preserve_ids: bool,
+    on_collision: OnCollision,
+    on_existing: OnExisting,
+    workspace: Option<String>,
+) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+    let options = ImportOptions {
+        into: list_name
+            .map(|name| repo.resolve_list(&name).map(|list| list.id))
+            .transpose()?,
+        preserve_ids,
+        on_collision: on_collision.into(),
+        on_existing: on_existing.into(),
+    };
+
+    let file = File::open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let reader = BufReader::new(file);
+    let report = if format == ImportFormat::Json {
+        let records = read_json(reader).context("Failed to read export")?;
+        import_records(&mut repo, records.into_iter().map(Ok), &options)
+    } else {
+        import_records(&mut repo, read_ndjson(reader), &options)
+    }
+    .with_context(|| format!("Failed to import {}", path.display()))?;
+
+    for list in &report.created_lists {
+        output::item(&format!("Created list \"{}\"", list));
+    }
+    for list in &report.replaced_lists {
+        output::item(&format!("Replaced the tasks of \"{}\"", list));
+    }
+    for list in &report.skipped_lists {
+        output::item(&format!("Skipped \"{}\", which already exists", list));
+    }
+    for (old, new) in &report.reassigned {
+        output::item(&format!("ID {} already in use, imported as {}", old, new));
+    }
+    for title in &report.renamed {
+        output::item(&format!("Title already taken, imported as \"{}\"", title));
+    }
+    output::success(&format!("Imported {} tasks from {}", report.imported.len(), path.display()));
+
+    Ok(Some(workspace_name))
+}
+
+fn import_markdown_dir(dir: PathBuf, list_name: Option<String>, workspace: Option<String>) -> Result<Changed> {
+    if !dir.is_dir() {
+        anyhow::bail!("'{}' is not a directory", dir.display());
+    }
+
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+
+    let mut lists = repo.get_lists()
+        .context("Failed to get lists")?;
+
+    if lists.is_empty() {
+        anyhow::bail!("No lists found. Create one with 'onyx list create <name>'");
+    }
+
+    let list = if let Some(name) = list_name {
+        repo.resolve_list(&name)?
+    } else {
+        lists.remove(0)
+    };
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(&dir)?
+        .collect::<std::result::Result<Vec<_>, _>>()?
+        .into_iter()
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .collect();
+    paths.sort();
+
+    let mut imported = 0;
+    let mut skipped = 0;
+
+    for path in paths {
+        if path.extension().and_then(|s| s.to_str()) != Some("md") {
+            skipped += 1;
+            continue;
+        }
+
+        let task = match read_markdown_task(&path) {
+            Ok(task) => task,
+            Err(e) => {
+                output::warning(&format!("Skipping {}: {}", path.display(), e));
+                skipped += 1;
+                continue;
+            }
+        };
+
+        repo.create_task(list.id, task.clone())
+            .context(format!("Failed to import {}", path.display()))?;
+        output::item(&format!("Imported \"{}\"", task.title));
+        imported += 1;
+    }
+
+    output::success(&format!("Imported {} tasks into \"{}\"", imported, list.title));
+    if skipped > 0 {
+        output::info(&format!("Skipped {} files", skipped));
+    }
+
+    Ok((imported > 0).then_some(workspace_name))
+}
+
+fn read_markdown_task(path: &Path) -> Result<Task> {
+    let stem = path.file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| anyhow::anyhow!("Invalid filename"))?;
+    let content = std::fs::read_to_string(path)?;
+    task_from_markdown(stem, &content)
+}
+
+/// Build a task from a markdown file. Files with Onyx frontmatter (YAML or TOML) keep their status and
+/// dates; loose notes use the first `# ` heading (or the file name) as the title and the
+/// rest of the file as the description. Imported tasks always get a fresh ID.
+fn task_from_markdown(file_stem: &str, content: &str) -> Result<Task> {
+    let has_frontmatter = ["---", "+++"].iter()
+        .any(|fence| content.starts_with(&format!("{fence}\n")) || content.starts_with(&format!("{fence}\r\n")));
+    if has_frontmatter {
+        let mut task = parse_task_file(content, file_stem)?;
+        task.id = uuid::Uuid::new_v4();
+        task.parent_id = None;
+        return Ok(task);
+    }
+
+    let mut lines = content.lines().peekable();
+    while lines.peek().is_some_and(|l| l.trim().is_empty()) {
+        lines.next();
+    }
+
+    let (title, body) = match lines.peek() {
+        Some(first) if first.starts_with("# ") => {
+            let title = first.trim_start_matches("# ").trim().to_string();
+            lines.next();
+            (title, lines.collect::<Vec<_>>().join("\n"))
+        }
+        _ => (file_stem.to_string(), lines.collect::<Vec<_>>().join("\n")),
+    };
+
+    if title.is_empty() {
+        anyhow::bail!("Could not determine a title");
+    }
+
+    Ok(Task::new(title).with_description(body.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use onyx_core::TaskStatus;
+
+    #[test]
+    fn test_title_from_heading() {
+        let task = task_from_markdown("note", "# Buy milk\n\nTwo litres").unwrap();
+        assert_eq!(task.title, "Buy milk");
+        assert_eq!(task.description, "Two litres");
+    }
+
+    #[test]
+    fn test_title_from_filename() {
+        let task = task_from_markdown("Call mum", "Sunday afternoon\n- ask about trip").unwrap();
+        assert_eq!(task.title, "Call mum");
+        assert_eq!(task.description, "Sunday afternoon\n- ask about trip");
+    }
+
+    #[test]
+    fn test_frontmatter_file_keeps_status_with_new_id() {
+        let content = "---\nid: 550e8400-e29b-41d4-a716-446655440000\nstatus: completed\ncreated: 2026-01-01T00:00:00Z\nupdated: 2026-01-01T00:00:00Z\n---\n\nDone already";
+        let task = task_from_markdown("Old task", content).unwrap();
+        assert_eq!(task.title, "Old task");
+        assert_eq!(task.status, TaskStatus::Completed);
+        assert_eq!(task.description, "Done already");
+        assert_ne!(task.id.to_string(), "550e8400-e29b-41d4-a716-446655440000");
+    }
+
+    #[test]
+    fn test_invalid_frontmatter_is_error() {
+        assert!(task_from_markdown("Broken", "---\nstatus: nope\n").is_err());
+    }
+}
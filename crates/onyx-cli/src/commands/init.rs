@@ -1,43 +1,67 @@
 use anyhow::{Context, Result};
-use onyx_core::{AppConfig, TaskRepository, WorkspaceConfig};
-use std::path::PathBuf;
+use onyx_core::{AppConfig, WorkspaceConfig};
+use crate::commands::{check_path_unclaimed, init_workspace_folder, sync, with_workspace_path};
 use crate::output;
 
-pub fn execute(path: String, name: String) -> Result<()> {
-    let path_buf = PathBuf::from(path);
-    let path_buf = if path_buf.is_relative() {
-        std::env::current_dir()?.join(path_buf)
-    } else {
-        path_buf
-    };
-
-    // Initialize the repository
-    let mut repo = TaskRepository::init(path_buf.clone())
-        .context("Failed to initialize tasks folder")?;
-
-    // Create default list if it doesn't exist
-    let lists = repo.get_lists().context("Failed to get lists")?;
-    if !lists.iter().any(|l| l.title == "My Tasks") {
-        repo.create_list("My Tasks".to_string())
-            .context("Failed to create default list")?;
-    }
-
+/// Create a workspace and make it current. With `webdav_url`, also run the sync setup
+/// steps (password prompt, connection test, credential storage) in the same go.
+pub fn execute(
+    path: String,
+    name: String,
+    force: bool,
+    default_list: String,
+    webdav_url: Option<String>,
+    webdav_username: Option<String>,
+) -> Result<()> {
     // Load or create config
     let config_path = AppConfig::get_config_path();
     let mut config = AppConfig::load_from_file(&config_path)
         .unwrap_or_else(|_| AppConfig::new());
 
-    // Add workspace
-    config.add_workspace(name.clone(), WorkspaceConfig::new(path_buf.clone()));
-    config.set_current_workspace(name.clone())?;
+    let (path_buf, setup, sync_requested, sync_ready) = with_workspace_path(path, |path_buf| {
+        check_path_unclaimed(&config, &path_buf, &name, force)?;
+
+        // Initialize the repository, or adopt the workspace already there
+        let setup = init_workspace_folder(&path_buf, &default_list)?;
+
+        // Set up sync if asked; a failed connection test still leaves a local workspace
+        let mut workspace = WorkspaceConfig::new(path_buf.clone());
+        let sync_requested = webdav_url.is_some();
+        let sync_ready = match webdav_url {
+            Some(url) => {
+                let username = match webdav_username {
+                    Some(username) => username,
+                    None => sync::prompt("Username: ")?,
+                };
+                sync::connect(&mut workspace, url, username)?
+            }
+            None => false,
+        };
 
-    // Save config
-    config.save_to_file(&config_path)
-        .context("Failed to save config")?;
+        // Add workspace
+        config.add_workspace(name.clone(), workspace);
+        config.set_current_workspace(name.clone())?;
 
-    output::success(&format!("Initialized workspace \"{}\" at {}", name, path_buf.display()));
-    output::success("Created default list \"My Tasks\"");
+        // Save config
+        config.save_to_file(&config_path)
+            .context("Failed to save config")?;
+        Ok((path_buf, setup, sync_requested, sync_ready))
+    })?;
+
+    if setup.adopted {
+        output::success(&format!("Adopted existing workspace at {} as \"{}\"", path_buf.display(), name));
+    } else {
+        output::success(&format!("Initialized workspace \"{}\" at {}", name, path_buf.display()));
+    }
+    if setup.created_default_list {
+        output::success(&format!("Created default list \"{}\"", default_list));
+    }
     output::success(&format!("Set \"{}\" as current workspace", name));
+    if sync_ready {
+        output::success("Sync set up. Run 'onyx sync' to sync.");
+    } else if sync_requested {
+        output::info("Sync was not set up. Run 'onyx sync --setup' to try again.");
+    }
 
     Ok(())
 }
@@ -1,56 +1,276 @@
+use std::collections::HashSet;
 use anyhow::{Context, Result};
 use colored::*;
-use onyx_core::{Task, TaskStatus};
+use chrono::{DateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use onyx_core::{QuerySort, Task, TaskList, TaskQuery, TaskRepository, TaskStatus};
+use onyx_core::config::{CompletedVisibility, DateOnlyDue};
+use onyx_core::models::DueBucket;
+use uuid::Uuid;
 use crate::output;
-use crate::commands::get_repository;
+use crate::commands::{display_timezone, get_repository, workspace_settings, Changed};
+use crate::commands::find::StatusFilter;
+use crate::commands::task::parse_due_date;
 
-fn print_tasks(tasks: &[Task]) {
+/// How dates are rendered: absolute in the display timezone, or relative to now.
+struct DateDisplay {
+    format: String,
+    tz: Tz,
+    relative: bool,
+}
+
+impl DateDisplay {
+    fn from_settings(repo: &TaskRepository, relative_flag: bool) -> Result<Self> {
+        let settings = workspace_settings(repo)?;
+        let format = settings.date_format.unwrap_or_else(|| "%Y-%m-%d".to_string());
+        if chrono::format::StrftimeItems::new(&format).parse().is_err() {
+            anyhow::bail!("Invalid date_format in workspace settings: {}", format);
+        }
+        Ok(Self {
+            format,
+            tz: display_timezone()?,
+            relative: relative_flag || settings.relative_dates.unwrap_or(false),
+        })
+    }
+
+    fn render(&self, date: DateTime<Utc>) -> String {
+        if self.relative {
+            output::humanize_relative(date, Utc::now())
+        } else {
+            date.with_timezone(&self.tz).format(&self.format).to_string()
+        }
+    }
+
+    /// Like `render`, with the time of day added for tasks due at a specific time.
+    fn render_due(&self, task: &Task) -> Option<String> {
+        let due = task.due_date?;
+        Some(if self.relative {
+            output::humanize_relative(due, Utc::now())
+        } else {
+            output::format_due(due, task.has_time, &self.format, &self.tz)
+        })
+    }
+}
+
+/// How pressing a task's due date is, used to color it in listings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Urgency {
+    Overdue,
+    DueToday,
+    Normal,
+}
+
+/// Classify a task at `now`, judging "today" by calendar day in `tz`.
+fn urgency<Z: TimeZone>(task: &Task, now: DateTime<Utc>, tz: &Z) -> Urgency {
+    match task.due_bucket(now, tz) {
+        DueBucket::Overdue => Urgency::Overdue,
+        DueBucket::Today => Urgency::DueToday,
+        _ => Urgency::Normal,
+    }
+}
+
+/// Print tasks as checkbox lines; those in `blocked` (see `TaskRepository::blocked_tasks`)
+/// are marked as such.
+fn print_tasks(tasks: &[Task], dates: &DateDisplay, blocked: &HashSet<Uuid>) {
     if tasks.is_empty() {
         output::item("No tasks");
         return;
     }
+    let now = Utc::now();
+    // Labels get a column as wide as the widest one, so titles stay aligned
+    let label_width = tasks.iter()
+        .filter_map(|t| t.label.as_deref())
+        .map(output::display_width)
+        .max();
     for task in tasks {
         let checkbox = if task.status == TaskStatus::Completed { "[✓]".green() } else { "[ ]".normal() };
-        let due_str = task.due_date.map(|d| format!(" (due: {})", d.format("%Y-%m-%d")).yellow().to_string()).unwrap_or_default();
-        output::item(&format!("{} {}{} {}", checkbox, task.title, due_str, task.id.to_string().dimmed()));
+        let label = label_width
+            .map(|width| format!("{} ", output::pad_to_width(task.label.as_deref().unwrap_or(""), width)))
+            .unwrap_or_default();
+        let due_str = dates.render_due(task)
+            .map(|due| format!(" (due: {})", due))
+            .unwrap_or_default();
+        let text = format!("{}{}", task.title, due_str);
+        let text = match urgency(task, now, &dates.tz) {
+            Urgency::Overdue => text.red(),
+            Urgency::DueToday => text.yellow(),
+            Urgency::Normal => text.normal(),
+        };
+        let blocked_str = if blocked.contains(&task.id) { format!(" {}", "(blocked)".dimmed()) } else { String::new() };
+        let assignee_str = task.assignee.as_ref()
+            .map(|a| format!(" @{}", a).cyan().to_string())
+            .unwrap_or_default();
+        output::item(&format!("{} {}{}{}{} {}", checkbox, label, text, blocked_str, assignee_str, task.id.to_string().dimmed()));
     }
 }
 
-pub fn create(name: String, workspace: Option<String>) -> Result<()> {
-    let (mut repo, _workspace_name) = get_repository(workspace)?;
+/// Print a list's tasks, split into day buckets (in the display timezone) when grouping by due date is on.
+fn print_list_tasks(list: &TaskList, dates: &DateDisplay, blocked: &HashSet<Uuid>) {
+    if !list.group_by_due_date || list.tasks.is_empty() {
+        print_tasks(&list.tasks, dates, blocked);
+        return;
+    }
+    let now = Utc::now();
+    let mut buckets: Vec<(DueBucket, Vec<Task>)> = Vec::new();
+    for task in &list.tasks {
+        let bucket = task.due_bucket(now, &dates.tz);
+        match buckets.iter_mut().find(|(b, _)| *b == bucket) {
+            Some((_, tasks)) => tasks.push(task.clone()),
+            None => buckets.push((bucket, vec![task.clone()])),
+        }
+    }
+    buckets.sort_by_key(|(bucket, _)| *bucket);
+    for (bucket, tasks) in buckets {
+        output::item(&bucket.to_string().bold().to_string());
+        print_tasks(&tasks, dates, blocked);
+    }
+}
+
+/// Print a list's title, indented under its parent when it is a sublist, with its
+/// nearest open deadline if it has one.
+fn print_list_header(list: &TaskList, dates: &DateDisplay) {
+    let next_due = list.next_due
+        .map(|due| format!(" {}", format!("next due: {}", dates.render(due)).dimmed()))
+        .unwrap_or_default();
+    output::header(&format!(
+        "{}{} ({}) {}{}",
+        "  ".repeat(list.depth),
+        list.title,
+        format!("{} tasks", list.tasks.len()).dimmed(),
+        output::render_progress_bar(list.completed_count(), list.tasks.len(), 10),
+        next_due
+    ));
+}
+
+pub fn create(name: String, parent: Option<String>, allow_duplicate: bool, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+    repo.allow_duplicate_list_names(allow_duplicate);
+
+    if let Some(parent) = parent {
+        let parent = repo.resolve_list(&parent)?;
+        repo.create_sublist(parent.id, name.clone())
+            .context("Failed to create list")?;
+        output::success(&format!("Created list \"{}\" in \"{}\"", name, parent.title));
+        return Ok(Some(workspace_name));
+    }
 
     repo.create_list(name.clone())
         .context("Failed to create list")?;
 
     output::success(&format!("Created list \"{}\"", name));
 
-    Ok(())
+    Ok(Some(workspace_name))
 }
 
-pub fn show(list_name: Option<String>, workspace: Option<String>) -> Result<()> {
-    let (repo, _workspace_name) = get_repository(workspace)?;
+/// What `list show` prints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShowOutput {
+    /// Headers and tasks, with due dates relative to now if `relative`.
+    Full { relative: bool },
+    /// One line per shown task, filled in from `template`.
+    Format { template: TaskTemplate, relative: bool },
+    /// Only [`porcelain_line`]s for the shown tasks.
+    Porcelain,
+    /// Only [`count_line`]s, one per list.
+    Count,
+}
+
+/// `visibility` overrides the workspace's `show_completed` setting when given, and
+/// `hide_blocked` leaves out tasks waiting on an open task.
+pub fn show(
+    list_name: Option<String>,
+    assignee: Option<String>,
+    visibility: Option<CompletedVisibility>,
+    sort: QuerySort,
+    mode: ShowOutput,
+    hide_blocked: bool,
+    workspace: Option<String>,
+) -> Result<()> {
+    let (mut repo, _workspace_name) = get_repository(workspace)?;
 
     let lists = repo.get_lists()
         .context("Failed to get lists")?;
 
+    // Only the full view talks about an empty workspace; the script-facing modes print nothing
+    let (relative, quiet) = match &mode {
+        ShowOutput::Full { relative } => (*relative, false),
+        ShowOutput::Format { relative, .. } => (*relative, true),
+        ShowOutput::Porcelain | ShowOutput::Count => (false, true),
+    };
     if lists.is_empty() {
+        if quiet {
+            return Ok(());
+        }
         output::info("No lists found. Create one with 'onyx list create <name>'");
         return Ok(());
     }
 
-    // If a specific list is requested, show only that one
-    if let Some(name) = list_name {
-        let list = lists.iter()
-            .find(|l| l.title == name)
-            .ok_or_else(|| anyhow::anyhow!("List '{}' not found", name))?;
+    let dates = DateDisplay::from_settings(&repo, relative)?;
 
-        output::header(&format!("{} ({})", list.title, format!("{} tasks", list.tasks.len()).dimmed()));
-        print_tasks(&list.tasks);
+    // If a specific list is requested, show only that one and remember it for `add`
+    let mut lists = if let Some(name) = list_name {
+        let list = repo.resolve_list(&name)?;
+        repo.set_last_opened_list(list.id)
+            .context("Failed to update workspace metadata")?;
+        vec![list]
     } else {
-        // Show all lists
-        for list in &lists {
-            output::header(&format!("{} ({})", list.title, format!("{} tasks", list.tasks.len()).dimmed()));
-            print_tasks(&list.tasks);
+        lists
+    };
+
+    let visibility = match visibility {
+        Some(visibility) => visibility,
+        None => workspace_settings(&repo)?.show_completed.unwrap_or_default(),
+    };
+    if let Some(assignee) = &assignee {
+        for list in &mut lists {
+            list.tasks.retain(|t| t.is_assigned_to(assignee));
+        }
+    }
+    // Dependencies can point into any list, so finding what's blocked takes a pass over
+    // the whole workspace; skip it when nothing shown has dependencies
+    let blocked = if lists.iter().flat_map(|l| &l.tasks).any(|t| !t.depends_on.is_empty()) {
+        repo.blocked_tasks().context("Failed to check task dependencies")?
+    } else {
+        HashSet::new()
+    };
+    if hide_blocked {
+        for list in &mut lists {
+            list.tasks.retain(|t| !blocked.contains(&t.id));
+        }
+    }
+
+    match mode {
+        ShowOutput::Porcelain => {
+            for list in &lists {
+                for task in &shown_tasks(list, visibility, sort).tasks {
+                    println!("{}", porcelain_line(task));
+                }
+            }
+            return Ok(());
+        }
+        ShowOutput::Count => {
+            for list in &lists {
+                println!("{}", count_line(list, visibility));
+            }
+            return Ok(());
+        }
+        ShowOutput::Format { template, .. } => {
+            for list in &lists {
+                for task in &shown_tasks(list, visibility, sort).tasks {
+                    println!("{}", template.render(task, &list.title, &dates));
+                }
+            }
+            return Ok(());
+        }
+        ShowOutput::Full { .. } => {}
+    }
+
+    let many = lists.len() > 1;
+    for list in &lists {
+        // The header's counts and progress cover the whole list, even when completed tasks are hidden
+        print_list_header(list, &dates);
+        print_list_tasks(&shown_tasks(list, visibility, sort), &dates, &blocked);
+        if many {
             output::blank();
         }
     }
@@ -58,28 +278,339 @@ pub fn show(list_name: Option<String>, workspace: Option<String>) -> Result<()>
     Ok(())
 }
 
-pub fn delete(name: String, workspace: Option<String>) -> Result<()> {
-    let (mut repo, _workspace_name) = get_repository(workspace)?;
+/// The part of `list` that `show` prints: tasks `visibility` lets through (all of them
+/// for `Show`), in `sort` order.
+fn shown_tasks(list: &TaskList, visibility: CompletedVisibility, sort: QuerySort) -> TaskList {
+    let query = TaskQuery { sort, ..Default::default() };
+    let tasks = list.tasks.iter()
+        .filter(|t| visibility.shows(t.status))
+        .map(|t| (list.id, t.clone()))
+        .collect();
+    TaskList {
+        tasks: query.apply(tasks).into_iter().map(|(_, task)| task).collect(),
+        ..list.clone()
+    }
+}
 
-    let lists = repo.get_lists()
-        .context("Failed to get lists")?;
+/// One list for `list show --count`: how many of its tasks `visibility` lets through, a
+/// tab, then the title. Like [`porcelain_line`], the format must not change.
+fn count_line(list: &TaskList, visibility: CompletedVisibility) -> String {
+    let shown = list.tasks.iter().filter(|t| visibility.shows(t.status)).count();
+    format!("{}\t{}", shown, list.title.replace(['\t', '\n', '\r'], " "))
+}
 
-    let list = lists.iter()
-        .find(|l| l.title == name)
-        .ok_or_else(|| anyhow::anyhow!("List '{}' not found", name))?;
+/// One task for `list show --porcelain`: `id<TAB>status<TAB>due<TAB>title`. Scripts rely
+/// on this, so the columns and their formats must never change: status is `backlog` or
+/// `completed`, due is RFC 3339 in UTC (`2026-06-01T21:59:59Z`) or empty, and tabs or
+/// line breaks in the title become spaces so every task stays on one line.
+fn porcelain_line(task: &Task) -> String {
+    let status = match task.status {
+        TaskStatus::Backlog => "backlog",
+        TaskStatus::Completed => "completed",
+    };
+    let due = task.due_date
+        .map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_default();
+    let title = task.title.replace(['\t', '\n', '\r'], " ");
+    format!("{}\t{}\t{}\t{}", task.id, status, due, title)
+}
 
-    // Confirm
-    output::warning(&format!("This will delete list \"{}\" and all its tasks", name));
-    print!("Continue? (y/n): ");
-    use std::io::{self, Write};
-    io::stdout().flush()?;
+/// A value a [`TaskTemplate`] can fill in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TemplateField {
+    Id,
+    Id8,
+    Title,
+    Status,
+    Due,
+    List,
+}
+
+/// Token names accepted in a [`TaskTemplate`], in the order errors list them.
+const TEMPLATE_FIELDS: [(&str, TemplateField); 6] = [
+    ("id", TemplateField::Id),
+    ("id8", TemplateField::Id8),
+    ("title", TemplateField::Title),
+    ("status", TemplateField::Status),
+    ("due", TemplateField::Due),
+    ("list", TemplateField::List),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplatePart {
+    Text(String),
+    Field(TemplateField),
+}
+
+/// A `list show --format` template such as `"{id8} {status} {due} {title}"`. It is
+/// checked when parsed, so a mistyped token fails before anything is printed; `{{` and
+/// `}}` stand for literal braces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskTemplate {
+    parts: Vec<TemplatePart>,
+}
+
+impl TaskTemplate {
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => anyhow::bail!("Unclosed '{{{}' in format template", name),
+                        }
+                    }
+                    let field = TEMPLATE_FIELDS.iter()
+                        .find(|(token, _)| *token == name)
+                        .map(|(_, field)| *field)
+                        .ok_or_else(|| {
+                            let valid: Vec<String> = TEMPLATE_FIELDS.iter().map(|(token, _)| format!("{{{}}}", token)).collect();
+                            anyhow::anyhow!("Unknown token '{{{}}}' in format template. Valid tokens: {}", name, valid.join(", "))
+                        })?;
+                    if !text.is_empty() {
+                        parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(TemplatePart::Field(field));
+                }
+                '}' => anyhow::bail!("Unmatched '}}' in format template; write '}}}}' for a literal brace"),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(TemplatePart::Text(text));
+        }
+        Ok(Self { parts })
+    }
+
+    /// Fill the template in for `task` from the list titled `list`. Status is `backlog` or
+    /// `completed` as in [`porcelain_line`]; a task with no due date gets an empty `{due}`.
+    fn render(&self, task: &Task, list: &str, dates: &DateDisplay) -> String {
+        let mut line = String::new();
+        for part in &self.parts {
+            match part {
+                TemplatePart::Text(text) => line.push_str(text),
+                TemplatePart::Field(field) => line.push_str(&match field {
+                    TemplateField::Id => task.id.to_string(),
+                    TemplateField::Id8 => task.id.to_string()[..8].to_string(),
+                    TemplateField::Title => task.title.clone(),
+                    TemplateField::Status => match task.status {
+                        TaskStatus::Backlog => "backlog".to_string(),
+                        TaskStatus::Completed => "completed".to_string(),
+                    },
+                    TemplateField::Due => dates.render_due(task).unwrap_or_default(),
+                    TemplateField::List => list.to_string(),
+                }),
+            }
+        }
+        line
+    }
+}
+
+pub fn info(name: String, relative: bool, workspace: Option<String>) -> Result<()> {
+    let (repo, _workspace_name) = get_repository(workspace)?;
+    let dates = DateDisplay::from_settings(&repo, relative)?;
+
+    let list = repo.find_list_by_name(&name)?;
 
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
+    output::header(&list.title);
+    output::detail("ID", &list.id.to_string());
+    output::detail("Tasks", &list.tasks.len().to_string());
+    output::detail("Open", &list.open_count().to_string());
+    output::detail("Completed", &list.completed_count().to_string());
+    output::detail("Progress", &output::render_progress_bar(list.completed_count(), list.tasks.len(), 20));
+    output::detail("Next due", &list.next_due.map_or_else(|| "none".to_string(), |due| dates.render(due)));
+    let timestamp = |date: DateTime<Utc>| if dates.relative {
+        dates.render(date)
+    } else {
+        date.with_timezone(&dates.tz).format("%Y-%m-%d %H:%M:%S %Z").to_string()
+    };
+    output::detail("Created", &timestamp(list.created_at));
+    output::detail("Updated", &timestamp(list.updated_at));
+    output::detail("Group by due date", if list.group_by_due_date { "yes" } else { "no" });
+
+    Ok(())
+}
+
+/// Merge `source` into `into`; with `dry_run`, only print what the merge would do.
+pub fn merge(source: String, into: String, keep_duplicates: bool, dry_run: bool, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+
+    let source = repo.resolve_list(&source)?;
+    let dest = repo.resolve_list(&into)?;
+
+    if dry_run {
+        let preview = repo.preview_merge(source.id, dest.id, !keep_duplicates)
+            .context("Failed to preview merge")?;
+        output::header(&format!(
+            "Would move {} task{} from \"{}\" into \"{}\"",
+            preview.moved.len(), if preview.moved.len() == 1 { "" } else { "s" }, source.title, dest.title
+        ));
+        let title_of = |id: &uuid::Uuid| source.tasks.iter().find(|t| t.id == *id).map_or("", |t| t.title.as_str());
+        for (id, title) in &preview.renamed {
+            output::item(&format!("\"{}\" would be renamed to \"{}\" to avoid a clash", title_of(id), title));
+        }
+        for id in &preview.skipped {
+            output::item(&format!("\"{}\" is a copy of a task in \"{}\" and would be dropped", title_of(id), dest.title));
+        }
+        if preview.deletes_source {
+            output::item(&format!("\"{}\" would then be deleted", source.title));
+        }
+        output::info("Dry run: nothing was changed.");
+        return Ok(None);
+    }
+
+    let report = repo.merge_lists(source.id, dest.id, !keep_duplicates)
+        .context("Failed to merge lists")?;
+
+    for (_, title) in &report.renamed {
+        output::item(&format!("Renamed to \"{}\" to avoid a clash", title));
+    }
+    if !report.skipped.is_empty() {
+        output::warning(&format!(
+            "Dropped {} task{} identical to one in \"{}\" (use --keep-duplicates to keep them)",
+            report.skipped.len(), if report.skipped.len() == 1 { "" } else { "s" }, dest.title
+        ));
+    }
+    output::success(&format!(
+        "Merged {} task{} from \"{}\" into \"{}\"",
+        report.moved.len(), if report.moved.len() == 1 { "" } else { "s" }, source.title, dest.title
+    ));
+
+    Ok(Some(workspace_name))
+}
+
+/// Mark every open task in list `name` completed, or with `dry_run` only list them.
+pub fn complete(name: String, dry_run: bool, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+    let list = repo.resolve_list(&name)?;
+    let plural = |n: usize| if n == 1 { "" } else { "s" };
 
-    if input.trim().to_lowercase() != "y" {
+    if dry_run {
+        let open: Vec<&Task> = list.tasks.iter().filter(|t| t.status != TaskStatus::Completed).collect();
+        output::header(&format!("Would complete {} task{} in \"{}\"", open.len(), plural(open.len()), list.title));
+        for task in open {
+            output::item(&task.title);
+        }
+        output::info("Dry run: nothing was changed.");
+        return Ok(None);
+    }
+
+    let completed = repo.complete_all(list.id)
+        .context("Failed to complete tasks")?;
+    if completed.is_empty() {
+        output::info(&format!("\"{}\" has no open tasks", list.title));
+        return Ok(None);
+    }
+    output::success(&format!("Completed {} task{} in \"{}\"", completed.len(), plural(completed.len()), list.title));
+    Ok(Some(workspace_name))
+}
+
+/// Set every completed task in list `name` back to open, or with `dry_run` only list them.
+pub fn reopen(name: String, dry_run: bool, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+    let list = repo.resolve_list(&name)?;
+    let plural = |n: usize| if n == 1 { "" } else { "s" };
+
+    if dry_run {
+        let done: Vec<&Task> = list.tasks.iter().filter(|t| t.status == TaskStatus::Completed).collect();
+        output::header(&format!("Would reopen {} task{} in \"{}\"", done.len(), plural(done.len()), list.title));
+        for task in done {
+            output::item(&task.title);
+        }
+        output::info("Dry run: nothing was changed.");
+        return Ok(None);
+    }
+
+    let reopened = repo.reopen_all(list.id)
+        .context("Failed to reopen tasks")?;
+    if reopened.is_empty() {
+        output::info(&format!("\"{}\" has no completed tasks", list.title));
+        return Ok(None);
+    }
+    output::success(&format!("Reopened {} task{} in \"{}\"", reopened.len(), plural(reopened.len()), list.title));
+    Ok(Some(workspace_name))
+}
+
+/// Move the tasks of `source` matching the given filters into a new list `into`.
+pub fn split(
+    source: String,
+    into: String,
+    status: Option<StatusFilter>,
+    tags: Vec<String>,
+    due_before: Option<String>,
+    workspace: Option<String>,
+) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+    let tz = display_timezone()?;
+
+    let source = repo.resolve_list(&source)?;
+    // As for `find`, a date-only bound excludes the named day
+    let due_before = due_before
+        .map(|s| parse_due_date(&s, DateOnlyDue::StartOfDay, &tz).map(|(d, _)| d))
+        .transpose()?;
+    let query = TaskQuery {
+        status: status.map(TaskStatus::from),
+        tags,
+        due_before,
+        ..Default::default()
+    };
+
+    let report = repo.split_list(source.id, into, &query)
+        .context("Failed to split list")?;
+
+    output::success(&format!(
+        "Moved {} task{} from \"{}\" into the new list \"{}\"",
+        report.moved.len(), if report.moved.len() == 1 { "" } else { "s" }, source.title, report.list.title
+    ));
+
+    Ok(Some(workspace_name))
+}
+
+/// Designate `name` as the workspace's inbox list, or clear the inbox when `name` is `None`.
+pub fn set_inbox(name: Option<String>, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+
+    let Some(name) = name else {
+        repo.set_inbox_list(None)
+            .context("Failed to update workspace metadata")?;
+        output::success("Cleared the inbox list");
+        return Ok(Some(workspace_name));
+    };
+    let list = repo.resolve_list(&name)?;
+    repo.set_inbox_list(Some(list.id))
+        .context("Failed to update workspace metadata")?;
+
+    output::success(&format!("\"{}\" is now the inbox; 'onyx inbox <title>' adds there", list.title));
+
+    Ok(Some(workspace_name))
+}
+
+/// Delete a list after a y/n prompt. `--yes` alone does not answer it, since the list's
+/// tasks and sublists go too; pass `force` to skip it.
+pub fn delete(name: String, force: bool, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+
+    let list = repo.find_list_by_name(&name)?;
+
+    // Confirm
+    output::warning(&format!("This will delete list \"{}\" with all its tasks and sublists", name));
+    if !force && !output::ask("Continue?")? {
         output::info("Cancelled");
-        return Ok(());
+        return Ok(None);
     }
 
     repo.delete_list(list.id)
@@ -87,5 +618,118 @@ pub fn delete(name: String, workspace: Option<String>) -> Result<()> {
 
     output::success(&format!("Deleted list \"{}\"", name));
 
-    Ok(())
+    Ok(Some(workspace_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    #[test]
+    fn test_urgency_with_fixed_now() {
+        let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+        // 10:00 local on June 1
+        let now = tz.with_ymd_and_hms(2026, 6, 1, 10, 0, 0).unwrap().with_timezone(&Utc);
+        let due_at = |d, h| Task::builder("T")
+            .due_time(tz.with_ymd_and_hms(2026, 6, d, h, 0, 0).unwrap().with_timezone(&Utc))
+            .build();
+
+        assert_eq!(urgency(&due_at(1, 9), now, &tz), Urgency::Overdue);
+        assert_eq!(urgency(&due_at(1, 18), now, &tz), Urgency::DueToday);
+        assert_eq!(urgency(&due_at(2, 0), now, &tz), Urgency::Normal);
+        assert_eq!(urgency(&Task::new("No due".into()), now, &tz), Urgency::Normal);
+    }
+
+    #[test]
+    fn test_shown_tasks_all_statuses_with_sort() {
+        let mut list = TaskList::new("Work".into());
+        list.tasks = vec![
+            Task::builder("b open").build(),
+            Task::builder("a done").status(TaskStatus::Completed).build(),
+            Task::builder("c done").status(TaskStatus::Completed).build(),
+        ];
+        let titles = |visibility, sort| -> Vec<String> {
+            shown_tasks(&list, visibility, sort).tasks.into_iter().map(|t| t.title).collect()
+        };
+
+        assert_eq!(titles(CompletedVisibility::Show, QuerySort::ListOrder), vec!["b open", "a done", "c done"]);
+        assert_eq!(titles(CompletedVisibility::Show, QuerySort::Title), vec!["a done", "b open", "c done"]);
+        assert_eq!(titles(CompletedVisibility::Hide, QuerySort::Title), vec!["b open"]);
+        assert_eq!(titles(CompletedVisibility::Only, QuerySort::Title), vec!["a done", "c done"]);
+        assert_eq!(shown_tasks(&list, CompletedVisibility::Only, QuerySort::ListOrder).id, list.id);
+    }
+
+    #[test]
+    fn test_porcelain_line_columns() {
+        let due = Utc.with_ymd_and_hms(2026, 6, 1, 21, 59, 59).unwrap();
+        let mut task = Task::builder("Pay\trent").due_time(due).status(TaskStatus::Completed).build();
+        assert_eq!(porcelain_line(&task), format!("{}\tcompleted\t2026-06-01T21:59:59Z\tPay rent", task.id));
+
+        task.due_date = None;
+        task.status = TaskStatus::Backlog;
+        assert_eq!(porcelain_line(&task), format!("{}\tbacklog\t\tPay rent", task.id));
+    }
+
+    #[test]
+    fn test_format_template_fills_in_tokens() {
+        let dates = DateDisplay { format: "%Y-%m-%d".to_string(), tz: chrono_tz::UTC, relative: false };
+        let template = TaskTemplate::parse("{id8} [{status}] {{{due}}} {title} ({list})").unwrap();
+
+        let due = Utc.with_ymd_and_hms(2026, 6, 1, 0, 0, 0).unwrap();
+        let task = Task::builder("Pay rent").due_date(due).build();
+        let id = task.id.to_string();
+        assert_eq!(template.render(&task, "Home", &dates), format!("{} [backlog] {{2026-06-01}} Pay rent (Home)", &id[..8]));
+
+        // A missing due date leaves the token empty rather than failing
+        let task = Task::builder("Call mum").status(TaskStatus::Completed).build();
+        let id = task.id.to_string();
+        assert_eq!(template.render(&task, "Home", &dates), format!("{} [completed] {{}} Call mum (Home)", &id[..8]));
+        assert_eq!(TaskTemplate::parse("{id}").unwrap().render(&task, "Home", &dates), id);
+    }
+
+    #[test]
+    fn test_format_template_rejects_bad_tokens() {
+        let err = TaskTemplate::parse("{id8} {name}").unwrap_err().to_string();
+        assert!(err.contains("'{name}'"), "{}", err);
+        assert!(err.contains("{id}, {id8}, {title}, {status}, {due}, {list}"), "{}", err);
+        assert!(TaskTemplate::parse("{title").is_err());
+        assert!(TaskTemplate::parse("title}").is_err());
+        assert_eq!(TaskTemplate::parse("plain").unwrap().parts, vec![TemplatePart::Text("plain".to_string())]);
+    }
+
+    #[test]
+    fn test_count_line_is_bare_count_and_title() {
+        let mut list = TaskList::new("Groceries".to_string());
+        list.tasks = vec![
+            Task::new("Milk".into()),
+            Task::new("Eggs".into()),
+            Task::builder("Bread").status(TaskStatus::Completed).build(),
+        ];
+        assert_eq!(count_line(&list, CompletedVisibility::Show), "3\tGroceries");
+        assert_eq!(count_line(&list, CompletedVisibility::Hide), "2\tGroceries");
+        assert_eq!(count_line(&list, CompletedVisibility::Only), "1\tGroceries");
+
+        let count = count_line(&list, CompletedVisibility::Hide);
+        let (number, _) = count.split_once('\t').unwrap();
+        assert_eq!(number.parse::<usize>().unwrap(), 2);
+
+        assert_eq!(count_line(&TaskList::new("Empty\tlist".to_string()), CompletedVisibility::Show), "0\tEmpty list");
+    }
+
+    #[test]
+    fn test_urgency_completed_tasks_are_not_overdue() {
+        let now = Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap();
+        let done = Task::builder("Done")
+            .due_time(now - chrono::Duration::days(3))
+            .status(TaskStatus::Completed)
+            .build();
+        assert_eq!(urgency(&done, now, &Utc), Urgency::Normal);
+
+        let done_today = Task::builder("Done today")
+            .due_time(now + chrono::Duration::hours(2))
+            .status(TaskStatus::Completed)
+            .build();
+        assert_eq!(urgency(&done_today, now, &Utc), Urgency::DueToday);
+    }
 }
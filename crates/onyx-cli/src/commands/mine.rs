@@ -0,0 +1,41 @@
+use anyhow::{Context, Result};
+use colored::*;
+use onyx_core::TaskStatus;
+use crate::output;
+use crate::commands::{get_config_path, get_repository, load_config};
+
+pub fn execute(unassigned: bool, workspace: Option<String>) -> Result<()> {
+    let Some(my_name) = load_config()?.my_name.filter(|n| !n.trim().is_empty()) else {
+        output::info(&format!(
+            "No identity configured. Add \"my_name\": \"<your name>\" to {}",
+            get_config_path().display()
+        ));
+        return Ok(());
+    };
+
+    let (repo, _workspace_name) = get_repository(workspace)?;
+
+    let tasks = repo.assigned_tasks(&my_name, unassigned)
+        .context("Failed to get tasks")?;
+
+    output::header(&format!("Assigned to {}", my_name));
+    if tasks.is_empty() {
+        output::item("No tasks");
+        return Ok(());
+    }
+
+    for (list_title, task) in &tasks {
+        let checkbox = if task.status == TaskStatus::Completed { "[✓]".green() } else { "[ ]".normal() };
+        let unassigned_flag = if task.assignee.is_none() { " (unassigned)".yellow().to_string() } else { String::new() };
+        output::item(&format!(
+            "{} {}{} {} {}",
+            checkbox,
+            task.title,
+            unassigned_flag,
+            format!("({})", list_title).dimmed(),
+            task.id.to_string().dimmed()
+        ));
+    }
+
+    Ok(())
+}
@@ -4,10 +4,53 @@ pub mod list;
 pub mod task;
 pub mod group;
 pub mod sync;
+pub mod import;
+pub mod export;
+pub mod backup;
+pub mod doctor;
+pub mod recent;
+pub mod mine;
+pub mod reminder;
+pub mod streak;
+pub mod find;
+pub mod all;
+pub mod upcoming;
+pub mod tags;
+pub mod field;
 
-use onyx_core::{AppConfig, TaskRepository};
+use chrono_tz::Tz;
+use onyx_core::{AppConfig, FolderContents, TaskRepository, WorkspaceConfig, WorkspaceSettings};
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Name of the workspace a command changed tasks, lists or settings in, returned by the
+/// handlers that can change one; `None` when nothing was written (a dry run, a cancelled
+/// prompt, nothing to do). `main` passes it on to [`after_change`].
+pub type Changed = Option<String>;
+
+/// Post-command hook for commands that change a workspace: pushes it to its WebDAV server
+/// when `auto_sync` is on. Never fails the command; a failed push is only warned about.
+/// The push runs before the process exits rather than in the background, since exiting
+/// would cut a background push short and leave the remote half updated.
+pub fn after_change(workspace_name: String) {
+    let result = load_config().and_then(|config| {
+        let (name, workspace) = resolve_workspace(&config, Some(workspace_name))?;
+        auto_sync(&workspace, || sync::push_quietly(&name, &workspace, &config.defaults))
+    });
+    if let Err(e) = result {
+        crate::output::warning(&format!("Auto-sync failed: {:#}", e));
+    }
+}
+
+/// Run `push` if `workspace` has `auto_sync` on and a WebDAV server set up. Returns
+/// whether it ran.
+fn auto_sync(workspace: &WorkspaceConfig, push: impl FnOnce() -> Result<()>) -> Result<bool> {
+    if !workspace.auto_sync || workspace.webdav_url.is_none() {
+        return Ok(false);
+    }
+    push()?;
+    Ok(true)
+}
 
 pub fn get_config_path() -> PathBuf {
     AppConfig::get_config_path()
@@ -23,21 +66,215 @@ pub fn save_config(config: &AppConfig) -> Result<()> {
     config.save_to_file(&path).context("Failed to save config")
 }
 
-pub fn get_repository(workspace_name: Option<String>) -> Result<(TaskRepository, String)> {
-    let config = load_config()?;
+/// Run `setup` on a workspace folder from the command line, as the absolute, canonical
+/// path stored in config, so it keeps working from any directory. The folder is created
+/// if missing, and removed again (with any parents created for it) if `setup` fails, so
+/// a refused command or a mistyped path doesn't leave empty folders behind.
+pub fn with_workspace_path<T>(path: String, setup: impl FnOnce(PathBuf) -> Result<T>) -> Result<T> {
+    let path = PathBuf::from(path);
+    let base = std::env::current_dir()?;
+    let joined = base.join(&path);
+    let created = joined.ancestors().take_while(|p| !p.exists()).last().map(Path::to_path_buf);
+    let result = WorkspaceConfig::canonical_path(&path, &base)
+        .with_context(|| format!("Invalid workspace path '{}'", path.display()))
+        .and_then(setup);
+    if let (Err(_), Some(created)) = (&result, created) {
+        let _ = std::fs::remove_dir_all(created);
+    }
+    result
+}
+
+/// Name of the list a fresh workspace starts with unless `--default-list` says otherwise.
+pub const DEFAULT_LIST_NAME: &str = "My Tasks";
+
+/// What `init_workspace_folder` found and did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FolderSetup {
+    /// The folder already held a workspace, which was adopted.
+    pub adopted: bool,
+    /// The default list was created.
+    pub created_default_list: bool,
+}
 
-    let (name, workspace_config) = if let Some(name) = workspace_name {
-        let workspace_config = config.get_workspace(&name)
-            .ok_or_else(|| anyhow::anyhow!("Workspace '{}' not found", name))?;
-        (name, workspace_config.clone())
+/// Set up the tasks folder for a new workspace entry. An existing workspace there is
+/// adopted as is; a fresh one, or an adopted one without any lists (say, from an init
+/// that was cut short), gets a first list named `default_list` unless a list by that
+/// name is already there. Running it again on the same folder is therefore harmless.
+/// Unrelated files are left alone, with a warning.
+pub fn init_workspace_folder(path: &Path, default_list: &str) -> Result<FolderSetup> {
+    let contents = TaskRepository::inspect_folder(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    if let FolderContents::Unrelated(names) = &contents {
+        let mut shown = names.iter().take(3).cloned().collect::<Vec<_>>().join(", ");
+        if names.len() > 3 {
+            shown.push_str(&format!(" and {} more", names.len() - 3));
+        }
+        crate::output::warning(&format!(
+            "{} already contains other files ({}); they are left alone", path.display(), shown
+        ));
+    }
+    let adopted = contents == FolderContents::Workspace;
+
+    let mut repo = TaskRepository::init(path.to_path_buf()).with_context(|| if adopted {
+        format!("Existing workspace at {} could not be opened", path.display())
     } else {
-        let (name, workspace_config) = config.get_current_workspace()
-            .context("No workspace set. Use 'onyx init' to create one.")?;
-        (name.clone(), workspace_config.clone())
+        "Failed to initialize tasks folder".to_string()
+    })?;
+
+    let lists = repo.get_lists().context("Failed to get lists")?;
+    let wanted = (!adopted || lists.is_empty()) && !lists.iter().any(|l| l.title == default_list);
+    let created_default_list = wanted && match repo.create_list(default_list.to_string()) {
+        Ok(_) => true,
+        // Something that isn't a list already has the folder; leave it be
+        Err(onyx_core::Error::ListAlreadyExists(_)) => {
+            crate::output::warning(&format!(
+                "Not creating list \"{}\": {} already has a folder by that name", default_list, path.display()
+            ));
+            false
+        }
+        Err(e) => return Err(e).context("Failed to create default list"),
+    };
+    Ok(FolderSetup { adopted, created_default_list })
+}
+
+/// Refuse to point workspace `name` at `path` when another workspace already uses that
+/// folder, unless `force` is set, in which case only warn.
+pub fn check_path_unclaimed(config: &AppConfig, path: &Path, name: &str, force: bool) -> Result<()> {
+    let Some(other) = config.workspace_at_path(path, name) else {
+        return Ok(());
     };
+    let message = format!("Workspace '{}' already uses {}", other, path.display());
+    if !force {
+        anyhow::bail!("{}. Pass --force to register it anyway.", message);
+    }
+    crate::output::warning(&message);
+    Ok(())
+}
+
+/// The workspace to use: `--workspace` if given, else `ONYX_WORKSPACE`, else the current one.
+pub fn resolve_workspace(config: &AppConfig, workspace_name: Option<String>) -> Result<(String, WorkspaceConfig)> {
+    let (name, workspace_config) = config.resolve_workspace(workspace_name.as_deref())
+        .context("No usable workspace. See 'onyx workspace list', or use 'onyx init' to create one.")?;
+    Ok((name, workspace_config.clone()))
+}
 
-    let repo = TaskRepository::new(workspace_config.path.clone())
+pub fn get_repository(workspace_name: Option<String>) -> Result<(TaskRepository, String)> {
+    let config = load_config()?;
+    let (name, workspace_config) = resolve_workspace(&config, workspace_name)?;
+
+    let mut repo = TaskRepository::new(workspace_config.path.clone())
         .context(format!("Failed to open workspace '{}'", name))?;
+    repo.set_settings_defaults(config.defaults);
 
     Ok((repo, name))
 }
+
+/// Workspace settings from `.workspace.json`, merged over the global defaults in config
+/// (which `get_repository` hands the repository).
+pub fn workspace_settings(repo: &TaskRepository) -> Result<WorkspaceSettings> {
+    repo.effective_workspace_settings()
+        .context("Failed to read workspace settings")
+}
+
+/// `on`/`off` argument for switches like `sync auto` and `tags strict`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Toggle {
+    On,
+    Off,
+}
+
+/// Timezone for resolving and displaying due dates (config `timezone` override or system).
+pub fn display_timezone() -> Result<Tz> {
+    load_config()?.resolve_timezone().context("Invalid timezone in config")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(auto_sync: bool, webdav_url: Option<&str>) -> WorkspaceConfig {
+        let mut workspace = WorkspaceConfig::new(PathBuf::from("/tmp/tasks"));
+        workspace.auto_sync = auto_sync;
+        workspace.webdav_url = webdav_url.map(str::to_string);
+        workspace
+    }
+
+    #[test]
+    fn test_init_workspace_folder_creates_named_default_list() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let setup = init_workspace_folder(temp_dir.path(), "Inbox").unwrap();
+        assert_eq!(setup, FolderSetup { adopted: false, created_default_list: true });
+
+        let repo = TaskRepository::new(temp_dir.path().to_path_buf()).unwrap();
+        let titles: Vec<String> = repo.get_lists().unwrap().into_iter().map(|l| l.title).collect();
+        assert_eq!(titles, vec!["Inbox"]);
+    }
+
+    #[test]
+    fn test_init_workspace_folder_twice_keeps_one_default_list() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        init_workspace_folder(temp_dir.path(), DEFAULT_LIST_NAME).unwrap();
+        let again = init_workspace_folder(temp_dir.path(), DEFAULT_LIST_NAME).unwrap();
+        assert_eq!(again, FolderSetup { adopted: true, created_default_list: false });
+
+        let repo = TaskRepository::new(temp_dir.path().to_path_buf()).unwrap();
+        let titles: Vec<String> = repo.get_lists().unwrap().into_iter().map(|l| l.title).collect();
+        assert_eq!(titles, vec![DEFAULT_LIST_NAME]);
+    }
+
+    #[test]
+    fn test_init_workspace_folder_finishes_an_interrupted_init() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        // Metadata written, but the default list never created
+        TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let setup = init_workspace_folder(temp_dir.path(), DEFAULT_LIST_NAME).unwrap();
+        assert_eq!(setup, FolderSetup { adopted: true, created_default_list: true });
+
+        // A plain folder in the way is left alone instead of failing the init
+        let other = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(other.path().join(DEFAULT_LIST_NAME)).unwrap();
+        let setup = init_workspace_folder(other.path(), DEFAULT_LIST_NAME).unwrap();
+        assert_eq!(setup, FolderSetup { adopted: false, created_default_list: false });
+    }
+
+    #[test]
+    fn test_with_workspace_path_removes_the_folders_it_created_on_failure() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let nested = temp_dir.path().join("typo").join("tasks");
+        let result: Result<()> = with_workspace_path(nested.display().to_string(), |path| {
+            assert!(path.is_dir());
+            anyhow::bail!("refused")
+        });
+        assert_eq!(result.unwrap_err().to_string(), "refused");
+        assert!(!temp_dir.path().join("typo").exists());
+
+        // A folder that was already there stays, and so does one the command succeeded on
+        let existing = temp_dir.path().display().to_string();
+        assert!(with_workspace_path(existing, |_| -> Result<()> { anyhow::bail!("refused") }).is_err());
+        assert!(temp_dir.path().is_dir());
+        let path = with_workspace_path(nested.display().to_string(), Ok).unwrap();
+        assert_eq!(path, std::fs::canonicalize(&nested).unwrap());
+    }
+
+    #[test]
+    fn test_auto_sync_runs_push_only_when_enabled_and_configured() {
+        let url = Some("https://dav.example.com/tasks");
+        for (ws, expected) in [
+            (workspace(true, url), true),
+            (workspace(false, url), false),
+            (workspace(true, None), false),
+        ] {
+            let mut pushed = false;
+            let ran = auto_sync(&ws, || { pushed = true; Ok(()) }).unwrap();
+            assert_eq!(ran, expected);
+            assert_eq!(pushed, expected);
+        }
+    }
+
+    #[test]
+    fn test_auto_sync_passes_push_errors_up() {
+        let ws = workspace(true, Some("https://dav.example.com/tasks"));
+        let result = auto_sync(&ws, || anyhow::bail!("offline"));
+        assert_eq!(result.unwrap_err().to_string(), "offline");
+    }
+}
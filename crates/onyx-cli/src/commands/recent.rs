@@ -0,0 +1,34 @@
+use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
+use colored::*;
+use onyx_core::TaskStatus;
+use crate::output;
+use crate::commands::get_repository;
+
+pub fn execute(days: u32, workspace: Option<String>) -> Result<()> {
+    let (repo, _workspace_name) = get_repository(workspace)?;
+
+    let now = Utc::now();
+    let recent = repo.recent_tasks(now - Duration::days(days as i64))
+        .context("Failed to get recent tasks")?;
+
+    output::header(&format!("Updated in the last {} day{}", days, if days == 1 { "" } else { "s" }));
+    if recent.is_empty() {
+        output::item("No tasks");
+        return Ok(());
+    }
+
+    for (list_title, task) in &recent {
+        let checkbox = if task.status == TaskStatus::Completed { "[✓]".green() } else { "[ ]".normal() };
+        output::item(&format!(
+            "{} {} {} {} {}",
+            checkbox,
+            task.title,
+            format!("({})", list_title).dimmed(),
+            output::humanize_relative(task.updated_at, now).yellow(),
+            task.id.to_string().dimmed()
+        ));
+    }
+
+    Ok(())
+}
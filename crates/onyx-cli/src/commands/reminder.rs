@@ -0,0 +1,72 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use colored::*;
+use notify_rust::Notification;
+use onyx_core::config::DateOnlyDue;
+use crate::output;
+use crate::commands::{display_timezone, get_repository, Changed};
+use crate::commands::task::{find_task, parse_due_date, remember_task_list};
+
+pub fn add(task_id_str: String, at_str: String, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+
+    // A date-only --at fires at the start of that day
+    let tz = display_timezone()?;
+    let (at, _has_time) = parse_due_date(&at_str, DateOnlyDue::StartOfDay, &tz)?;
+
+    let (list, mut task) = find_task(&repo, &workspace_name, &task_id_str)?;
+    let list_id = list.id;
+
+    let when = at.with_timezone(&tz).format("%Y-%m-%d %H:%M");
+    if !task.add_reminder(at) {
+        output::info(&format!("Task \"{}\" already has a reminder at {}", task.title, when));
+        return Ok(None);
+    }
+
+    repo.update_task(list_id, task.clone())
+        .context("Failed to update task")?;
+    remember_task_list(&workspace_name, list_id)?;
+
+    output::success(&format!("Reminder set for \"{}\" at {}", task.title, when));
+
+    Ok(Some(workspace_name))
+}
+
+/// Send a desktop notification for every due, unsent reminder. Meant to be run from cron.
+pub fn notify(workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+
+    let due = repo.due_reminders(Utc::now())
+        .context("Failed to get due reminders")?;
+
+    if due.is_empty() {
+        output::info("No reminders due");
+        return Ok(None);
+    }
+
+    let mut sent = 0;
+    for reminder in &due {
+        let shown = Notification::new()
+            .appname("Onyx")
+            .summary(&reminder.task.title)
+            .body(&format!("Reminder from {}", reminder.list_title))
+            .show();
+        if let Err(e) = shown {
+            output::error(&format!("Could not notify for \"{}\": {}", reminder.task.title, e));
+            continue;
+        }
+        repo.mark_reminder_sent(reminder)
+            .context("Failed to mark reminder as sent")?;
+        output::item(&format!("{} {}", reminder.task.title, reminder.task.id.to_string().dimmed()));
+        sent += 1;
+    }
+
+    let summary = format!("Sent {} of {} reminder{}", sent, due.len(), if due.len() == 1 { "" } else { "s" });
+    if sent == due.len() {
+        output::success(&summary);
+    } else {
+        output::warning(&summary);
+    }
+
+    Ok((sent > 0).then_some(workspace_name))
+}
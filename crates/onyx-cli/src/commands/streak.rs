@@ -0,0 +1,28 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use colored::*;
+use onyx_core::streak::compute_streaks;
+use crate::output;
+use crate::commands::{display_timezone, get_repository};
+
+pub fn execute(list_name: String, workspace: Option<String>) -> Result<()> {
+    let (repo, _workspace_name) = get_repository(workspace)?;
+
+    let list = repo.resolve_list(&list_name)?;
+    let history = repo.completion_history(list.id)
+        .context("Failed to read completion history")?;
+
+    let tz = display_timezone()?;
+    let streaks = compute_streaks(&history, Utc::now(), &tz);
+
+    output::header(&format!("Streaks for \"{}\"", list.title));
+    output::detail("Completed", &history.len().to_string());
+    output::detail("Current streak", &days(streaks.current).green().to_string());
+    output::detail("Longest streak", &days(streaks.longest));
+
+    Ok(())
+}
+
+fn days(n: u32) -> String {
+    format!("{} day{}", n, if n == 1 { "" } else { "s" })
+}
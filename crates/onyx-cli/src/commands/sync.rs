@@ -1,24 +1,32 @@
+use std::path::Path;
 use anyhow::{Context, Result};
+use clap::ValueEnum;
 use colored::Colorize;
-use onyx_core::sync::{SyncMode, sync_workspace, get_sync_status};
-use onyx_core::webdav::{WebDavClient, store_credentials, load_credentials};
+use onyx_core::sync::{check_remote, format_bytes, ConflictSide, ProgressCallback, SyncMode, push_changed_since, sync_workspace_with, get_sync_status, list_conflicts, resolve_conflict};
+use onyx_core::webdav::{WebDavClient, store_credentials, resolve_credentials};
+use onyx_core::{WorkspaceConfig, WorkspaceSettings};
 use crate::output;
-use super::{load_config, save_config};
+use super::{load_config, resolve_workspace, save_config, Changed, Toggle};
 
 /// Run sync setup: prompt for URL, username, password, test connection, store credentials.
-pub fn setup(workspace_name: Option<String>) -> Result<()> {
+/// `proxy` and `ca_cert` replace the workspace's saved values when given; an empty string
+/// clears one.
+pub fn setup(proxy: Option<String>, ca_cert: Option<String>, workspace_name: Option<String>) -> Result<()> {
     let mut config = load_config()?;
 
-    let (name, workspace) = if let Some(name) = workspace_name {
-        let ws = config.get_workspace(&name)
-            .ok_or_else(|| anyhow::anyhow!("Workspace '{}' not found", name))?
-            .clone();
-        (name, ws)
-    } else {
-        let (n, ws) = config.get_current_workspace()
-            .context("No workspace set. Use 'onyx init' to create one.")?;
-        (n.clone(), ws.clone())
-    };
+    let (name, mut workspace) = resolve_workspace(&config, workspace_name)?;
+    if let Some(proxy) = proxy {
+        workspace.webdav_proxy = Some(proxy).filter(|p| !p.is_empty());
+    }
+    if let Some(ca_cert) = ca_cert {
+        workspace.webdav_ca_cert = if ca_cert.is_empty() {
+            None
+        } else {
+            // Stored absolute, since the config is shared by every working directory
+            Some(std::fs::canonicalize(&ca_cert)
+                .with_context(|| format!("CA certificate not found: {}", ca_cert))?)
+        };
+    }
 
     // Prompt for WebDAV URL
     output::header(&format!("WebDAV sync setup for workspace \"{}\"", name.green()));
@@ -31,6 +39,21 @@ pub fn setup(workspace_name: Option<String>) -> Result<()> {
     }
 
     let username = prompt("Username: ")?;
+    if !connect(&mut workspace, url, username)? {
+        return Ok(());
+    }
+
+    config.add_workspace(name, workspace);
+    save_config(&config)?;
+
+    output::success("Sync setup complete. Run 'onyx sync' to sync.");
+    Ok(())
+}
+
+/// Prompt for the password, test the connection, store the credentials and set
+/// `webdav_url` on `workspace`. Returns false, after reporting the error, if the
+/// connection test fails; `workspace` is then left unchanged.
+pub fn connect(workspace: &mut WorkspaceConfig, url: String, username: String) -> Result<bool> {
     let password = rpassword::read_password_from_tty(Some("Password: "))
         .context("Failed to read password")?;
 
@@ -39,8 +62,8 @@ pub fn setup(workspace_name: Option<String>) -> Result<()> {
     output::info("Testing connection...");
 
     let rt = tokio::runtime::Runtime::new().context("Failed to create async runtime")?;
-    let client = WebDavClient::new(&url, &username, &password)
-        .context("Invalid WebDAV URL")?;
+    let client = WebDavClient::with_options(&url, &username, &password, &workspace.webdav_options())
+        .context("Failed to create WebDAV client")?;
 
     match rt.block_on(client.test_connection()) {
         Ok(()) => {
@@ -48,7 +71,7 @@ pub fn setup(workspace_name: Option<String>) -> Result<()> {
         }
         Err(e) => {
             output::error(&format!("Connection failed: {}", e));
-            return Ok(());
+            return Ok(false);
         }
     }
 
@@ -64,61 +87,53 @@ pub fn setup(workspace_name: Option<String>) -> Result<()> {
         }
     }
 
-    // Update workspace config with WebDAV URL
-    let mut ws = workspace;
-    ws.webdav_url = Some(url);
-    config.add_workspace(name, ws);
-    save_config(&config)?;
-
-    output::success("Sync setup complete. Run 'onyx sync' to sync.");
-    Ok(())
+    workspace.webdav_url = Some(url);
+    Ok(true)
 }
 
-/// Execute a sync operation.
-pub fn execute(mode: SyncMode, workspace_name: Option<String>) -> Result<()> {
+/// Execute a sync operation. With `json`, progress is suppressed and only the `SyncResult` is printed.
+/// With `since` (push only), just the files modified within that window are considered.
+pub fn execute(mode: SyncMode, since: Option<chrono::Duration>, json: bool, password_file: Option<String>, workspace_name: Option<String>) -> Result<()> {
     let config = load_config()?;
 
-    let (name, workspace) = if let Some(name) = workspace_name {
-        let ws = config.get_workspace(&name)
-            .ok_or_else(|| anyhow::anyhow!("Workspace '{}' not found", name))?
-            .clone();
-        (name, ws)
-    } else {
-        let (n, ws) = config.get_current_workspace()
-            .context("No workspace set. Use 'onyx init' to create one.")?;
-        (n.clone(), ws.clone())
-    };
-
-    let url = workspace.webdav_url.as_ref()
-        .ok_or_else(|| anyhow::anyhow!(
-            "No WebDAV URL configured for workspace '{}'. Run 'onyx sync --setup' first.", name
-        ))?;
+    let (name, workspace) = resolve_workspace(&config, workspace_name)?;
 
-    let domain = extract_domain(url);
-    let (username, password) = load_credentials(&domain)
-        .context("Failed to load credentials")?;
+    let client = client_for(&name, &workspace, password_file.as_deref())?;
 
     let mode_str = match mode {
         SyncMode::Full => "Syncing",
         SyncMode::Push => "Pushing",
         SyncMode::Pull => "Pulling",
     };
-    output::info(&format!("{} workspace \"{}\"...", mode_str, name.green()));
+    if !json {
+        output::info(&format!("{} workspace \"{}\"...", mode_str, name.green()));
+    }
+
+    let on_progress: Option<ProgressCallback> = if json {
+        None
+    } else {
+        Some(Box::new(|msg: &str| { println!("{}", msg); }))
+    };
 
     let rt = tokio::runtime::Runtime::new().context("Failed to create async runtime")?;
-    let result = rt.block_on(sync_workspace(
-        &workspace.path,
-        url,
-        &username,
-        &password,
-        mode,
-        Some(Box::new(|msg: &str| { println!("{}", msg); })),
-    )).context("Sync failed")?;
+    let result = match since {
+        Some(window) => rt.block_on(push_changed_since(&workspace.path, &config.defaults, &client, chrono::Utc::now() - window, on_progress)),
+        None => rt.block_on(sync_workspace_with(&workspace.path, &config.defaults, &client, mode, on_progress)),
+    }.context("Sync failed")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&result).context("Failed to serialize sync result")?);
+        return Ok(());
+    }
 
     // Print summary
     let mut parts = Vec::new();
-    if result.uploaded > 0 { parts.push(format!("{} uploaded", result.uploaded)); }
-    if result.downloaded > 0 { parts.push(format!("{} downloaded", result.downloaded)); }
+    if result.uploaded > 0 {
+        parts.push(format!("{} uploaded ({})", result.uploaded, format_bytes(result.bytes_uploaded)));
+    }
+    if result.downloaded > 0 {
+        parts.push(format!("{} downloaded ({})", result.downloaded, format_bytes(result.bytes_downloaded)));
+    }
     if result.deleted_local > 0 { parts.push(format!("{} deleted locally", result.deleted_local)); }
     if result.deleted_remote > 0 { parts.push(format!("{} deleted remotely", result.deleted_remote)); }
     if result.conflicts > 0 { parts.push(format!("{} conflicts", result.conflicts)); }
@@ -140,6 +155,39 @@ pub fn execute(mode: SyncMode, workspace_name: Option<String>) -> Result<()> {
     Ok(())
 }
 
+/// Push `workspace` without progress output, for the auto-sync hook. Per-file errors are
+/// reported as one failure.
+pub fn push_quietly(name: &str, workspace: &WorkspaceConfig, defaults: &WorkspaceSettings) -> Result<()> {
+    let client = client_for(name, workspace, None)?;
+    let rt = tokio::runtime::Runtime::new().context("Failed to create async runtime")?;
+    let result = rt.block_on(sync_workspace_with(&workspace.path, defaults, &client, SyncMode::Push, None))?;
+    if let Some(first) = result.errors.first() {
+        anyhow::bail!("{} file(s) not pushed ({})", result.errors.len(), first);
+    }
+    Ok(())
+}
+
+/// Turn pushing after every change on or off for a workspace.
+pub fn set_auto(toggle: Toggle, workspace_name: Option<String>) -> Result<()> {
+    let mut config = load_config()?;
+    let (name, mut workspace) = resolve_workspace(&config, workspace_name)?;
+    workspace.auto_sync = toggle == Toggle::On;
+    let configured = workspace.webdav_url.is_some();
+    config.add_workspace(name.clone(), workspace);
+    save_config(&config)?;
+
+    match toggle {
+        Toggle::On => {
+            output::success(&format!("Auto-sync enabled for workspace \"{}\"", name.green()));
+            if !configured {
+                output::warning("No WebDAV URL configured yet; run 'onyx sync --setup' to start syncing");
+            }
+        }
+        Toggle::Off => output::success(&format!("Auto-sync disabled for workspace \"{}\"", name.green())),
+    }
+    Ok(())
+}
+
 /// Show sync status for a workspace.
 pub fn status(workspace_name: Option<String>, all: bool) -> Result<()> {
     let config = load_config()?;
@@ -163,21 +211,120 @@ pub fn status(workspace_name: Option<String>, all: bool) -> Result<()> {
         return Ok(());
     }
 
-    let (name, workspace) = if let Some(name) = workspace_name {
-        let ws = config.get_workspace(&name)
-            .ok_or_else(|| anyhow::anyhow!("Workspace '{}' not found", name))?
-            .clone();
-        (name, ws)
-    } else {
-        let (n, ws) = config.get_current_workspace()
-            .context("No workspace set.")?;
-        (n.clone(), ws.clone())
-    };
+    let (name, workspace) = resolve_workspace(&config, workspace_name)?;
 
     print_workspace_status(&name, &workspace.path, workspace.webdav_url.as_deref())?;
     Ok(())
 }
 
+/// Check each piece sync depends on, printing ✓/✗ per step. Fails if any step did.
+pub fn check(password_file: Option<String>, workspace_name: Option<String>) -> Result<()> {
+    let config = load_config()?;
+    let (name, workspace) = resolve_workspace(&config, workspace_name)?;
+    output::header(&format!("Checking sync for workspace \"{}\"", name.green()));
+
+    let Some(url) = workspace.webdav_url.as_deref() else {
+        output::error("No WebDAV URL configured. Run 'onyx sync --setup' first.");
+        anyhow::bail!("Sync check failed");
+    };
+    output::success(&format!("WebDAV URL configured ({})", url));
+
+    let (username, password) = match resolve_credentials(&extract_domain(url), password_file.as_deref().map(Path::new)) {
+        Ok(credentials) => {
+            output::success("Credentials found");
+            credentials
+        }
+        Err(e) => {
+            output::error(&format!("Credentials not found: {}", e));
+            anyhow::bail!("Sync check failed");
+        }
+    };
+    let client = match WebDavClient::with_options(url, &username, &password, &workspace.webdav_options()) {
+        Ok(client) => client,
+        Err(e) => {
+            output::error(&format!("Could not create WebDAV client: {}", e));
+            anyhow::bail!("Sync check failed");
+        }
+    };
+
+    let rt = tokio::runtime::Runtime::new().context("Failed to create async runtime")?;
+    let steps = rt.block_on(check_remote(&client));
+    let mut failed = false;
+    for step in &steps {
+        match &step.outcome {
+            Ok(()) => output::success(&step.description),
+            Err(e) => {
+                failed = true;
+                output::error(&format!("{}: {}", step.description, e));
+            }
+        }
+    }
+    if failed {
+        anyhow::bail!("Sync check failed");
+    }
+    output::success("Sync is ready.");
+    Ok(())
+}
+
+/// `--keep` choices for `sync resolve`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum KeepSide {
+    /// Restore the local version; the next sync uploads it
+    Local,
+    /// Keep the downloaded remote version
+    Remote,
+}
+
+impl From<KeepSide> for ConflictSide {
+    fn from(side: KeepSide) -> Self {
+        match side {
+            KeepSide::Local => ConflictSide::Local,
+            KeepSide::Remote => ConflictSide::Remote,
+        }
+    }
+}
+
+/// List conflict backups left by syncs where the remote version won.
+pub fn conflicts(workspace_name: Option<String>) -> Result<()> {
+    let config = load_config()?;
+    let (_name, workspace) = resolve_workspace(&config, workspace_name)?;
+
+    let conflicts = list_conflicts(&workspace.path).context("Failed to scan for conflicts")?;
+    if conflicts.is_empty() {
+        output::success("No sync conflicts.");
+        return Ok(());
+    }
+
+    output::header(&format!("{} conflict{}", conflicts.len(), if conflicts.len() == 1 { "" } else { "s" }));
+    for conflict in &conflicts {
+        let when = conflict.backed_up_at
+            .map(|t| format!(" (local version saved {})", t.format("%Y-%m-%d %H:%M UTC")))
+            .unwrap_or_default();
+        output::item(&format!("{}{} {}", conflict.path, when, conflict.backup_path.dimmed()));
+    }
+    output::blank();
+    output::info("Run 'onyx sync resolve <file> --keep local|remote' to settle one.");
+    Ok(())
+}
+
+/// Keep one side of a conflict and remove its backup.
+pub fn resolve(file: String, keep: KeepSide, workspace_name: Option<String>) -> Result<Changed> {
+    let config = load_config()?;
+    let (name, workspace) = resolve_workspace(&config, workspace_name)?;
+
+    let conflict = resolve_conflict(&workspace.path, &file, keep.into())
+        .context("Failed to resolve conflict")?;
+    match keep {
+        KeepSide::Local => output::success(&format!(
+            "Restored the local version of {}; the next sync uploads it", conflict.path
+        )),
+        KeepSide::Remote => output::success(&format!(
+            "Kept the remote version of {} and removed {}", conflict.path, conflict.backup_path
+        )),
+    }
+    Ok(Some(name))
+}
+
 fn print_workspace_status(name: &str, path: &std::path::Path, webdav_url: Option<&str>) -> Result<()> {
     output::header(&format!("Workspace: {}", name.green()));
 
@@ -205,6 +352,41 @@ fn print_workspace_status(name: &str, path: &std::path::Path, webdav_url: Option
     Ok(())
 }
 
+/// WebDAV client for workspace `name`, with credentials from `password_file` or, without
+/// one, from ONYX_WEBDAV_PASS or the keychain.
+fn client_for(name: &str, workspace: &WorkspaceConfig, password_file: Option<&str>) -> Result<WebDavClient> {
+    let url = workspace.webdav_url.as_ref()
+        .ok_or_else(|| anyhow::anyhow!(
+            "No WebDAV URL configured for workspace '{}'. Run 'onyx sync --setup' first.", name
+        ))?;
+
+    let domain = extract_domain(url);
+    let (username, password) = resolve_credentials(&domain, password_file.map(Path::new))
+        .context("Failed to load credentials")?;
+
+    WebDavClient::with_options(url, &username, &password, &workspace.webdav_options())
+        .context("Failed to create WebDAV client")
+}
+
+/// Parse a `--since` window: a whole number followed by `m` (minutes), `h` (hours),
+/// `d` (days) or `w` (weeks).
+pub fn parse_since(input: &str) -> Result<chrono::Duration, String> {
+    let input = input.trim();
+    let split = input.len() - input.chars().last().map_or(0, char::len_utf8);
+    let (amount, unit) = input.split_at(split);
+    let amount: i64 = amount.parse()
+        .map_err(|_| format!("invalid window '{}': expected e.g. 30m, 1h, 2d or 1w", input))?;
+    let window = match unit {
+        "m" => chrono::Duration::try_minutes(amount),
+        "h" => chrono::Duration::try_hours(amount),
+        "d" => chrono::Duration::try_days(amount),
+        "w" => chrono::Duration::try_weeks(amount),
+        _ => None,
+    };
+    window.filter(|w| *w > chrono::Duration::zero())
+        .ok_or_else(|| format!("invalid window '{}': expected e.g. 30m, 1h, 2d or 1w", input))
+}
+
 /// Extract host from a URL for credential storage.
 fn extract_domain(url: &str) -> String {
     // Strip scheme
@@ -222,7 +404,7 @@ fn extract_domain(url: &str) -> String {
 }
 
 /// Prompt the user for text input.
-fn prompt(message: &str) -> Result<String> {
+pub fn prompt(message: &str) -> Result<String> {
     use std::io::Write;
     print!("{}", message);
     std::io::stdout().flush()?;
@@ -230,3 +412,19 @@ fn prompt(message: &str) -> Result<String> {
     std::io::stdin().read_line(&mut input)?;
     Ok(input.trim().to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(parse_since("30m"), Ok(chrono::Duration::minutes(30)));
+        assert_eq!(parse_since("1h"), Ok(chrono::Duration::hours(1)));
+        assert_eq!(parse_since("2d"), Ok(chrono::Duration::days(2)));
+        assert_eq!(parse_since("1w"), Ok(chrono::Duration::weeks(1)));
+        for bad in ["", "h", "1", "1y", "-1h", "0h", "1.5h"] {
+            assert!(parse_since(bad).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use anyhow::{Context, Result};
+use onyx_core::{TaskRepository, WorkspaceSettings};
+use crate::output;
+use crate::commands::{get_repository, workspace_settings, Changed, Toggle};
+
+/// Show the allowed tags (workspace settings over the global defaults) and strictness.
+pub fn list(workspace: Option<String>) -> Result<()> {
+    let (repo, _workspace_name) = get_repository(workspace)?;
+    let settings = workspace_settings(&repo)?;
+
+    output::header("Allowed tags");
+    if settings.allowed_tags.is_empty() {
+        output::item("Any tag (no allowed tags set)");
+        return Ok(());
+    }
+    for tag in &settings.allowed_tags {
+        output::item(tag);
+    }
+    output::blank();
+    if settings.strict_tags == Some(true) {
+        output::info("Strict: other tags are refused");
+    } else {
+        output::info("Lenient: other tags get a warning");
+    }
+    Ok(())
+}
+
+/// Add tags to the workspace's allowed tags.
+pub fn allow(tags: Vec<String>, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+    let added = update_settings(&mut repo, |settings| settings.allow_tags(&tags))?;
+    if added.is_empty() {
+        output::info("Those tags are already allowed");
+        return Ok(None);
+    }
+    output::success(&format!("Allowed {}", added.join(", ")));
+    Ok(Some(workspace_name))
+}
+
+/// Remove tags from the workspace's allowed tags. Tasks already using them keep them.
+pub fn disallow(tags: Vec<String>, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+    let removed = update_settings(&mut repo, |settings| settings.disallow_tags(&tags))?;
+    if removed.is_empty() {
+        output::info("None of those tags were allowed");
+        return Ok(None);
+    }
+    output::success(&format!("No longer allowed: {}", removed.join(", ")));
+    Ok(Some(workspace_name))
+}
+
+/// Turn refusing tags outside the allowed ones on or off.
+pub fn strict(state: Toggle, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+    update_settings(&mut repo, |settings| settings.strict_tags = Some(state == Toggle::On))?;
+    match state {
+        Toggle::On => output::success("Tags outside the allowed ones are now refused"),
+        Toggle::Off => output::success("Tags outside the allowed ones now only get a warning"),
+    }
+    Ok(Some(workspace_name))
+}
+
+/// Apply `change` to the workspace's own `.workspace.json` settings and save them.
+fn update_settings<R>(repo: &mut TaskRepository, change: impl FnOnce(&mut WorkspaceSettings) -> R) -> Result<R> {
+    let mut settings = repo.get_workspace_settings()
+        .context("Failed to read workspace settings")?;
+    let result = change(&mut settings);
+    repo.set_workspace_settings(&settings)
+        .context("Failed to save workspace settings")?;
+    Ok(result)
+}
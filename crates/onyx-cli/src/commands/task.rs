@@ -1,149 +1,422 @@
+use std::io::BufRead;
 use anyhow::{Context, Result};
-use onyx_core::Task;
-use chrono::{DateTime, Utc};
+use clap::Args;
+use onyx_core::{Task, TaskList, TaskRepository, TaskStatus};
+use onyx_core::config::DateOnlyDue;
+use onyx_core::models::{resolve_date_only_due, tasks_with_id_prefix, Priority};
+use onyx_core::quick::parse_quick_entry;
+use onyx_core::storage::parse_task_file;
+use chrono::{DateTime, TimeZone, Utc};
 use uuid::Uuid;
 use crate::output;
-use crate::commands::get_repository;
-
-pub fn add(title: String, list_name: Option<String>, due_str: Option<String>, workspace: Option<String>) -> Result<()> {
-    let (mut repo, _workspace_name) = get_repository(workspace)?;
-
-    // Get lists
-    let lists = repo.get_lists()
-        .context("Failed to get lists")?;
+use crate::commands::{display_timezone, get_repository, load_config, save_config, workspace_settings, Changed};
+
+#[derive(Debug, Args)]
+pub struct AddArgs {
+    /// Task title
+    title: String,
+    /// List to add task to (name or @index; defaults to the last list shown or added to)
+    #[arg(short, long)]
+    list: Option<String>,
+    /// Add to the workspace's inbox list (see `onyx list set-inbox`) instead
+    #[arg(long, conflicts_with = "list")]
+    inbox: bool,
+    /// Create the --list if it doesn't exist yet
+    #[arg(long, alias = "force", requires = "list")]
+    create_list: bool,
+    /// Due date (ISO 8601 format: YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS)
+    #[arg(short, long)]
+    due: Option<String>,
+    /// Resolve a date-only --due to 00:00 instead of 23:59:59 (local time)
+    #[arg(long)]
+    start_of_day: bool,
+    /// Priority (low, medium, high)
+    #[arg(short, long)]
+    priority: Option<Priority>,
+    /// Tag to attach (repeatable)
+    #[arg(short, long = "tag")]
+    tags: Vec<String>,
+    /// Place the new task right after this task (ID) in the list
+    #[arg(long, conflicts_with = "before")]
+    after: Option<String>,
+    /// Place the new task right before this task (ID) in the list
+    #[arg(long)]
+    before: Option<String>,
+    /// Workspace to use
+    #[arg(short, long)]
+    pub workspace: Option<String>,
+}
 
-    if lists.is_empty() {
-        anyhow::bail!("No lists found. Create one with 'onyx list create <name>'");
+pub fn add(args: AddArgs) -> Result<Changed> {
+    let AddArgs { title, list: list_name, inbox, create_list, due: due_str, start_of_day, priority, tags, after, before, workspace } = args;
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+    let settings = workspace_settings(&repo)?;
+
+    // Check the tags against the workspace's allowed tags, if it has any
+    let unknown = settings.check_tags(&tags).map_err(|e| anyhow::anyhow!(
+        "{} (allow it with 'onyx tags allow' or turn off 'onyx tags strict')", e
+    ))?;
+    for tag in unknown {
+        output::warning(&format!("Tag \"{}\" is not one of the allowed tags (see 'onyx tags list')", tag));
     }
 
     // Find the target list
     let list = if let Some(name) = list_name {
-        lists.iter()
-            .find(|l| l.title == name)
-            .ok_or_else(|| anyhow::anyhow!("List '{}' not found", name))?
+        target_list(&mut repo, &name, create_list)?
     } else {
-        // Use the first list
-        &lists[0]
+        let lists = repo.get_lists()
+            .context("Failed to get lists")?;
+        if lists.is_empty() {
+            anyhow::bail!("No lists found. Create one with 'onyx list create <name>'");
+        }
+        let inbox = if inbox {
+            repo.get_inbox_list().context("Failed to read workspace metadata")?
+        } else {
+            None
+        };
+        let last_opened = repo.get_last_opened_list()
+            .context("Failed to read workspace metadata")?;
+        fallback_list(lists, inbox, settings.default_list.as_deref(), last_opened)?
+    };
+
+    // Work out where an --after/--before anchor puts the task before creating anything
+    let position = match (after, before) {
+        (Some(anchor), _) => Some(anchor_position(&repo.get_task_order(list.id)?, &anchor, true, &list.title)?),
+        (_, Some(anchor)) => Some(anchor_position(&repo.get_task_order(list.id)?, &anchor, false, &list.title)?),
+        (None, None) => None,
     };
 
     // Create task
-    let mut task = Task::new(title.clone());
+    let mut builder = Task::builder(title.clone()).tags(tags);
+    if let Some(priority) = priority {
+        builder = builder.priority(priority);
+    }
 
     // Parse due date if provided
+    let tz = display_timezone()?;
     if let Some(due_str) = due_str {
-        let due_date = parse_due_date(&due_str)?;
-        task.due_date = Some(due_date);
+        let date_only_due = if start_of_day {
+            DateOnlyDue::StartOfDay
+        } else {
+            settings.date_only_due.unwrap_or_default()
+        };
+        let (due_date, has_time) = parse_due_date(&due_str, date_only_due, &tz)?;
+        builder = if has_time { builder.due_time(due_date) } else { builder.due_date(due_date) };
     }
+    let task = builder.build();
 
     // Save task
     repo.create_task(list.id, task.clone())
         .context("Failed to create task")?;
+    if let Some(position) = position {
+        repo.reorder_task(list.id, task.id, position)
+            .context("Failed to position task")?;
+    }
+    repo.set_last_opened_list(list.id)
+        .context("Failed to update workspace metadata")?;
 
     let due_info = if let Some(due) = task.due_date {
-        format!("\n  Due: {}", due.format("%Y-%m-%d"))
+        format!("\n  Due: {}", output::format_due(due, task.has_time, "%Y-%m-%d", &tz))
     } else {
         String::new()
     };
 
-    output::success(&format!("Created task \"{}\" ({}){}", title, task.id, due_info));
+    output::success(&format!("Created task \"{}\" ({}){}", task.title, task.id, due_info));
 
-    Ok(())
+    Ok(Some(workspace_name))
+}
+
+/// `add` from a quick-entry line: the due word, `#tags` and `!priority` in `input` are
+/// parsed out by `onyx_core::quick::parse_quick_entry`, and the rest is the title.
+pub fn quick(input: String, list: Option<String>, workspace: Option<String>) -> Result<Changed> {
+    let today = Utc::now().with_timezone(&display_timezone()?).date_naive();
+    let (title, meta) = parse_quick_entry(&input, today);
+    add(AddArgs {
+        title,
+        list,
+        inbox: false,
+        create_list: false,
+        due: meta.due.map(|due| due.format("%Y-%m-%d").to_string()),
+        start_of_day: false,
+        priority: meta.priority,
+        tags: meta.tags,
+        after: None,
+        before: None,
+        workspace,
+    })
 }
 
-pub fn complete(task_id_str: String, workspace: Option<String>) -> Result<()> {
-    let (mut repo, _workspace_name) = get_repository(workspace)?;
+/// `add --inbox`: add `title` to the inbox list, or where `add` would put it when the
+/// workspace has none.
+pub fn inbox(title: String, workspace: Option<String>) -> Result<Changed> {
+    add(AddArgs {
+        title,
+        list: None,
+        inbox: true,
+        create_list: false,
+        due: None,
+        start_of_day: false,
+        priority: None,
+        tags: Vec::new(),
+        after: None,
+        before: None,
+        workspace,
+    })
+}
 
-    let task_id = Uuid::parse_str(&task_id_str)
-        .context("Invalid task ID")?;
+pub fn complete(task_ids: Vec<String>, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
 
-    // Find the task across all lists
-    let lists = repo.get_lists()?;
-    let mut found = false;
+    let (specs, _) = task_id_args(task_ids, &mut std::io::stdin().lock())?;
+    // Look every task up before completing any, so a bad ID doesn't leave a job half done
+    let tasks = specs.iter()
+        .map(|spec| find_task(&repo, &workspace_name, spec))
+        .collect::<Result<Vec<_>>>()?;
 
-    for list in lists {
-        if let Some(mut task) = list.tasks.iter().find(|t| t.id == task_id).cloned() {
-            task.complete();
-            repo.update_task(list.id, task.clone())
-                .context("Failed to update task")?;
+    for (list, mut task) in tasks.iter().cloned() {
+        task.complete();
+        repo.update_task(list.id, task.clone())
+            .context("Failed to update task")?;
 
-            output::success(&format!("Completed task \"{}\"", task.title));
-            found = true;
-            break;
+        output::success(&format!("Completed task \"{}\"", task.title));
+    }
+
+    if let Some((list, _)) = tasks.last() {
+        remember_task_list(&workspace_name, list.id)?;
+    }
+    Ok(Some(workspace_name))
+}
+
+/// Task IDs from the command line, with a `-` replaced by the IDs read from `stdin` (one
+/// per line, blank lines skipped), as `onyx find --ids` prints them. Repeated IDs are
+/// dropped. Also says whether stdin was read, since it can't answer a prompt after that.
+fn task_id_args(args: Vec<String>, stdin: &mut impl BufRead) -> Result<(Vec<String>, bool)> {
+    let mut ids: Vec<String> = Vec::new();
+    let mut read_stdin = false;
+    for arg in args {
+        let piped = if arg == "-" && !read_stdin {
+            read_stdin = true;
+            stdin.lines()
+                .map(|line| line.map(|l| l.trim().to_string()))
+                .collect::<std::io::Result<Vec<String>>>()
+                .context("Failed to read task IDs from stdin")?
+        } else if arg == "-" {
+            Vec::new()
+        } else {
+            vec![arg]
+        };
+        for id in piped {
+            if !id.is_empty() && !ids.contains(&id) {
+                ids.push(id);
+            }
         }
     }
+    if read_stdin && ids.is_empty() {
+        anyhow::bail!("No task IDs on stdin");
+    }
+    Ok((ids, read_stdin))
+}
+
+pub fn show(task_id_str: String, render: bool, workspace: Option<String>) -> Result<()> {
+    let (repo, workspace_name) = get_repository(workspace)?;
 
-    if !found {
-        anyhow::bail!("Task not found: {}", task_id_str);
+    let (list, task) = find_task(&repo, &workspace_name, &task_id_str)?;
+
+    let tz = display_timezone()?;
+    output::header(&task.title);
+    output::detail("ID", &task.id.to_string());
+    output::detail("List", &list.title);
+    output::detail("Status", if task.status == TaskStatus::Completed { "completed" } else { "open" });
+    if let Some(label) = &task.label {
+        output::detail("Label", label);
+    }
+    if let Some(due) = task.due_date {
+        let due_str = output::format_due(due, task.has_time, "%Y-%m-%d", &tz);
+        let zone = if task.has_time { format!(" {}", due.with_timezone(&tz).format("%Z")) } else { String::new() };
+        output::detail("Due", &format!("{}{}", due_str, zone));
+    }
+    if let Some(priority) = task.priority {
+        output::detail("Priority", &priority.to_string());
+    }
+    if !task.tags.is_empty() {
+        output::detail("Tags", &task.tags.join(", "));
+    }
+    if let Some(assignee) = &task.assignee {
+        output::detail("Assignee", assignee);
+    }
+    if !task.depends_on.is_empty() {
+        let mut waits_on = Vec::new();
+        for id in &task.depends_on {
+            waits_on.push(match repo.find_task(*id) {
+                Ok((_, dep)) if dep.status == TaskStatus::Completed => format!("{} (done)", dep.title),
+                Ok((_, dep)) => dep.title,
+                Err(e) if e.is_not_found() => format!("{} (deleted)", id),
+                Err(e) => return Err(e).context("Failed to look up dependencies"),
+            });
+        }
+        output::detail("Waits on", &waits_on.join(", "));
+    }
+    for (key, value) in &task.fields {
+        output::detail(key, value);
+    }
+
+    output::blank();
+    match description_text(&task.description, render) {
+        Some(text) => println!("{}", text.trim_end()),
+        None => output::item("No description"),
     }
 
     Ok(())
 }
 
-pub fn delete(task_id_str: String, workspace: Option<String>) -> Result<()> {
-    let (mut repo, _workspace_name) = get_repository(workspace)?;
+/// Description as printed by `show`: rendered for the terminal (headings, emphasis, lists)
+/// when `render` is set, raw markdown otherwise. `None` when there is no description.
+fn description_text(description: &str, render: bool) -> Option<String> {
+    if description.trim().is_empty() {
+        return None;
+    }
+    if render {
+        Some(termimad::MadSkin::default().term_text(description).to_string())
+    } else {
+        Some(description.to_string())
+    }
+}
 
-    let task_id = Uuid::parse_str(&task_id_str)
-        .context("Invalid task ID")?;
+pub fn assign(task_id_str: String, name: Option<String>, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
 
-    // Find the task across all lists
-    let lists = repo.get_lists()?;
-    let mut found = false;
+    let name = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
 
-    for list in lists {
-        if let Some(task) = list.tasks.iter().find(|t| t.id == task_id) {
-            let title = task.title.clone();
+    let (list, mut task) = find_task(&repo, &workspace_name, &task_id_str)?;
+    task.assignee = name.clone();
+    task.updated_at = Utc::now();
+    repo.update_task(list.id, task.clone())
+        .context("Failed to update task")?;
+    remember_task_list(&workspace_name, list.id)?;
 
-            output::warning(&format!("This will delete task \"{}\"", title));
-            print!("Continue? (y/n): ");
-            use std::io::{self, Write};
-            io::stdout().flush()?;
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
-            if input.trim().to_lowercase() != "y" {
-                output::info("Cancelled");
-                return Ok(());
-            }
+    match &name {
+        Some(name) => output::success(&format!("Assigned \"{}\" to {}", task.title, name)),
+        None => output::success(&format!("Unassigned \"{}\"", task.title)),
+    }
 
-            repo.delete_task(list.id, task_id)
-                .context("Failed to delete task")?;
+    Ok(Some(workspace_name))
+}
+
+/// Widest label accepted, in terminal columns: room for a few emoji or a short word.
+const MAX_LABEL_WIDTH: usize = 8;
 
-            output::success(&format!("Deleted task \"{}\"", title));
-            found = true;
-            break;
+/// Set or (with `None`) clear the label shown before a task's title.
+pub fn label(task_id_str: String, label: Option<String>, workspace: Option<String>) -> Result<Changed> {
+    let label = label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+    if let Some(label) = &label {
+        if label.chars().any(char::is_control) {
+            anyhow::bail!("A label can't contain line breaks or other control characters");
+        }
+        if output::display_width(label) > MAX_LABEL_WIDTH {
+            anyhow::bail!("Label \"{}\" is too wide; keep it to {} columns (an emoji takes 2)", label, MAX_LABEL_WIDTH);
         }
     }
 
-    if !found {
-        anyhow::bail!("Task not found: {}", task_id_str);
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+    let (list, mut task) = find_task(&repo, &workspace_name, &task_id_str)?;
+    task.label = label.clone();
+    task.updated_at = Utc::now();
+    repo.update_task(list.id, task.clone())
+        .context("Failed to update task")?;
+    remember_task_list(&workspace_name, list.id)?;
+
+    match &label {
+        Some(label) => output::success(&format!("Labeled \"{}\" {}", task.title, label)),
+        None => output::success(&format!("Removed the label from \"{}\"", task.title)),
     }
+    Ok(Some(workspace_name))
+}
 
+pub fn block(task_id_str: String, on_str: String, remove: bool, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+    let (list, task) = find_task(&repo, &workspace_name, &task_id_str)?;
+
+    if remove {
+        // The task waited on may be gone already, so a full ID is taken as is
+        let (on_id, on_name) = match find_task(&repo, &workspace_name, &on_str) {
+            Ok((_, on)) => (on.id, format!("\"{}\"", on.title)),
+            Err(e) => match Uuid::parse_str(&on_str) {
+                Ok(id) => (id, id.to_string()),
+                Err(_) => return Err(e),
+            },
+        };
+        match repo.remove_dependency(task.id, on_id).context("Failed to update task")? {
+            Some(_) => output::success(&format!("\"{}\" no longer waits on {}", task.title, on_name)),
+            None => output::info(&format!("\"{}\" doesn't wait on {}", task.title, on_name)),
+        }
+        remember_task_list(&workspace_name, list.id)?;
+        return Ok(Some(workspace_name));
+    }
+
+    let (_, on) = find_task(&repo, &workspace_name, &on_str)?;
+    repo.add_dependency(task.id, on.id)?;
+    remember_task_list(&workspace_name, list.id)?;
+    if on.status == TaskStatus::Completed {
+        output::success(&format!("\"{}\" waits on \"{}\", which is already completed", task.title, on.title));
+    } else {
+        output::success(&format!("\"{}\" is blocked until \"{}\" is completed", task.title, on.title));
+    }
+    Ok(Some(workspace_name))
+}
+
+pub fn delete(task_ids: Vec<String>, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
+
+    let (specs, read_stdin) = task_id_args(task_ids, &mut std::io::stdin().lock())?;
+    // Look every task up before deleting any, so a bad ID doesn't leave a job half done
+    let tasks = specs.iter()
+        .map(|spec| find_task(&repo, &workspace_name, spec))
+        .collect::<Result<Vec<_>>>()?;
+
+    if read_stdin && !output::assumes_yes() {
+        anyhow::bail!("Task IDs were read from stdin, so there is no way to confirm; add --yes to delete them");
+    }
+    match tasks.as_slice() {
+        [(_, task)] => output::warning(&format!("This will delete task \"{}\"", task.title)),
+        _ => output::warning(&format!("This will delete {} tasks", tasks.len())),
+    }
+    if !output::confirm("Continue?")? {
+        output::info("Cancelled");
+        return Ok(None);
+    }
+
+    delete_tasks(&mut repo, &tasks)?;
+    if let Some((list, _)) = tasks.last() {
+        remember_task_list(&workspace_name, list.id)?;
+    }
+    Ok(Some(workspace_name))
+}
+
+fn delete_tasks(repo: &mut TaskRepository, tasks: &[(TaskList, Task)]) -> Result<()> {
+    for (list, task) in tasks {
+        repo.delete_task(list.id, task.id)
+            .with_context(|| format!("Failed to delete task \"{}\"", task.title))?;
+        output::success(&format!("Deleted task \"{}\"", task.title));
+    }
     Ok(())
 }
 
-pub fn edit(task_id_str: String, workspace: Option<String>) -> Result<()> {
-    let (mut repo, _workspace_name) = get_repository(workspace)?;
+pub fn edit(task_id_str: String, editor_override: Option<String>, raw: bool, workspace: Option<String>) -> Result<Changed> {
+    let (mut repo, workspace_name) = get_repository(workspace)?;
 
-    let task_id = Uuid::parse_str(&task_id_str)
-        .context("Invalid task ID")?;
+    let (list, task) = find_task(&repo, &workspace_name, &task_id_str)?;
+    let list_id = list.id;
 
-    // Find the task across all lists
-    let lists = repo.get_lists()?;
-    let mut task_list_id = None;
-    let mut task_to_edit = None;
+    let editor = resolve_editor(editor_override)?;
 
-    for list in lists {
-        if let Some(task) = list.tasks.iter().find(|t| t.id == task_id).cloned() {
-            task_list_id = Some(list.id);
-            task_to_edit = Some(task);
-            break;
+    if raw {
+        if !edit_raw(&mut repo, list_id, task, &editor)? {
+            return Ok(None);
         }
+        remember_task_list(&workspace_name, list_id)?;
+        return Ok(Some(workspace_name));
     }
 
-    let (list_id, task) = match (task_list_id, task_to_edit) {
-        (Some(lid), Some(t)) => (lid, t),
-        _ => anyhow::bail!("Task not found: {}", task_id_str),
-    };
-
     // Create temporary file with task content
     let temp_dir = std::env::temp_dir();
     let temp_file = temp_dir.join(format!("onyx-{}.md", task.id));
@@ -152,24 +425,7 @@ pub fn edit(task_id_str: String, workspace: Option<String>) -> Result<()> {
     let content = format!("# {}\n\n{}", task.title, task.description);
     std::fs::write(&temp_file, content)?;
 
-    // Get editor from environment
-    let editor = std::env::var("EDITOR").unwrap_or_else(|_| {
-        if cfg!(windows) {
-            "notepad".to_string()
-        } else {
-            "nano".to_string()
-        }
-    });
-
-    // Open editor
-    let status = std::process::Command::new(&editor)
-        .arg(&temp_file)
-        .status()
-        .context(format!("Failed to open editor: {}", editor))?;
-
-    if !status.success() {
-        anyhow::bail!("Editor exited with non-zero status");
-    }
+    run_editor(&editor, &temp_file)?;
 
     // Read updated content
     let updated_content = std::fs::read_to_string(&temp_file)?;
@@ -190,33 +446,450 @@ pub fn edit(task_id_str: String, workspace: Option<String>) -> Result<()> {
 
     // Update task
     let mut updated_task = task.clone();
-    updated_task.title = title;
+    updated_task.set_title(&title)?;
     updated_task.description = description;
     updated_task.updated_at = Utc::now();
 
     repo.update_task(list_id, updated_task.clone())
         .context("Failed to update task")?;
+    remember_task_list(&workspace_name, list_id)?;
 
     // Clean up temp file
     std::fs::remove_file(&temp_file).ok();
 
     output::success(&format!("Updated task \"{}\"", updated_task.title));
 
+    Ok(Some(workspace_name))
+}
+
+/// Edit the stored markdown file (frontmatter included). The file is edited as a
+/// temporary copy and only written back through storage if it still parses. Returns
+/// whether anything was written.
+fn edit_raw(repo: &mut TaskRepository, list_id: Uuid, task: Task, editor: &str) -> Result<bool> {
+    let original = repo.get_task_source(list_id, task.id)
+        .context("Failed to read task file")?;
+
+    let temp_file = std::env::temp_dir().join(format!("onyx-{}.md", task.id));
+    std::fs::write(&temp_file, &original)?;
+
+    run_editor(editor, &temp_file)?;
+
+    let edited = std::fs::read_to_string(&temp_file)?;
+    if edited == original {
+        std::fs::remove_file(&temp_file).ok();
+        output::info("No changes");
+        return Ok(false);
+    }
+
+    let mut updated_task = match parse_task_file(&edited, &task.title) {
+        Ok(t) => t,
+        Err(e) => anyhow::bail!(
+            "Edited file is invalid, task left unchanged: {}\n  Your edits are saved in {}",
+            e, temp_file.display()
+        ),
+    };
+    if updated_task.id != task.id {
+        anyhow::bail!(
+            "Changing the task ID is not allowed, task left unchanged\n  Your edits are saved in {}",
+            temp_file.display()
+        );
+    }
+    updated_task.updated_at = Utc::now();
+
+    repo.update_task(list_id, updated_task.clone())
+        .context("Failed to update task")?;
+
+    std::fs::remove_file(&temp_file).ok();
+
+    output::success(&format!("Updated task \"{}\"", updated_task.title));
+
+    Ok(true)
+}
+
+fn run_editor(editor: &str, file: &std::path::Path) -> Result<()> {
+    let (program, args) = parse_editor_command(editor)?;
+
+    let status = std::process::Command::new(&program)
+        .args(&args)
+        .arg(file)
+        .status()
+        .context(format!("Failed to open editor: {}", editor))?;
+
+    if !status.success() {
+        anyhow::bail!("Editor exited with non-zero status");
+    }
+
     Ok(())
 }
 
-fn parse_due_date(s: &str) -> Result<DateTime<Utc>> {
+fn resolve_editor(editor_override: Option<String>) -> Result<String> {
+    if let Some(editor) = editor_override {
+        return Ok(editor);
+    }
+    if let Some(editor) = load_config()?.editor {
+        return Ok(editor);
+    }
+    Ok(std::env::var("EDITOR").unwrap_or_else(|_| {
+        if cfg!(windows) {
+            "notepad".to_string()
+        } else {
+            "nano".to_string()
+        }
+    }))
+}
+
+/// Split an editor command such as `code --wait` into the program and its arguments.
+fn parse_editor_command(editor: &str) -> Result<(String, Vec<String>)> {
+    let mut parts = shell_words::split(editor)
+        .context(format!("Invalid editor command: {}", editor))?;
+    if parts.is_empty() {
+        anyhow::bail!("Editor command is empty");
+    }
+    let program = parts.remove(0);
+    Ok((program, parts))
+}
+
+/// Most candidate IDs listed when a task ID can't be resolved.
+const MAX_ID_SUGGESTIONS: usize = 5;
+
+/// Find the task with ID `spec` in `workspace`, with its list (`tasks` left empty). The
+/// list recorded by [`remember_task_list`] is searched first, then the last-opened list;
+/// failing both, only the `id` line of each task file is read until the task turns up.
+/// Nothing is written. Errors are those of [`find_task_in_lists`].
+pub fn find_task(repo: &TaskRepository, workspace: &str, spec: &str) -> Result<(TaskList, Task)> {
+    let Ok(task_id) = Uuid::parse_str(spec.trim()) else {
+        // Not a whole ID, so this ends in an error; reading every task lets it name matches
+        return find_task_in_lists(repo.get_lists()?, spec);
+    };
+
+    let remembered = load_config()?.workspaces.get(workspace).and_then(|ws| ws.last_task_list);
+    let mut found = None;
+    for list_id in [remembered, repo.get_last_opened_list()?].into_iter().flatten() {
+        match repo.find_task_in(list_id, task_id) {
+            Ok(hit) => {
+                found = Some(hit);
+                break;
+            }
+            Err(e) if e.is_not_found() => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    match found {
+        Some(hit) => Ok(hit),
+        None => match repo.find_task(task_id) {
+            Ok(hit) => Ok(hit),
+            Err(e) if e.is_not_found() => find_task_in_lists(repo.get_list_headers()?, spec),
+            Err(e) => Err(e.into()),
+        },
+    }
+}
+
+/// Record `list_id` as the list [`find_task`] searches first in `workspace`. Commands that
+/// change tasks found by ID call this once, after writing, so lookups alone (`show`,
+/// `field get`) never rewrite the config. Nothing is saved when the list is already the
+/// recorded one.
+pub fn remember_task_list(workspace: &str, list_id: Uuid) -> Result<()> {
+    let mut config = load_config()?;
+    match config.workspaces.get_mut(workspace) {
+        Some(ws) if ws.last_task_list != Some(list_id) => {
+            ws.last_task_list = Some(list_id);
+            save_config(&config)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Find the task with ID `spec` in `lists`, with its list. Errors say what to do next: an
+/// ID prefix lists the tasks it matches, other non-IDs point at `onyx find`, and an unknown
+/// ID names the lists that were searched.
+fn find_task_in_lists(lists: Vec<TaskList>, spec: &str) -> Result<(TaskList, Task)> {
+    let spec = spec.trim();
+    let Ok(task_id) = Uuid::parse_str(spec) else {
+        let Ok(matches) = tasks_with_id_prefix(&lists, spec) else {
+            anyhow::bail!(
+                "'{}' is not a task ID. Task IDs look like 550e8400-e29b-41d4-a716-446655440000; \
+                 to look a task up by title, try: onyx find -q \"{}\"",
+                spec, spec
+            );
+        };
+        let matches: Vec<String> = matches.into_iter()
+            .map(|(list, task)| format!("  {}  {} ({})", task.id, task.title, list.title))
+            .collect();
+        if matches.is_empty() {
+            anyhow::bail!("'{}' is not a full task ID, and no task ID starts with it", spec);
+        }
+        let more = matches.len().saturating_sub(MAX_ID_SUGGESTIONS);
+        anyhow::bail!(
+            "'{}' is not a full task ID. Use the whole ID of one of these tasks:\n{}{}",
+            spec,
+            matches.into_iter().take(MAX_ID_SUGGESTIONS).collect::<Vec<_>>().join("\n"),
+            if more > 0 { format!("\n  ... and {} more", more) } else { String::new() }
+        );
+    };
+
+    let searched: Vec<String> = lists.iter().map(|list| format!("\"{}\"", list.title)).collect();
+    lists.into_iter()
+        .find_map(|list| {
+            let task = list.tasks.iter().find(|t| t.id == task_id).cloned()?;
+            Some((list, task))
+        })
+        .ok_or_else(|| anyhow::anyhow!(
+            "Task not found: {} (searched {} list{}: {}). It may have been deleted or live in another workspace; see 'onyx list show' for current IDs",
+            task_id,
+            searched.len(),
+            if searched.len() == 1 { "" } else { "s" },
+            if searched.is_empty() { "none".to_string() } else { searched.join(", ") }
+        ))
+}
+
+/// Index in `order` for a new task placed after (or before) the task `anchor`.
+fn anchor_position(order: &[Uuid], anchor: &str, after: bool, list_title: &str) -> Result<usize> {
+    let anchor_id = Uuid::parse_str(anchor)
+        .with_context(|| format!("Invalid task ID '{}'", anchor))?;
+    let index = order.iter().position(|&id| id == anchor_id)
+        .ok_or_else(|| anyhow::anyhow!("Task {} is not in list \"{}\"", anchor_id, list_title))?;
+    Ok(if after { index + 1 } else { index })
+}
+
+/// Resolve `add --list`. A missing list is created when `create_missing` is set (unless
+/// `spec` is an `@index`); otherwise the error points at `--create-list`.
+fn target_list(repo: &mut TaskRepository, spec: &str, create_missing: bool) -> Result<TaskList> {
+    match repo.resolve_list(spec) {
+        Ok(list) => Ok(list),
+        Err(e) if e.is_not_found() && create_missing && !is_index_spec(spec) => {
+            let list = repo.create_list(spec.to_string())
+                .context("Failed to create list")?;
+            output::info(&format!("Created list \"{}\"", list.title));
+            Ok(list)
+        }
+        Err(e) if e.is_not_found() && !is_index_spec(spec) => {
+            anyhow::bail!("{}. Pass --create-list to create it", e)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn is_index_spec(spec: &str) -> bool {
+    spec.strip_prefix('@').is_some_and(|i| i.parse::<usize>().is_ok())
+}
+
+/// List that `add` targets without `--list`: the `inbox` list if given and still there,
+/// then the workspace `default_list` setting if set, otherwise the last-opened list,
+/// otherwise the first list. `lists` must not be empty.
+fn fallback_list(mut lists: Vec<TaskList>, inbox: Option<Uuid>, default_list: Option<&str>, last_opened: Option<Uuid>) -> Result<TaskList> {
+    if let Some(pos) = inbox.and_then(|id| lists.iter().position(|l| l.id == id)) {
+        return Ok(lists.swap_remove(pos));
+    }
+    if let Some(name) = default_list {
+        return lists.into_iter()
+            .find(|l| l.title == name)
+            .ok_or_else(|| anyhow::anyhow!("Default list '{}' from workspace settings not found", name));
+    }
+    if let Some(pos) = last_opened.and_then(|id| lists.iter().position(|l| l.id == id)) {
+        return Ok(lists.swap_remove(pos));
+    }
+    Ok(lists.remove(0))
+}
+
+/// Parse a `--due` value. Returns the instant and whether it carries a time of day.
+/// Date-only inputs resolve to start or end of that day in `tz`; full datetimes are kept exact.
+pub fn parse_due_date<Tz: TimeZone>(s: &str, date_only_due: DateOnlyDue, tz: &Tz) -> Result<(DateTime<Utc>, bool)> {
     // Try parsing as date only (YYYY-MM-DD)
     if let Ok(naive_date) = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
-        let naive_datetime = naive_date.and_hms_opt(0, 0, 0)
-            .ok_or_else(|| anyhow::anyhow!("Invalid date"))?;
-        return Ok(DateTime::from_naive_utc_and_offset(naive_datetime, Utc));
+        return Ok((resolve_date_only_due(naive_date, date_only_due, tz), false));
     }
 
     // Try parsing as full datetime (ISO 8601)
     if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
-        return Ok(dt.with_timezone(&Utc));
+        return Ok((dt.with_timezone(&Utc), true));
     }
 
     anyhow::bail!("Invalid date format. Use YYYY-MM-DD or ISO 8601 format (YYYY-MM-DDTHH:MM:SS)")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_due_date_only_defaults_to_end_of_day() {
+        let tz = chrono::FixedOffset::east_opt(3600).unwrap();
+        let (due, has_time) = parse_due_date("2025-06-01", DateOnlyDue::EndOfDay, &tz).unwrap();
+        assert!(!has_time);
+        assert_eq!(due, Utc.with_ymd_and_hms(2025, 6, 1, 22, 59, 59).unwrap());
+
+        let (due, _) = parse_due_date("2025-06-01", DateOnlyDue::StartOfDay, &tz).unwrap();
+        assert_eq!(due, Utc.with_ymd_and_hms(2025, 5, 31, 23, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_parse_due_full_datetime_is_exact() {
+        let tz = chrono::FixedOffset::east_opt(3600).unwrap();
+        let (due, has_time) = parse_due_date("2025-06-01T09:30:00Z", DateOnlyDue::EndOfDay, &tz).unwrap();
+        assert!(has_time);
+        assert_eq!(due, Utc.with_ymd_and_hms(2025, 6, 1, 9, 30, 0).unwrap());
+        assert!(parse_due_date("June 1", DateOnlyDue::EndOfDay, &tz).is_err());
+    }
+
+    #[test]
+    fn test_fallback_list_prefers_last_opened() {
+        let lists = vec![TaskList::new("Inbox".into()), TaskList::new("Work".into()), TaskList::new("Home".into())];
+        let work = lists[1].id;
+
+        assert_eq!(fallback_list(lists.clone(), None, None, Some(work)).unwrap().title, "Work");
+        assert_eq!(fallback_list(lists.clone(), None, None, None).unwrap().title, "Inbox");
+        // A stale last-opened ID falls back to the first list
+        assert_eq!(fallback_list(lists.clone(), None, None, Some(Uuid::new_v4())).unwrap().title, "Inbox");
+        // An explicit default_list setting wins
+        assert_eq!(fallback_list(lists.clone(), None, Some("Home"), Some(work)).unwrap().title, "Home");
+        assert!(fallback_list(lists, None, Some("Missing"), Some(work)).is_err());
+    }
+
+    #[test]
+    fn test_fallback_list_inbox_comes_first() {
+        let lists = vec![TaskList::new("Work".into()), TaskList::new("Home".into()), TaskList::new("Inbox".into())];
+        let (work, inbox) = (lists[0].id, lists[2].id);
+
+        assert_eq!(fallback_list(lists.clone(), Some(inbox), Some("Home"), Some(work)).unwrap().title, "Inbox");
+        // An inbox that no longer exists falls through to the usual order
+        assert_eq!(fallback_list(lists.clone(), Some(Uuid::new_v4()), Some("Home"), Some(work)).unwrap().title, "Home");
+        assert_eq!(fallback_list(lists, Some(Uuid::new_v4()), None, Some(work)).unwrap().title, "Work");
+    }
+
+    #[test]
+    fn test_target_list_missing_with_and_without_create() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+
+        let err = target_list(&mut repo, "Errands", false).unwrap_err();
+        assert!(err.to_string().contains("--create-list"));
+        assert!(repo.get_lists().unwrap().is_empty());
+
+        let created = target_list(&mut repo, "Errands", true).unwrap();
+        assert_eq!(created.title, "Errands");
+        assert_eq!(repo.get_lists().unwrap().len(), 1);
+
+        // Existing lists are reused rather than created again
+        assert_eq!(target_list(&mut repo, "Errands", true).unwrap().id, created.id);
+        // An out-of-range @index is never turned into a list
+        assert!(target_list(&mut repo, "@5", true).is_err());
+        assert_eq!(repo.get_lists().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_find_task_errors_are_actionable() {
+        let mut work = TaskList::new("Work".into());
+        work.tasks = vec![Task::new("Report".into()), Task::new("Email".into())];
+        let mut home = TaskList::new("Home".into());
+        home.tasks = vec![Task::new("Dishes".into())];
+        let lists = vec![work.clone(), home];
+        let error = |spec: &str| find_task_in_lists(lists.clone(), spec).unwrap_err().to_string();
+
+        let (list, task) = find_task_in_lists(lists.clone(), &format!(" {} ", work.tasks[1].id)).unwrap();
+        assert_eq!((list.title.as_str(), task.title.as_str()), ("Work", "Email"));
+
+        let report_id = work.tasks[0].id.to_string();
+        let prefix = error(&report_id[..8].to_uppercase());
+        assert!(prefix.contains("not a full task ID"));
+        assert!(prefix.contains(&format!("{}  Report (Work)", report_id)));
+
+        assert!(error("buy milk").contains("onyx find -q \"buy milk\""));
+        assert!(error("zz-not-hex").contains("not a task ID"));
+
+        let missing = error(&Uuid::new_v4().to_string());
+        assert!(missing.starts_with("Task not found"));
+        assert!(missing.contains("searched 2 lists: \"Work\", \"Home\""));
+    }
+
+    #[test]
+    fn test_task_id_args_reads_stdin_for_dash() {
+        let mut stdin = std::io::Cursor::new("a1\n\n  b2  \r\na1\n");
+        let (ids, read_stdin) = task_id_args(vec!["z9".into(), "-".into(), "-".into()], &mut stdin).unwrap();
+        assert_eq!(ids, vec!["z9", "a1", "b2"]);
+        assert!(read_stdin);
+
+        let (ids, read_stdin) = task_id_args(vec!["z9".into()], &mut std::io::Cursor::new("a1")).unwrap();
+        assert_eq!((ids, read_stdin), (vec!["z9".to_string()], false));
+        assert!(task_id_args(vec!["-".into()], &mut std::io::Cursor::new("\n")).is_err());
+    }
+
+    #[test]
+    fn test_piped_find_ids_delete_tasks() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let list = repo.create_list("Attic".to_string()).unwrap();
+        for title in ["old lamp", "new rug", "old chair"] {
+            repo.create_task(list.id, Task::new(title.to_string())).unwrap();
+        }
+
+        // What `onyx find -q old --ids` prints
+        let query = onyx_core::TaskQuery { text: Some("old".to_string()), ..Default::default() };
+        let piped: String = repo.query(&query).unwrap().iter().map(|(_, t)| format!("{}\n", t.id)).collect();
+
+        let (ids, _) = task_id_args(vec!["-".into()], &mut std::io::Cursor::new(piped)).unwrap();
+        let tasks: Vec<(TaskList, Task)> = ids.iter()
+            .map(|id| repo.find_task(Uuid::parse_str(id).unwrap()).unwrap())
+            .collect();
+        delete_tasks(&mut repo, &tasks).unwrap();
+
+        let left: Vec<String> = repo.list_tasks(list.id).unwrap().into_iter().map(|t| t.title).collect();
+        assert_eq!(left, vec!["new rug"]);
+    }
+
+    #[test]
+    fn test_anchor_position() {
+        let order: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        let middle = order[1].to_string();
+
+        assert_eq!(anchor_position(&order, &middle, true, "L").unwrap(), 2);
+        assert_eq!(anchor_position(&order, &middle, false, "L").unwrap(), 1);
+        assert_eq!(anchor_position(&order, &order[2].to_string(), true, "L").unwrap(), 3);
+
+        let err = anchor_position(&order, &Uuid::new_v4().to_string(), true, "Work").unwrap_err();
+        assert!(err.to_string().contains("not in list \"Work\""));
+        assert!(anchor_position(&order, "nope", false, "L").is_err());
+    }
+
+    #[test]
+    fn test_description_text() {
+        assert_eq!(description_text("", true), None);
+        assert_eq!(description_text("  \n\n", false), None);
+
+        let markdown = "# Notes\n\n- **one**\n- two";
+        assert_eq!(description_text(markdown, false).unwrap(), markdown);
+        let rendered = description_text(markdown, true).unwrap();
+        assert!(rendered.contains("Notes") && rendered.contains("one") && rendered.contains("two"));
+        assert!(!rendered.contains("**") && !rendered.contains("# "));
+    }
+
+    #[test]
+    fn test_parse_editor_single_word() {
+        let (program, args) = parse_editor_command("vim").unwrap();
+        assert_eq!(program, "vim");
+        assert!(args.is_empty());
+    }
+
+    #[test]
+    fn test_parse_editor_with_arguments() {
+        let (program, args) = parse_editor_command("code --wait --new-window").unwrap();
+        assert_eq!(program, "code");
+        assert_eq!(args, vec!["--wait", "--new-window"]);
+    }
+
+    #[test]
+    fn test_parse_editor_quoted_program_path() {
+        let (program, args) = parse_editor_command("\"/opt/My Editor/bin/edit\" -w").unwrap();
+        assert_eq!(program, "/opt/My Editor/bin/edit");
+        assert_eq!(args, vec!["-w"]);
+    }
+
+    #[test]
+    fn test_parse_editor_empty() {
+        assert!(parse_editor_command("   ").is_err());
+    }
+
+    #[test]
+    fn test_parse_editor_unterminated_quote() {
+        assert!(parse_editor_command("code \"--wait").is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use anyhow::{Context, Result};
+use chrono::{Days, Utc};
+use colored::*;
+use onyx_core::AllTasksOptions;
+use onyx_core::upcoming::{group_upcoming, UpcomingDay};
+use crate::output;
+use crate::commands::{display_timezone, get_repository};
+
+pub fn execute(days: u32, workspace: Option<String>) -> Result<()> {
+    let (repo, _workspace_name) = get_repository(workspace)?;
+
+    let tasks = repo.all_tasks(&AllTasksOptions { include_completed: false })
+        .context("Failed to get tasks")?;
+    let tz = display_timezone()?;
+    let now = Utc::now();
+    let groups = group_upcoming(tasks, now, days, &tz);
+
+    output::header(&format!("Due in the next {} day{}", days, if days == 1 { "" } else { "s" }));
+    if groups.is_empty() {
+        output::item("No tasks");
+        return Ok(());
+    }
+
+    let today = now.with_timezone(&tz).date_naive();
+    let tomorrow = today.checked_add_days(Days::new(1));
+    for group in &groups {
+        output::blank();
+        let heading = match group.day {
+            UpcomingDay::Overdue => "Overdue".red().to_string(),
+            UpcomingDay::Day(day) if day == today => "Today".to_string(),
+            UpcomingDay::Day(day) if Some(day) == tomorrow => "Tomorrow".to_string(),
+            UpcomingDay::Day(day) => day.format("%A, %b %-d").to_string(),
+        };
+        output::header(&heading);
+        for (list_title, task) in &group.tasks {
+            // Overdue tasks show their date; the others' day is the heading
+            let when = match (group.day, task.due_date) {
+                (UpcomingDay::Overdue, Some(due)) => {
+                    format!(" (due: {})", output::format_due(due, task.has_time, "%Y-%m-%d", &tz)).red().to_string()
+                }
+                (UpcomingDay::Day(_), Some(due)) if task.has_time => {
+                    format!(" {}", due.with_timezone(&tz).format("%H:%M")).yellow().to_string()
+                }
+                _ => String::new(),
+            };
+            output::item(&format!(
+                "[ ] {}{} {} {}",
+                task.title,
+                when,
+                format!("({})", list_title).dimmed(),
+                task.id.to_string().dimmed()
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -1,45 +1,40 @@
 use anyhow::{Context, Result};
-use onyx_core::{TaskRepository, WorkspaceConfig};
-use std::path::PathBuf;
+use onyx_core::{TaskList, TaskRepository, WorkspaceConfig};
+use std::path::{Path, PathBuf};
 use colored::*;
 use crate::output;
-use crate::commands::{load_config, save_config};
-
-pub fn add(name: String, path: String) -> Result<()> {
-    let path_buf = PathBuf::from(path);
-    let path_buf = if path_buf.is_relative() {
-        std::env::current_dir()?.join(path_buf)
-    } else {
-        path_buf
-    };
-
-    // Initialize the repository
-    let mut repo = TaskRepository::init(path_buf.clone())
-        .context("Failed to initialize tasks folder")?;
-
-    // Create default list if it doesn't exist
-    let lists = repo.get_lists().context("Failed to get lists")?;
-    if !lists.iter().any(|l| l.title == "My Tasks") {
-        repo.create_list("My Tasks".to_string())
-            .context("Failed to create default list")?;
-    }
+use crate::commands::{check_path_unclaimed, display_timezone, init_workspace_folder, load_config, resolve_workspace, save_config, with_workspace_path};
 
+pub fn add(name: String, path: String, force: bool, default_list: String) -> Result<()> {
     // Load config
     let mut config = load_config()?;
 
-    // Check if workspace already exists
+    // Check if workspace already exists, before the path (which creates the folder)
     if config.get_workspace(&name).is_some() {
         anyhow::bail!("Workspace '{}' already exists", name);
     }
+    let (path_buf, setup) = with_workspace_path(path, |path_buf| {
+        check_path_unclaimed(&config, &path_buf, &name, force)?;
 
-    // Add workspace
-    config.add_workspace(name.clone(), WorkspaceConfig::new(path_buf.clone()));
+        // Initialize the repository, or adopt the workspace already there
+        let setup = init_workspace_folder(&path_buf, &default_list)?;
 
-    // Save config
-    save_config(&config)?;
+        // Add workspace
+        config.add_workspace(name.clone(), WorkspaceConfig::new(path_buf.clone()));
 
-    output::success(&format!("Added workspace \"{}\" at {}", name, path_buf.display()));
-    output::success("Created default list \"My Tasks\"");
+        // Save config
+        save_config(&config)?;
+        Ok((path_buf, setup))
+    })?;
+
+    if setup.adopted {
+        output::success(&format!("Added existing workspace at {} as \"{}\"", path_buf.display(), name));
+    } else {
+        output::success(&format!("Added workspace \"{}\" at {}", name, path_buf.display()));
+    }
+    if setup.created_default_list {
+        output::success(&format!("Created default list \"{}\"", default_list));
+    }
 
     Ok(())
 }
@@ -69,6 +64,40 @@ pub fn list() -> Result<()> {
     Ok(())
 }
 
+pub fn info(name: Option<String>) -> Result<()> {
+    let config = load_config()?;
+    let (name, workspace_config) = resolve_workspace(&config, name)?;
+    let repo = TaskRepository::new(workspace_config.path.clone())
+        .context(format!("Failed to open workspace '{}'", name))?;
+    let metadata = repo.get_root_metadata()?;
+    let tz = display_timezone()?;
+    let now = chrono::Utc::now();
+    let when = |at: chrono::DateTime<chrono::Utc>| {
+        format!("{} ({})", at.with_timezone(&tz).format("%Y-%m-%d %H:%M"), output::humanize_relative(at, now))
+    };
+
+    let lists = repo.get_lists()?;
+    let total: usize = lists.iter().map(|list| list.tasks.len()).sum();
+    let completed: usize = lists.iter().map(TaskList::completed_count).sum();
+
+    output::header(&format!("Workspace \"{}\"", name));
+    output::detail("Path", &workspace_config.path.display().to_string());
+    output::detail("Created", &when(metadata.created_at));
+    output::detail("Last modified", &when(metadata.updated_at));
+    output::detail("Lists", &lists.len().to_string());
+    output::detail("Tasks", &format!("{} ({} open, {} completed)", total, total - completed, completed));
+    match &workspace_config.webdav_url {
+        Some(url) => {
+            output::detail("WebDAV", url);
+            let last_sync = workspace_config.last_sync.map_or_else(|| "never".to_string(), when);
+            output::detail("Last sync", &last_sync);
+        }
+        None => output::detail("WebDAV", "not configured"),
+    }
+
+    Ok(())
+}
+
 pub fn switch(name: String) -> Result<()> {
     let mut config = load_config()?;
 
@@ -95,14 +124,7 @@ pub fn remove(name: String) -> Result<()> {
 
     // Confirm
     output::warning("This will delete workspace config (files remain on disk)");
-    print!("Continue? (y/n): ");
-    use std::io::{self, Write};
-    io::stdout().flush()?;
-
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-
-    if input.trim().to_lowercase() != "y" {
+    if !output::confirm("Continue?")? {
         output::info("Cancelled");
         return Ok(());
     }
@@ -115,31 +137,31 @@ pub fn remove(name: String) -> Result<()> {
     Ok(())
 }
 
-pub fn retarget(name: String, path: String) -> Result<()> {
-    let path_buf = PathBuf::from(path);
-    let path_buf = if path_buf.is_relative() {
-        std::env::current_dir()?.join(path_buf)
-    } else {
-        path_buf
-    };
-
+pub fn retarget(name: String, path: String, force: bool) -> Result<()> {
     let mut config = load_config()?;
 
-    // Verify workspace exists
+    // Verify workspace exists, before the path (which creates the folder)
     if config.get_workspace(&name).is_none() {
         anyhow::bail!("Workspace '{}' not found", name);
     }
+    let path_buf = with_workspace_path(path, |path_buf| {
+        check_path_unclaimed(&config, &path_buf, &name, force)?;
 
-    // Update path
-    config.add_workspace(name.clone(), WorkspaceConfig::new(path_buf.clone()));
-    save_config(&config)?;
+        // Update path
+        config.add_workspace(name.clone(), WorkspaceConfig::new(path_buf.clone()));
+        save_config(&config)?;
+        Ok(path_buf)
+    })?;
 
     output::success(&format!("Workspace \"{}\" now points to {}", name, path_buf.display()));
 
     Ok(())
 }
 
-pub fn migrate(name: String, new_path: String) -> Result<()> {
+/// Move a workspace's files to `new_path` and repoint it there. With `dry_run`, only list
+/// what would move. Files move one at a time (see `move_tree`); a failure moves the ones
+/// already moved back.
+pub fn migrate(name: String, new_path: String, dry_run: bool) -> Result<()> {
     let new_path_buf = PathBuf::from(new_path);
     let new_path_buf = if new_path_buf.is_relative() {
         std::env::current_dir()?.join(new_path_buf)
@@ -150,23 +172,10 @@ pub fn migrate(name: String, new_path: String) -> Result<()> {
     let mut config = load_config()?;
 
     // Get current workspace config
-    let old_path = config.get_workspace(&name)
+    let mut workspace_config = config.get_workspace(&name)
         .ok_or_else(|| anyhow::anyhow!("Workspace '{}' not found", name))?
-        .path.clone();
-
-    // Confirm
-    output::warning(&format!("This will move all files from {} to {}", old_path.display(), new_path_buf.display()));
-    print!("Continue? (y/n): ");
-    use std::io::{self, Write};
-    io::stdout().flush()?;
-
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-
-    if input.trim().to_lowercase() != "y" {
-        output::info("Cancelled");
-        return Ok(());
-    }
+        .clone();
+    let old_path = workspace_config.path.clone();
 
     // Validate destination
     if old_path == new_path_buf {
@@ -176,60 +185,308 @@ pub fn migrate(name: String, new_path: String) -> Result<()> {
         anyhow::bail!("Destination directory '{}' already contains files", new_path_buf.display());
     }
 
+    TaskRepository::check_removable_folder(&old_path)
+        .context(format!("Refusing to migrate workspace '{}'", name))?;
+
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(&old_path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::result::Result<_, _>>()?;
+    entries.sort();
+
+    if dry_run {
+        output::header(&format!("Would move {} items from {} to {}", entries.len(), old_path.display(), new_path_buf.display()));
+        for entry in &entries {
+            let name = entry.file_name().unwrap_or_default().to_string_lossy();
+            if entry.is_dir() {
+                let files = files_under(entry)?.len();
+                output::item(&format!("{}/ ({} file{})", name, files, if files == 1 { "" } else { "s" }));
+            } else {
+                output::item(&name);
+            }
+        }
+        output::info("Dry run: nothing was moved.");
+        return Ok(());
+    }
+
+    // Confirm
+    output::warning(&format!("This will move all files from {} to {}", old_path.display(), new_path_buf.display()));
+    if !output::confirm("Continue?")? {
+        output::info("Cancelled");
+        return Ok(());
+    }
+
     // Create destination directory
     std::fs::create_dir_all(&new_path_buf)?;
+    let new_path_buf = std::fs::canonicalize(&new_path_buf)?;
 
-    // Move files, tracking what was moved for rollback
+    // Move files; a failure moves the ones already moved back
     output::info("Moving files...");
-    let entries: Vec<_> = std::fs::read_dir(&old_path)?
-        .collect::<std::result::Result<Vec<_>, _>>()?;
-    let mut moved: Vec<(std::path::PathBuf, std::path::PathBuf)> = Vec::new();
+    let moved = move_tree(&old_path, &entries, &new_path_buf, |from, to| std::fs::rename(from, to))?;
 
-    let move_result: Result<()> = (|| {
-        for entry in &entries {
-            let file_name = entry.file_name();
-            let dest = new_path_buf.join(&file_name);
+    // Remove old directory if empty
+    if old_path.exists() && old_path.read_dir()?.next().is_none() {
+        std::fs::remove_dir(&old_path)?;
+    }
 
-            if entry.path().is_dir() {
-                let mut options = fs_extra::dir::CopyOptions::new();
-                options.copy_inside = true;
-                fs_extra::dir::move_dir(entry.path(), &new_path_buf, &options)?;
-            } else {
-                std::fs::rename(entry.path(), &dest)?;
+    // Update config, keeping the workspace's sync settings
+    workspace_config.path = new_path_buf.clone();
+    config.add_workspace(name.clone(), workspace_config);
+    save_config(&config)?;
+
+    output::success(&format!("Migrated {} files to {}", moved, new_path_buf.display()));
+    output::success(&format!("Workspace \"{}\" now points to {}", name, new_path_buf.display()));
+
+    Ok(())
+}
+
+/// Move `entries` (the top level of `src_dir`) into `dest_dir` file by file. Folders are
+/// recreated at the destination and each file goes through `move_file`. A list folder is
+/// removed from the source only once the destination holds as many files as it had, and
+/// progress is reported per list. On failure every file already moved is moved back.
+/// Returns how many files were moved.
+fn move_tree(
+    src_dir: &Path,
+    entries: &[PathBuf],
+    dest_dir: &Path,
+    rename: impl Fn(&Path, &Path) -> std::io::Result<()>,
+) -> Result<usize> {
+    let mut moved: Vec<PathBuf> = Vec::new();
+    let result = (|| -> Result<()> {
+        for entry in entries {
+            let name = entry.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            if !entry.is_dir() {
+                move_file(entry, &dest_dir.join(&name), &rename)?;
+                moved.push(entry.strip_prefix(src_dir)?.to_path_buf());
+                output::item(&format!("Moved {}", name));
+                continue;
+            }
+
+            let files = files_under(entry)?;
+            for dir in dirs_under(entry)? {
+                std::fs::create_dir_all(dest_dir.join(dir.strip_prefix(src_dir)?))?;
             }
-            moved.push((entry.path(), dest));
-            output::item(&format!("Moved {}", file_name.to_string_lossy()));
+            for file in &files {
+                let relative = file.strip_prefix(src_dir)?;
+                move_file(file, &dest_dir.join(relative), &rename)?;
+                moved.push(relative.to_path_buf());
+            }
+            let arrived = files_under(&dest_dir.join(&name))?.len();
+            if arrived != files.len() {
+                anyhow::bail!("{} has {} of {} files after moving", name, arrived, files.len());
+            }
+            std::fs::remove_dir_all(entry)?;
+            output::item(&format!("Moved list {} ({} file{})", name, files.len(), if files.len() == 1 { "" } else { "s" }));
         }
         Ok(())
     })();
 
-    if let Err(e) = move_result {
+    if let Err(e) = result {
         output::error(&format!("Migration failed: {}. Rolling back...", e));
-        for (src, dest) in moved.into_iter().rev() {
-            if dest.exists() {
-                if dest.is_dir() {
-                    let mut options = fs_extra::dir::CopyOptions::new();
-                    options.copy_inside = true;
-                    let _ = fs_extra::dir::move_dir(&dest, &old_path, &options);
-                } else {
-                    let _ = std::fs::rename(&dest, &src);
-                }
+        for relative in moved.iter().rev() {
+            let (src, dest) = (src_dir.join(relative), dest_dir.join(relative));
+            let back = src.parent().map_or(Ok(()), std::fs::create_dir_all)
+                .map_err(anyhow::Error::from)
+                .and_then(|()| move_file(&dest, &src, &rename));
+            if let Err(back) = back {
+                output::error(&format!("Could not move {} back: {}", dest.display(), back));
+            }
+        }
+        for entry in entries.iter().filter(|entry| entry.is_dir()) {
+            let created = dest_dir.join(entry.file_name().unwrap_or_default());
+            if created.is_dir() && files_under(&created).is_ok_and(|files| files.is_empty()) {
+                let _ = std::fs::remove_dir_all(created);
             }
         }
         anyhow::bail!("Migration failed and was rolled back: {}", e);
     }
+    Ok(moved.len())
+}
 
-    // Remove old directory if empty
-    if old_path.exists() && old_path.read_dir()?.next().is_none() {
-        std::fs::remove_dir(&old_path)?;
+/// Every file below `dir`, at any depth, sorted.
+fn files_under(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(files_under(&path)?);
+        } else {
+            files.push(path);
+        }
     }
+    files.sort();
+    Ok(files)
+}
 
-    // Update config
-    config.add_workspace(name.clone(), WorkspaceConfig::new(new_path_buf.clone()));
-    save_config(&config)?;
+/// `dir` and every folder below it, parents before children.
+fn dirs_under(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut dirs = vec![dir.to_path_buf()];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            dirs.extend(dirs_under(&path)?);
+        }
+    }
+    Ok(dirs)
+}
 
-    output::success(&format!("Migrated {} items to {}", moved.len(), new_path_buf.display()));
-    output::success(&format!("Workspace \"{}\" now points to {}", name, new_path_buf.display()));
+/// Whether a rename failed because source and destination are on different filesystems
+/// (`EXDEV` on Unix, `ERROR_NOT_SAME_DEVICE` on Windows).
+fn is_cross_device(e: &std::io::Error) -> bool {
+    const EXDEV: i32 = if cfg!(windows) { 17 } else { 18 };
+    e.kind() == std::io::ErrorKind::CrossesDevices || e.raw_os_error() == Some(EXDEV)
+}
 
+/// Move the file `src` to `dest` with `rename`. When that fails because the two are on
+/// different filesystems, copy `src`, check the copy matches byte for byte, and only then
+/// delete `src`; a bad copy is removed and `src` is left in place.
+fn move_file(src: &Path, dest: &Path, rename: &impl Fn(&Path, &Path) -> std::io::Result<()>) -> Result<()> {
+    match rename(src, dest) {
+        Ok(()) => return Ok(()),
+        Err(e) if is_cross_device(&e) => {}
+        Err(e) => return Err(e).with_context(|| format!("Failed to move {}", src.display())),
+    }
+
+    if dest.exists() {
+        anyhow::bail!("Failed to copy {}: {} already exists", src.display(), dest.display());
+    }
+    let copied = std::fs::copy(src, dest).and_then(|_| {
+        if std::fs::read(src)? == std::fs::read(dest)? {
+            Ok(())
+        } else {
+            Err(std::io::Error::other("copy does not match the original"))
+        }
+    });
+    if let Err(e) = copied {
+        let _ = std::fs::remove_file(dest);
+        return Err(e).with_context(|| format!("Failed to copy {} to {}", src.display(), dest.display()));
+    }
+    std::fs::remove_file(src)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use tempfile::TempDir;
+
+    fn cross_device(_: &Path, _: &Path) -> io::Result<()> {
+        Err(io::Error::from(io::ErrorKind::CrossesDevices))
+    }
+
+    fn top_level(dir: &Path) -> Vec<PathBuf> {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(dir).unwrap().map(|e| e.unwrap().path()).collect();
+        entries.sort();
+        entries
+    }
+
+    #[test]
+    fn test_move_file_falls_back_to_copy_across_devices() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("note.md");
+        std::fs::write(&src, "note").unwrap();
+
+        let dest = temp_dir.path().join("moved.md");
+        move_file(&src, &dest, &cross_device).unwrap();
+        assert!(!src.exists());
+        assert_eq!(std::fs::read_to_string(dest).unwrap(), "note");
+    }
+
+    #[test]
+    fn test_move_file_keeps_source_on_other_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("note.md");
+        std::fs::write(&src, "note").unwrap();
+        let dest = temp_dir.path().join("moved.md");
+
+        let denied = |_: &Path, _: &Path| Err(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert!(move_file(&src, &dest, &denied).is_err());
+        assert!(src.exists());
+        assert!(!dest.exists());
+
+        // Copy fallback refuses to overwrite, so the source stays put
+        std::fs::write(&dest, "other").unwrap();
+        assert!(move_file(&src, &dest, &cross_device).is_err());
+        assert_eq!(std::fs::read_to_string(&src).unwrap(), "note");
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "other");
+    }
+
+    #[test]
+    fn test_migrate_moves_every_list_intact() {
+        let temp_dir = TempDir::new().unwrap();
+        let old = temp_dir.path().join("old");
+        let new = temp_dir.path().join("new");
+        std::fs::create_dir(&new).unwrap();
+        std::fs::create_dir_all(&old).unwrap();
+        std::fs::write(old.join(".metadata.json"), "{}").unwrap();
+        let lists = [("My Tasks", vec!["Milk", "Eggs"]), ("Work", vec!["Report", "Review", "Plan"]), ("Empty", vec![])];
+        for (list, tasks) in &lists {
+            std::fs::create_dir(old.join(list)).unwrap();
+            std::fs::write(old.join(list).join(".listdata.json"), format!("{{\"title\":\"{}\"}}", list)).unwrap();
+            for task in tasks {
+                std::fs::write(old.join(list).join(format!("{}.md", task)), format!("---\n---\n{}", task)).unwrap();
+            }
+        }
+
+        assert_eq!(move_tree(&old, &top_level(&old), &new, |from, to| std::fs::rename(from, to)).unwrap(), 9);
+
+        assert!(top_level(&old).is_empty());
+        assert_eq!(std::fs::read_to_string(new.join(".metadata.json")).unwrap(), "{}");
+        for (list, tasks) in &lists {
+            assert_eq!(files_under(&new.join(list)).unwrap().len(), tasks.len() + 1);
+            assert_eq!(std::fs::read_to_string(new.join(list).join(".listdata.json")).unwrap(), format!("{{\"title\":\"{}\"}}", list));
+            for task in tasks {
+                assert_eq!(std::fs::read_to_string(new.join(list).join(format!("{}.md", task))).unwrap(), format!("---\n---\n{}", task));
+            }
+        }
+    }
+
+    #[test]
+    fn test_migrate_moves_workspace_across_devices() {
+        // Regression: every rename fails with EXDEV, as it does for an external drive
+        let exdev = |_: &Path, _: &Path| Err(io::Error::from_raw_os_error(if cfg!(windows) { 17 } else { 18 }));
+        let temp_dir = TempDir::new().unwrap();
+        let old = temp_dir.path().join("old");
+        std::fs::create_dir_all(old.join("My Tasks/Sub")).unwrap();
+        std::fs::write(old.join(".metadata.json"), "{}").unwrap();
+        std::fs::write(old.join("My Tasks/.listdata.json"), "{}").unwrap();
+        std::fs::write(old.join("My Tasks/Milk.md"), "milk").unwrap();
+        std::fs::write(old.join("My Tasks/Sub/Eggs.md"), "eggs").unwrap();
+        let new = temp_dir.path().join("new");
+        std::fs::create_dir(&new).unwrap();
+
+        assert_eq!(move_tree(&old, &top_level(&old), &new, exdev).unwrap(), 4);
+
+        assert!(top_level(&old).is_empty());
+        assert_eq!(std::fs::read_to_string(new.join(".metadata.json")).unwrap(), "{}");
+        assert_eq!(std::fs::read_to_string(new.join("My Tasks/.listdata.json")).unwrap(), "{}");
+        assert_eq!(std::fs::read_to_string(new.join("My Tasks/Milk.md")).unwrap(), "milk");
+        assert_eq!(std::fs::read_to_string(new.join("My Tasks/Sub/Eggs.md")).unwrap(), "eggs");
+    }
+
+    #[test]
+    fn test_migrate_rolls_back_on_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let old = temp_dir.path().join("old");
+        std::fs::create_dir_all(old.join("Work")).unwrap();
+        std::fs::write(old.join(".metadata.json"), "{}").unwrap();
+        std::fs::write(old.join("Work/Draft.md"), "draft").unwrap();
+        std::fs::write(old.join("Work/Report.md"), "report").unwrap();
+        let new = temp_dir.path().join("new");
+        std::fs::create_dir(&new).unwrap();
+
+        // Moving Report.md fails; the metadata and Draft.md go back
+        let flaky = |from: &Path, to: &Path| {
+            if from.ends_with("Work/Report.md") {
+                Err(io::Error::from(io::ErrorKind::PermissionDenied))
+            } else {
+                std::fs::rename(from, to)
+            }
+        };
+        assert!(move_tree(&old, &top_level(&old), &new, flaky).is_err());
+        assert_eq!(std::fs::read_to_string(old.join(".metadata.json")).unwrap(), "{}");
+        assert_eq!(std::fs::read_to_string(old.join("Work/Draft.md")).unwrap(), "draft");
+        assert_eq!(std::fs::read_to_string(old.join("Work/Report.md")).unwrap(), "report");
+        assert!(top_level(&new).is_empty());
+    }
+}
@@ -1,14 +1,26 @@
 mod commands;
 mod output;
 
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, Utc};
+use clap::{CommandFactory, Parser, Subcommand};
 use commands::*;
+use onyx_core::config::{AppConfig, CompletedVisibility};
 
 #[derive(Parser)]
 #[command(name = "onyx")]
 #[command(about = "A local-first, cross-platform tasks application", long_about = None)]
 struct Cli {
+    /// When to use colored output
+    #[arg(long, value_enum, global = true, default_value_t = output::ColorMode::Auto)]
+    color: output::ColorMode,
+
+    /// Answer yes to confirmation prompts (list delete also needs --force)
+    #[arg(short = 'y', long, global = true, env = "ONYX_ASSUME_YES", value_parser = clap::builder::FalseyValueParser::new())]
+    yes: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -22,6 +34,18 @@ enum Commands {
         /// Name of the workspace
         #[arg(short, long)]
         name: String,
+        /// Register it even if another workspace already uses this folder
+        #[arg(long)]
+        force: bool,
+        /// Name of the first list in a fresh workspace
+        #[arg(long, default_value = DEFAULT_LIST_NAME)]
+        default_list: String,
+        /// Also set up WebDAV sync against this URL (prompts for the password)
+        #[arg(long)]
+        webdav_url: Option<String>,
+        /// WebDAV username (prompted for if omitted)
+        #[arg(long, requires = "webdav_url")]
+        webdav_username: Option<String>,
     },
 
     /// Manage workspaces
@@ -33,42 +57,132 @@ enum Commands {
     List(ListCommands),
 
     /// Add a new task
-    Add {
+    Add(task::AddArgs),
+
+    /// Add a task from one line, e.g. "Buy milk tomorrow #groceries !high"
+    Quick {
+        /// Title with an optional due word (today, tomorrow, a weekday or YYYY-MM-DD),
+        /// #tags and a !low/!medium/!high priority
+        input: String,
+        /// List to add task to (name or @index; defaults to the last list shown or added to)
+        #[arg(short, long)]
+        list: Option<String>,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Add a task to the workspace's inbox list (or where `add` would put it if none is set)
+    Inbox {
         /// Task title
         title: String,
-        /// List to add task to
+        /// Workspace to use
         #[arg(short, long)]
-        list: Option<String>,
-        /// Due date (ISO 8601 format: YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS)
+        workspace: Option<String>,
+    },
+
+    /// Show a task's details and description
+    Show {
+        /// Task ID
+        task_id: String,
+        /// Render the markdown description for the terminal instead of printing it raw
         #[arg(short, long)]
-        due: Option<String>,
+        render: bool,
         /// Workspace to use
         #[arg(short, long)]
         workspace: Option<String>,
     },
 
-    /// Mark a task as complete
+    /// Mark tasks as complete
     Complete {
+        /// Task IDs (`-` reads them from stdin, one per line)
+        #[arg(required = true)]
+        task_ids: Vec<String>,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Delete tasks
+    Delete {
+        /// Task IDs (`-` reads them from stdin, one per line)
+        #[arg(required = true)]
+        task_ids: Vec<String>,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Edit a task
+    Edit {
         /// Task ID
         task_id: String,
+        /// Editor command to use (overrides config and $EDITOR)
+        #[arg(long)]
+        editor: Option<String>,
+        /// Edit the stored markdown file, frontmatter included
+        #[arg(long)]
+        raw: bool,
         /// Workspace to use
         #[arg(short, long)]
         workspace: Option<String>,
     },
 
-    /// Delete a task
-    Delete {
+    /// Assign a task to a person
+    Assign {
         /// Task ID
         task_id: String,
+        /// Who to assign it to (omit to unassign)
+        name: Option<String>,
         /// Workspace to use
         #[arg(short, long)]
         workspace: Option<String>,
     },
 
-    /// Edit a task
-    Edit {
+    /// Put a short label, like an emoji, before a task's title
+    Label {
         /// Task ID
         task_id: String,
+        /// The label (omit to remove it)
+        label: Option<String>,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Mark a task as blocked until another task is completed
+    Block {
+        /// Task ID of the task that has to wait
+        task_id: String,
+        /// Task ID of the task it waits on
+        #[arg(long)]
+        on: String,
+        /// Stop waiting on that task instead
+        #[arg(long)]
+        remove: bool,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Set, read or remove custom key/value fields on a task
+    #[command(subcommand)]
+    Field(FieldCommands),
+
+    /// Add a reminder to a task
+    Remind {
+        /// Task ID
+        task_id: String,
+        /// When to remind (YYYY-MM-DD for the start of that day, or ISO 8601 datetime)
+        #[arg(long)]
+        at: String,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Send desktop notifications for due reminders (meant for cron)
+    Notify {
         /// Workspace to use
         #[arg(short, long)]
         workspace: Option<String>,
@@ -78,11 +192,166 @@ enum Commands {
     #[command(subcommand)]
     Group(GroupCommands),
 
+    /// Manage the allowed tags that catch typos like #wrk for #work
+    #[command(subcommand)]
+    Tags(TagsCommands),
+
+    /// Import tasks from external files
+    Import {
+        /// Source to import from
+        source: String,
+        /// Format of the source
+        #[arg(short, long, value_enum)]
+        format: import::ImportFormat,
+        /// List to import tasks into (name or @index)
+        #[arg(short, long)]
+        list: Option<String>,
+        /// Keep the task IDs from a json/ndjson export (e.g. when restoring) instead of generating new ones
+        #[arg(long)]
+        preserve_ids: bool,
+        /// What to do when a preserved ID is already used by another task
+        #[arg(long, value_enum, default_value_t = import::OnCollision::Error, requires = "preserve_ids")]
+        on_collision: import::OnCollision,
+        /// What to do with lists that already exist (json/ndjson): add to them, leave them
+        /// alone, or replace their tasks
+        #[arg(long, value_enum, default_value_t = import::OnExisting::Merge)]
+        on_existing: import::OnExisting,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Export every task as JSON
+    Export {
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = export::ExportFormat::Json)]
+        format: export::ExportFormat,
+        /// Only export this list (or @index), without its sublists
+        #[arg(short, long)]
+        list: Option<String>,
+        /// File to write (defaults to stdout)
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Show recently updated tasks across all lists
+    Recent {
+        /// How many days back to look
+        #[arg(short, long, default_value_t = 7)]
+        days: u32,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Find tasks across all lists by status, tags, due date and more
+    Find(find::FindArgs),
+
+    /// Build the search index used by `find --text` (searches keep it up to date afterwards)
+    Reindex {
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Show every task in the workspace as one flat list, with its list name
+    All {
+        /// Include completed tasks
+        #[arg(short, long)]
+        completed: bool,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Show open tasks due in the next few days, grouped by day, overdue ones first
+    Upcoming {
+        /// How many days ahead to look, today included
+        #[arg(short, long, default_value_t = 7)]
+        days: u32,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Show tasks assigned to you (set "my_name" in config) across all lists
+    Mine {
+        /// Also include tasks nobody is assigned to
+        #[arg(short, long)]
+        unassigned: bool,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Show consecutive-day completion streaks for a list
+    Streak {
+        /// Name of the list (or @index)
+        #[arg(short, long)]
+        list: String,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Check workspace metadata and repair task/list order
+    Doctor {
+        /// Only report problems, don't change anything
+        #[arg(long)]
+        check_only: bool,
+        /// Register folders without list metadata as lists, even if they hold no tasks yet
+        #[arg(long, conflicts_with = "check_only")]
+        adopt: bool,
+        /// Rename task files to match the workspace's filename_scheme setting
+        #[arg(long, conflicts_with = "check_only")]
+        rename_files: bool,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Archive the whole workspace to a zip file
+    Backup {
+        /// Zip file to write
+        #[arg(short, long)]
+        output: String,
+        /// Glob pattern (relative to the workspace root) to leave out; repeatable
+        #[arg(long)]
+        exclude: Vec<String>,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Unpack a backup zip into a new directory
+    Restore {
+        /// Backup zip file
+        archive: String,
+        /// Directory to restore into (must be missing or empty)
+        #[arg(short, long)]
+        path: String,
+        /// Register the restored directory as a workspace with this name
+        #[arg(short, long)]
+        name: Option<String>,
+    },
+
     /// Sync workspace with WebDAV server
+    #[command(args_conflicts_with_subcommands = true)]
     Sync {
+        #[command(subcommand)]
+        command: Option<SyncCommands>,
         /// Run initial setup (URL, credentials)
         #[arg(long)]
         setup: bool,
+        /// Send WebDAV requests through this proxy (with --setup; "" clears it)
+        #[arg(long, requires = "setup")]
+        proxy: Option<String>,
+        /// Also trust this PEM root certificate for the server (with --setup; "" clears it)
+        #[arg(long, requires = "setup")]
+        ca_cert: Option<String>,
         /// Push-only sync (upload local changes)
         #[arg(long, conflicts_with_all = ["pull", "setup", "status"])]
         push: bool,
@@ -95,6 +364,53 @@ enum Commands {
         /// Show status for all workspaces (with --status)
         #[arg(long, requires = "status")]
         all: bool,
+        /// Only push files modified within this window, e.g. 30m, 1h, 2d (with --push)
+        #[arg(long, requires = "push", value_parser = sync::parse_since)]
+        since: Option<chrono::Duration>,
+        /// Print the sync result as JSON instead of progress and a summary
+        #[arg(long, conflicts_with_all = ["setup", "status"])]
+        json: bool,
+        /// Read the WebDAV password from this file instead of ONYX_WEBDAV_PASS or the keychain
+        #[arg(long, conflicts_with_all = ["setup", "status"])]
+        password_file: Option<String>,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SyncCommands {
+    /// Verify the WebDAV setup end to end without syncing anything
+    Check {
+        /// Read the WebDAV password from this file instead of the keychain
+        #[arg(long)]
+        password_file: Option<String>,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+    /// Push after every command that changes tasks or lists (off by default)
+    Auto {
+        #[arg(value_enum)]
+        state: Toggle,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+    /// List local versions set aside by conflicts the remote side won
+    Conflicts {
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+    /// Settle a conflict by keeping one version and removing the backup
+    Resolve {
+        /// The conflicted file or its backup, relative to the workspace
+        file: String,
+        /// Version to keep
+        #[arg(long, value_enum)]
+        keep: sync::KeepSide,
         /// Workspace to use
         #[arg(short, long)]
         workspace: Option<String>,
@@ -109,11 +425,23 @@ enum WorkspaceCommands {
         name: String,
         /// Path to store tasks
         path: String,
+        /// Register it even if another workspace already uses this folder
+        #[arg(long)]
+        force: bool,
+        /// Name of the first list in a fresh workspace
+        #[arg(long, default_value = DEFAULT_LIST_NAME)]
+        default_list: String,
     },
 
     /// List all workspaces
     List,
 
+    /// Show details about a workspace
+    Info {
+        /// Name of the workspace (defaults to the current one)
+        name: Option<String>,
+    },
+
     /// Switch to a different workspace
     Switch {
         /// Name of the workspace
@@ -132,6 +460,9 @@ enum WorkspaceCommands {
         name: String,
         /// New path
         path: String,
+        /// Point it there even if another workspace already uses this folder
+        #[arg(long)]
+        force: bool,
     },
 
     /// Move workspace files to a new location
@@ -140,6 +471,9 @@ enum WorkspaceCommands {
         name: String,
         /// New path
         path: String,
+        /// List what would move without moving anything
+        #[arg(long)]
+        dry_run: bool,
     },
 }
 
@@ -149,6 +483,12 @@ enum ListCommands {
     Create {
         /// Name of the list
         name: String,
+        /// Create it as a sublist of this list (name or @index)
+        #[arg(short, long)]
+        parent: Option<String>,
+        /// Create it even if a list with the same name exists
+        #[arg(long)]
+        allow_duplicate: bool,
         /// Workspace to use
         #[arg(short, long)]
         workspace: Option<String>,
@@ -156,9 +496,130 @@ enum ListCommands {
 
     /// Show all tasks (or tasks in a specific list)
     Show {
-        /// Name of the list to show
+        /// Name of the list to show (or @index)
         #[arg(short, long)]
         list: Option<String>,
+        /// Only show tasks assigned to this person
+        #[arg(short, long)]
+        assignee: Option<String>,
+        /// Only show open tasks
+        #[arg(long, conflicts_with_all = ["completed", "all_statuses"])]
+        open: bool,
+        /// Only show completed tasks
+        #[arg(long, conflicts_with = "all_statuses")]
+        completed: bool,
+        /// Show open and completed tasks together
+        #[arg(long, visible_alias = "all")]
+        all_statuses: bool,
+        /// Order of tasks within each list
+        #[arg(long, value_enum, default_value_t = find::SortBy::List)]
+        sort: find::SortBy,
+        /// Show due dates relative to now ("in 2 days")
+        #[arg(short, long)]
+        relative: bool,
+        /// Script-friendly output: one `id<TAB>status<TAB>due<TAB>title` line per task, no headers or colors
+        #[arg(long, conflicts_with = "relative")]
+        porcelain: bool,
+        /// Only print how many tasks each list shows: one `count<TAB>title` line per list
+        #[arg(long, conflicts_with_all = ["relative", "porcelain"])]
+        count: bool,
+        /// Print one line per task from a template, e.g. "{id8} {status} {due} {title}"
+        /// (tokens: {id}, {id8}, {title}, {status}, {due}, {list})
+        #[arg(long, value_name = "TEMPLATE", conflicts_with_all = ["porcelain", "count"])]
+        format: Option<String>,
+        /// Leave out tasks that are waiting on an open task (see `onyx block`)
+        #[arg(long)]
+        hide_blocked: bool,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Show details and progress for a task list
+    Info {
+        /// Name of the list
+        name: String,
+        /// Show timestamps relative to now ("3 days ago")
+        #[arg(short, long)]
+        relative: bool,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Move all tasks of one list into another, keeping their order, and delete it
+    Merge {
+        /// List to merge and delete (name or @index)
+        source: String,
+        /// List to merge into (name or @index)
+        #[arg(long)]
+        into: String,
+        /// Keep tasks that are exact copies of one in the target too, under a numbered
+        /// title (same-titled tasks that differ are always kept that way)
+        #[arg(long)]
+        keep_duplicates: bool,
+        /// Show what the merge would do without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Mark every open task in a list as completed
+    Complete {
+        /// Name of the list (or @index)
+        name: String,
+        /// Show which tasks would be completed without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Set every completed task in a list back to open, to reuse it as a checklist
+    Reopen {
+        /// Name of the list (or @index)
+        name: String,
+        /// Show which tasks would be reopened without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Move the tasks of a list that match a filter into a new list
+    #[command(group(clap::ArgGroup::new("filter").required(true).multiple(true).args(["status", "tags", "due_before"])))]
+    Split {
+        /// List to take tasks from (name or @index)
+        source: String,
+        /// Name of the new list
+        #[arg(long)]
+        into: String,
+        /// Move only open or only completed tasks
+        #[arg(short, long, value_enum)]
+        status: Option<find::StatusFilter>,
+        /// Move tasks with this tag (repeatable; all must match)
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+        /// Move tasks due before this date (YYYY-MM-DD excludes that day) or datetime
+        #[arg(long)]
+        due_before: Option<String>,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Make a list the workspace's inbox, where `onyx inbox` adds tasks
+    SetInbox {
+        /// Name of the list (or @index)
+        #[arg(required_unless_present = "clear")]
+        name: Option<String>,
+        /// Unset the inbox instead
+        #[arg(long, conflicts_with = "name")]
+        clear: bool,
         /// Workspace to use
         #[arg(short, long)]
         workspace: Option<String>,
@@ -168,6 +629,79 @@ enum ListCommands {
     Delete {
         /// Name of the list to delete
         name: String,
+        /// Delete without asking (--yes alone still asks for this one)
+        #[arg(long)]
+        force: bool,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TagsCommands {
+    /// Show the allowed tags and whether they are enforced
+    List {
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+    /// Add tags to the allowed tags
+    Allow {
+        #[arg(required = true)]
+        tags: Vec<String>,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+    /// Remove tags from the allowed tags
+    Disallow {
+        #[arg(required = true)]
+        tags: Vec<String>,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+    /// Refuse tags outside the allowed ones (on) or only warn about them (off, the default)
+    Strict {
+        #[arg(value_enum)]
+        state: Toggle,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum FieldCommands {
+    /// Set a field, replacing any earlier value
+    Set {
+        /// Task ID
+        task_id: String,
+        /// Field name, e.g. "issue"
+        key: String,
+        /// The value to store
+        value: String,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+    /// Print a field's value
+    Get {
+        /// Task ID
+        task_id: String,
+        /// Field name
+        key: String,
+        /// Workspace to use
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+    /// Remove a field
+    Unset {
+        /// Task ID
+        task_id: String,
+        /// Field name
+        key: String,
         /// Workspace to use
         #[arg(short, long)]
         workspace: Option<String>,
@@ -178,7 +712,7 @@ enum ListCommands {
 enum GroupCommands {
     /// Enable group-by-due-date for a list
     Enable {
-        /// Name of the list
+        /// Name of the list (or @index)
         #[arg(short, long)]
         list: String,
         /// Workspace to use
@@ -188,7 +722,7 @@ enum GroupCommands {
 
     /// Disable group-by-due-date for a list
     Disable {
-        /// Name of the list
+        /// Name of the list (or @index)
         #[arg(short, long)]
         list: String,
         /// Workspace to use
@@ -197,69 +731,260 @@ enum GroupCommands {
     },
 }
 
+/// Whether `name` is one of onyx's own commands. Those always win over an alias.
+fn is_command(name: &str) -> bool {
+    Cli::command().find_subcommand(name).is_some()
+}
+
+/// `args` (program name first) with a leading alias from `aliases` replaced by the words
+/// it stands for, repeatedly, so an alias can start with another alias. In those words,
+/// `{today}` and `{tomorrow}` become dates counted from `today`. An alias that leads back
+/// to itself is an error rather than a loop.
+fn expand_aliases(mut args: Vec<OsString>, aliases: &BTreeMap<String, String>, today: NaiveDate) -> Result<Vec<OsString>> {
+    let tomorrow = today.succ_opt().unwrap_or(today);
+    let mut chain: Vec<String> = Vec::new();
+    while let Some(name) = args.get(1).and_then(|arg| arg.to_str()).map(str::to_string) {
+        if is_command(&name) {
+            break;
+        }
+        let Some(expansion) = aliases.get(&name) else { break };
+        if chain.contains(&name) {
+            chain.push(name);
+            anyhow::bail!("Alias loop: {}", chain.join(" -> "));
+        }
+        let words = shell_words::split(expansion)
+            .with_context(|| format!("Invalid alias '{}': {}", name, expansion))?;
+        args.splice(1..2, words.into_iter().map(|word| {
+            OsString::from(word
+                .replace("{today}", &today.to_string())
+                .replace("{tomorrow}", &tomorrow.to_string()))
+        }));
+        chain.push(name);
+    }
+    Ok(args)
+}
+
+/// The command line with config aliases expanded. The config is only read when the first
+/// argument isn't a command; if it can't be read, arguments are left for clap to report.
+fn command_line() -> Result<Vec<OsString>> {
+    let args: Vec<OsString> = std::env::args_os().collect();
+    match args.get(1).and_then(|arg| arg.to_str()) {
+        Some(first) if !first.starts_with('-') && !is_command(first) => {}
+        _ => return Ok(args),
+    }
+    let Ok(config) = AppConfig::load_from_file(&get_config_path()) else {
+        return Ok(args);
+    };
+    if config.aliases.is_empty() {
+        return Ok(args);
+    }
+    let today = Utc::now().with_timezone(&config.resolve_timezone()?).date_naive();
+    expand_aliases(args, &config.aliases, today)
+}
+
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let cli = Cli::parse_from(command_line()?);
+    cli.color.apply();
+    output::set_assume_yes(cli.yes);
 
-    match cli.command {
-        Commands::Init { path, name } => {
-            init::execute(path, name)?;
+    let changed: Changed = match cli.command {
+        Commands::Init { path, name, force, default_list, webdav_url, webdav_username } => {
+            init::execute(path, name, force, default_list, webdav_url, webdav_username)?;
+            None
         }
         Commands::Workspace(cmd) => match cmd {
-            WorkspaceCommands::Add { name, path } => {
-                workspace::add(name, path)?;
+            WorkspaceCommands::Add { name, path, force, default_list } => {
+                workspace::add(name, path, force, default_list)?;
+                None
             }
             WorkspaceCommands::List => {
                 workspace::list()?;
+                None
+            }
+            WorkspaceCommands::Info { name } => {
+                workspace::info(name)?;
+                None
             }
             WorkspaceCommands::Switch { name } => {
                 workspace::switch(name)?;
+                None
             }
             WorkspaceCommands::Remove { name } => {
                 workspace::remove(name)?;
+                None
             }
-            WorkspaceCommands::Retarget { name, path } => {
-                workspace::retarget(name, path)?;
+            WorkspaceCommands::Retarget { name, path, force } => {
+                workspace::retarget(name, path, force)?;
+                None
             }
-            WorkspaceCommands::Migrate { name, path } => {
-                workspace::migrate(name, path)?;
+            WorkspaceCommands::Migrate { name, path, dry_run } => {
+                workspace::migrate(name, path, dry_run)?;
+                None
             }
         },
         Commands::List(cmd) => match cmd {
-            ListCommands::Create { name, workspace } => {
-                list::create(name, workspace)?;
+            ListCommands::Create { name, parent, allow_duplicate, workspace } => {
+                list::create(name, parent, allow_duplicate, workspace)?
+            }
+            ListCommands::Show { list, assignee, open, completed, all_statuses, sort, relative, porcelain, count, format, hide_blocked, workspace } => {
+                let visibility = if open {
+                    Some(CompletedVisibility::Hide)
+                } else if completed {
+                    Some(CompletedVisibility::Only)
+                } else if all_statuses {
+                    Some(CompletedVisibility::Show)
+                } else {
+                    None
+                };
+                let mode = if porcelain {
+                    list::ShowOutput::Porcelain
+                } else if count {
+                    list::ShowOutput::Count
+                } else if let Some(format) = format {
+                    list::ShowOutput::Format { template: list::TaskTemplate::parse(&format)?, relative }
+                } else {
+                    list::ShowOutput::Full { relative }
+                };
+                list::show(list, assignee, visibility, sort.into(), mode, hide_blocked, workspace)?;
+                None
+            }
+            ListCommands::Info { name, relative, workspace } => {
+                list::info(name, relative, workspace)?;
+                None
             }
-            ListCommands::Show { list, workspace } => {
-                list::show(list, workspace)?;
+            ListCommands::Merge { source, into, keep_duplicates, dry_run, workspace } => {
+                list::merge(source, into, keep_duplicates, dry_run, workspace)?
             }
-            ListCommands::Delete { name, workspace } => {
-                list::delete(name, workspace)?;
+            ListCommands::Complete { name, dry_run, workspace } => {
+                list::complete(name, dry_run, workspace)?
+            }
+            ListCommands::Reopen { name, dry_run, workspace } => {
+                list::reopen(name, dry_run, workspace)?
+            }
+            ListCommands::Split { source, into, status, tags, due_before, workspace } => {
+                list::split(source, into, status, tags, due_before, workspace)?
+            }
+            ListCommands::SetInbox { name, clear: _, workspace } => {
+                list::set_inbox(name, workspace)?
+            }
+            ListCommands::Delete { name, force, workspace } => {
+                list::delete(name, force, workspace)?
             }
         },
-        Commands::Add { title, list, due, workspace } => {
-            task::add(title, list, due, workspace)?;
+        Commands::Add(args) => {
+            task::add(args)?
+        }
+        Commands::Quick { input, list, workspace } => {
+            task::quick(input, list, workspace)?
+        }
+        Commands::Inbox { title, workspace } => {
+            task::inbox(title, workspace)?
+        }
+        Commands::Show { task_id, render, workspace } => {
+            task::show(task_id, render, workspace)?;
+            None
+        }
+        Commands::Complete { task_ids, workspace } => {
+            task::complete(task_ids, workspace)?
+        }
+        Commands::Delete { task_ids, workspace } => {
+            task::delete(task_ids, workspace)?
+        }
+        Commands::Edit { task_id, editor, raw, workspace } => {
+            task::edit(task_id, editor, raw, workspace)?
         }
-        Commands::Complete { task_id, workspace } => {
-            task::complete(task_id, workspace)?;
+        Commands::Assign { task_id, name, workspace } => {
+            task::assign(task_id, name, workspace)?
         }
-        Commands::Delete { task_id, workspace } => {
-            task::delete(task_id, workspace)?;
+        Commands::Label { task_id, label, workspace } => {
+            task::label(task_id, label, workspace)?
         }
-        Commands::Edit { task_id, workspace } => {
-            task::edit(task_id, workspace)?;
+        Commands::Block { task_id, on, remove, workspace } => {
+            task::block(task_id, on, remove, workspace)?
+        }
+        Commands::Field(cmd) => match cmd {
+            FieldCommands::Set { task_id, key, value, workspace } => field::set(task_id, key, value, workspace)?,
+            FieldCommands::Get { task_id, key, workspace } => { field::get(task_id, key, workspace)?; None }
+            FieldCommands::Unset { task_id, key, workspace } => field::unset(task_id, key, workspace)?,
+        },
+        Commands::Remind { task_id, at, workspace } => {
+            reminder::add(task_id, at, workspace)?
+        }
+        Commands::Notify { workspace } => {
+            reminder::notify(workspace)?
         }
         Commands::Group(cmd) => match cmd {
             GroupCommands::Enable { list, workspace } => {
-                group::enable(list, workspace)?;
+                group::enable(list, workspace)?
             }
             GroupCommands::Disable { list, workspace } => {
-                group::disable(list, workspace)?;
+                group::disable(list, workspace)?
             }
         },
-        Commands::Sync { setup, push, pull, status, all, workspace } => {
+        Commands::Tags(cmd) => match cmd {
+            TagsCommands::List { workspace } => { tags::list(workspace)?; None }
+            TagsCommands::Allow { tags, workspace } => tags::allow(tags, workspace)?,
+            TagsCommands::Disallow { tags, workspace } => tags::disallow(tags, workspace)?,
+            TagsCommands::Strict { state, workspace } => tags::strict(state, workspace)?,
+        },
+        Commands::Import { source, format, list, preserve_ids, on_collision, on_existing, workspace } => {
+            import::execute(format, source, list, preserve_ids, on_collision, on_existing, workspace)?
+        }
+        Commands::Export { format, list, output, workspace } => {
+            export::execute(format, list, output, workspace)?;
+            None
+        }
+        Commands::Recent { days, workspace } => {
+            recent::execute(days, workspace)?;
+            None
+        }
+        Commands::Find(args) => {
+            find::execute(args)?;
+            None
+        }
+        Commands::Reindex { workspace } => {
+            find::reindex(workspace)?;
+            None
+        }
+        Commands::All { completed, workspace } => {
+            all::execute(completed, workspace)?;
+            None
+        }
+        Commands::Upcoming { days, workspace } => {
+            upcoming::execute(days, workspace)?;
+            None
+        }
+        Commands::Mine { unassigned, workspace } => {
+            mine::execute(unassigned, workspace)?;
+            None
+        }
+        Commands::Streak { list, workspace } => {
+            streak::execute(list, workspace)?;
+            None
+        }
+        Commands::Doctor { check_only, adopt, rename_files, workspace } => {
+            doctor::execute(check_only, adopt, rename_files, workspace)?
+        }
+        Commands::Backup { output, exclude, workspace } => {
+            backup::create(output, exclude, workspace)?;
+            None
+        }
+        Commands::Restore { archive, path, name } => {
+            backup::restore(archive, path, name)?
+        }
+        Commands::Sync { command: Some(command), .. } => match command {
+            SyncCommands::Check { password_file, workspace } => { sync::check(password_file, workspace)?; None }
+            SyncCommands::Auto { state, workspace } => { sync::set_auto(state, workspace)?; None }
+            SyncCommands::Conflicts { workspace } => { sync::conflicts(workspace)?; None }
+            SyncCommands::Resolve { file, keep, workspace } => sync::resolve(file, keep, workspace)?,
+        },
+        Commands::Sync { command: None, setup, proxy, ca_cert, push, pull, status, all, since, json, password_file, workspace } => {
             if setup {
-                sync::setup(workspace)?;
+                sync::setup(proxy, ca_cert, workspace)?;
+                None
             } else if status {
                 sync::status(workspace, all)?;
+                None
             } else {
                 let mode = if push {
                     onyx_core::sync::SyncMode::Push
@@ -268,10 +993,54 @@ fn main() -> Result<()> {
                 } else {
                     onyx_core::sync::SyncMode::Full
                 };
-                sync::execute(mode, workspace)?;
+                sync::execute(mode, since, json, password_file, workspace)?;
+                None
             }
         },
+    };
+
+    if let Some(workspace) = changed {
+        after_change(workspace);
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(args: &[&str], aliases: &[(&str, &str)]) -> Result<Vec<String>> {
+        let aliases = aliases.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        let args = args.iter().map(OsString::from).collect();
+        let today = NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+        Ok(expand_aliases(args, &aliases, today)?.into_iter().map(|a| a.into_string().unwrap()).collect())
+    }
+
+    #[test]
+    fn test_alias_expands_to_known_args() {
+        let aliases = [("today", "find --status open --due-before {tomorrow} --sort due"), ("w", "list show 'Work stuff'")];
+        let args = expand(&["onyx", "today", "-w", "home"], &aliases).unwrap();
+        assert_eq!(args, ["onyx", "find", "--status", "open", "--due-before", "2026-04-01", "--sort", "due", "-w", "home"]);
+        assert!(Cli::try_parse_from(&args).is_ok());
+
+        // Quoted words stay together
+        assert_eq!(expand(&["onyx", "w"], &aliases).unwrap(), ["onyx", "list", "show", "Work stuff"]);
+        // Unknown words and built-in commands are left alone
+        assert_eq!(expand(&["onyx", "nope"], &aliases).unwrap(), ["onyx", "nope"]);
+        let shadow = [("find", "list show")];
+        assert_eq!(expand(&["onyx", "find"], &shadow).unwrap(), ["onyx", "find"]);
+    }
+
+    #[test]
+    fn test_alias_chains_and_loops() {
+        let chained = [("t", "today --count"), ("today", "find --status open")];
+        assert_eq!(expand(&["onyx", "t"], &chained).unwrap(), ["onyx", "find", "--status", "open", "--count"]);
+
+        let looping = [("a", "b -x"), ("b", "a -y")];
+        let err = expand(&["onyx", "a"], &looping).unwrap_err().to_string();
+        assert_eq!(err, "Alias loop: a -> b -> a");
+        assert!(expand(&["onyx", "me"], &[("me", "me")]).is_err());
+        assert!(expand(&["onyx", "bad"], &[("bad", "find 'unclosed")]).is_err());
+    }
+}
@@ -1,4 +1,25 @@
+use clap::ValueEnum;
 use colored::*;
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// `--color` setting. `Auto` leaves the decision to `colored` (TTY and `NO_COLOR`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    pub fn apply(self) {
+        match self {
+            ColorMode::Auto => {}
+            ColorMode::Always => colored::control::set_override(true),
+            ColorMode::Never => colored::control::set_override(false),
+        }
+    }
+}
 
 pub fn success(message: &str) {
     println!("{} {}", "✓".green(), message);
@@ -31,3 +52,198 @@ pub fn item(message: &str) {
 pub fn blank() {
     println!();
 }
+
+/// Render an ASCII progress bar like `[#####-----] 50%`. Empty totals render as 0%.
+pub fn render_progress_bar(done: usize, total: usize, width: usize) -> String {
+    let ratio = if total == 0 { 0.0 } else { done.min(total) as f64 / total as f64 };
+    let filled = (ratio * width as f64).round() as usize;
+    format!(
+        "[{}{}] {}%",
+        "#".repeat(filled).green(),
+        "-".repeat(width - filled).dimmed(),
+        (ratio * 100.0).round() as u32
+    )
+}
+
+/// Relative time like `just now`, `5 minutes ago`, `3 days ago` or `in 2 days`.
+/// Units step up at 60 seconds, 60 minutes, 24 hours, 30 days and 365 days.
+pub fn humanize_relative(then: chrono::DateTime<chrono::Utc>, now: chrono::DateTime<chrono::Utc>) -> String {
+    let secs = (then - now).num_seconds();
+    let abs = secs.unsigned_abs();
+    if abs < 60 {
+        return "just now".to_string();
+    }
+    let (value, unit) = match abs {
+        s if s < 3600 => (s / 60, "minute"),
+        s if s < 86_400 => (s / 3600, "hour"),
+        s if s < 30 * 86_400 => (s / 86_400, "day"),
+        s if s < 365 * 86_400 => (s / (30 * 86_400), "month"),
+        s => (s / (365 * 86_400), "year"),
+    };
+    let plural = if value == 1 { "" } else { "s" };
+    if secs < 0 {
+        format!("{} {}{} ago", value, unit, plural)
+    } else {
+        format!("in {} {}{}", value, unit, plural)
+    }
+}
+
+/// A task's due date in `tz`: `date_format` alone for date-only tasks, followed by the
+/// time (`HH:MM`) when the task is due at a specific time (`has_time`).
+pub fn format_due<Z: chrono::TimeZone>(due: chrono::DateTime<chrono::Utc>, has_time: bool, date_format: &str, tz: &Z) -> String
+where
+    Z::Offset: std::fmt::Display,
+{
+    let local = due.with_timezone(tz);
+    if has_time {
+        format!("{} {}", local.format(date_format), local.format("%H:%M"))
+    } else {
+        local.format(date_format).to_string()
+    }
+}
+
+/// Columns `text` takes up in a terminal: 2 for most emoji and CJK characters, 0 for
+/// combining marks.
+pub fn display_width(text: &str) -> usize {
+    unicode_width::UnicodeWidthStr::width(text)
+}
+
+/// `text` followed by enough spaces to fill `width` columns, so what comes after lines
+/// up even when wide characters are involved.
+pub fn pad_to_width(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(display_width(text))))
+}
+
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+/// Answer yes to every `confirm` prompt (`--yes` / `ONYX_ASSUME_YES`).
+pub fn set_assume_yes(yes: bool) {
+    ASSUME_YES.store(yes, Ordering::Relaxed);
+}
+
+/// Whether `confirm` prompts are answered yes without asking.
+pub fn assumes_yes() -> bool {
+    ASSUME_YES.load(Ordering::Relaxed)
+}
+
+/// Ask a y/n question on stdin, or answer yes without asking under `--yes`.
+pub fn confirm(question: &str) -> std::io::Result<bool> {
+    confirm_from(question, ASSUME_YES.load(Ordering::Relaxed), &mut std::io::stdin().lock())
+}
+
+/// Ask a y/n question on stdin even under `--yes`, for operations that need their own
+/// override (such as `--force`).
+pub fn ask(question: &str) -> std::io::Result<bool> {
+    confirm_from(question, false, &mut std::io::stdin().lock())
+}
+
+fn confirm_from(question: &str, assume_yes: bool, input: &mut impl BufRead) -> std::io::Result<bool> {
+    if assume_yes {
+        return Ok(true);
+    }
+    print!("{} (y/n): ", question);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    Ok(answer.trim().eq_ignore_ascii_case("y"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_due_date_only_vs_timed() {
+        use chrono::TimeZone;
+        let tz = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+        // Date-only dues are stored at the end of the local day
+        let end_of_day = chrono::Utc.with_ymd_and_hms(2026, 6, 1, 21, 59, 59).unwrap();
+        assert_eq!(format_due(end_of_day, false, "%Y-%m-%d", &tz), "2026-06-01");
+
+        let timed = chrono::Utc.with_ymd_and_hms(2026, 6, 1, 7, 30, 0).unwrap();
+        assert_eq!(format_due(timed, true, "%Y-%m-%d", &tz), "2026-06-01 09:30");
+        assert_eq!(format_due(timed, true, "%d/%m", &tz), "01/06 09:30");
+
+        // An explicit midnight time is still shown
+        let midnight = chrono::Utc.with_ymd_and_hms(2026, 5, 31, 22, 0, 0).unwrap();
+        assert_eq!(format_due(midnight, true, "%Y-%m-%d", &tz), "2026-06-01 00:00");
+        assert_eq!(format_due(midnight, false, "%Y-%m-%d", &tz), "2026-06-01");
+    }
+
+    #[test]
+    fn test_pad_to_width_counts_columns() {
+        assert_eq!(pad_to_width("ab", 4), "ab  ");
+        // Emoji take two columns, so one fewer space
+        assert_eq!(pad_to_width("🔥", 4), "🔥  ");
+        assert_eq!(pad_to_width("é", 2), "é ");
+        assert_eq!(pad_to_width("toolong", 3), "toolong");
+        assert_eq!(display_width(""), 0);
+    }
+
+    #[test]
+    fn test_confirm_reads_answer() {
+        assert!(confirm_from("Continue?", false, &mut "y\n".as_bytes()).unwrap());
+        assert!(confirm_from("Continue?", false, &mut " Y \n".as_bytes()).unwrap());
+        assert!(!confirm_from("Continue?", false, &mut "n\n".as_bytes()).unwrap());
+        assert!(!confirm_from("Continue?", false, &mut "".as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_confirm_with_yes_skips_prompt() {
+        // Nothing is read: the "n" would otherwise decline
+        let mut input = "n\n".as_bytes();
+        assert!(confirm_from("Continue?", true, &mut input).unwrap());
+        assert_eq!(input, b"n\n");
+    }
+
+    #[test]
+    fn test_progress_bar_half() {
+        colored::control::set_override(false);
+        assert_eq!(render_progress_bar(5, 10, 10), "[#####-----] 50%");
+    }
+
+    #[test]
+    fn test_progress_bar_zero_total() {
+        colored::control::set_override(false);
+        assert_eq!(render_progress_bar(0, 0, 10), "[----------] 0%");
+    }
+
+    #[test]
+    fn test_progress_bar_rounds() {
+        colored::control::set_override(false);
+        assert_eq!(render_progress_bar(1, 3, 10), "[###-------] 33%");
+        assert_eq!(render_progress_bar(3, 3, 4), "[####] 100%");
+    }
+
+    #[test]
+    fn test_humanize_relative_seconds_and_minutes() {
+        let now = chrono::Utc::now();
+        let ago = |secs| humanize_relative(now - chrono::Duration::seconds(secs), now);
+        assert_eq!(ago(0), "just now");
+        assert_eq!(ago(59), "just now");
+        assert_eq!(ago(-59), "just now");
+        assert_eq!(ago(60), "1 minute ago");
+        assert_eq!(ago(3599), "59 minutes ago");
+    }
+
+    #[test]
+    fn test_humanize_relative_hours_and_days() {
+        let now = chrono::Utc::now();
+        let ago = |secs| humanize_relative(now - chrono::Duration::seconds(secs), now);
+        assert_eq!(ago(3600), "1 hour ago");
+        assert_eq!(ago(86_399), "23 hours ago");
+        assert_eq!(ago(86_400), "1 day ago");
+        assert_eq!(ago(3 * 86_400 + 10), "3 days ago");
+        assert_eq!(ago(30 * 86_400), "1 month ago");
+        assert_eq!(ago(400 * 86_400), "1 year ago");
+    }
+
+    #[test]
+    fn test_humanize_relative_future() {
+        let now = chrono::Utc::now();
+        let ahead = |secs| humanize_relative(now + chrono::Duration::seconds(secs), now);
+        assert_eq!(ahead(90), "in 1 minute");
+        assert_eq!(ahead(2 * 86_400), "in 2 days");
+        assert_eq!(ahead(5 * 3600), "in 5 hours");
+    }
+}
@@ -0,0 +1,186 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use crate::error::{Error, Result};
+
+/// Zip the whole workspace directory (tasks and metadata) into `output`.
+///
+/// `exclude` holds glob patterns matched against paths relative to the workspace root
+/// (e.g. `Archive/*`); a matching directory is skipped entirely. If `output` lives inside
+/// the workspace it is never added to itself. Returns the number of files written.
+pub fn create_backup(workspace_path: &Path, output: &Path, exclude: &[String]) -> Result<usize> {
+    if !workspace_path.is_dir() {
+        return Err(Error::WorkspaceNotFound(workspace_path.display().to_string()));
+    }
+    let exclude = compile_excludes(exclude)?;
+    let file = File::create(output)?;
+    // Compare canonical paths so a relative `output` inside the workspace is still skipped.
+    let output = output.canonicalize()?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut count = 0;
+    add_dir_recursive(&mut zip, workspace_path, workspace_path, &output, &exclude, options, &mut count)?;
+    zip.finish()?;
+    Ok(count)
+}
+
+fn add_dir_recursive(
+    zip: &mut ZipWriter<File>,
+    root: &Path,
+    dir: &Path,
+    output: &Path,
+    exclude: &[glob::Pattern],
+    options: SimpleFileOptions,
+    count: &mut usize,
+) -> Result<()> {
+    let mut entries = std::fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let relative = path.strip_prefix(root)
+            .map_err(|e| Error::InvalidPath(e.to_string()))?
+            .to_str()
+            .ok_or_else(|| Error::InvalidPath(path.display().to_string()))?
+            .replace('\\', "/");
+
+        if exclude.iter().any(|p| p.matches(&relative)) {
+            continue;
+        }
+        if path.is_dir() {
+            zip.add_directory(format!("{}/", relative), options)?;
+            add_dir_recursive(zip, root, &path, output, exclude, options, count)?;
+        } else if path.canonicalize()? != output {
+            zip.start_file(relative, options)?;
+            zip.write_all(&std::fs::read(&path)?)?;
+            *count += 1;
+        }
+    }
+    Ok(())
+}
+
+fn compile_excludes(patterns: &[String]) -> Result<Vec<glob::Pattern>> {
+    patterns.iter()
+        .map(|p| glob::Pattern::new(p)
+            .map_err(|e| Error::InvalidData(format!("Invalid exclude pattern '{}': {}", p, e))))
+        .collect()
+}
+
+/// Unpack a backup created by [`create_backup`] into `dest`, which must be missing or empty.
+///
+/// Entries that would escape `dest` (absolute paths, `..`) are rejected. Returns the number
+/// of files restored.
+pub fn restore_backup(archive_path: &Path, dest: &Path) -> Result<usize> {
+    if dest.exists() && std::fs::read_dir(dest)?.next().is_some() {
+        return Err(Error::InvalidPath(format!("Restore target {} is not empty", dest.display())));
+    }
+    let mut archive = ZipArchive::new(File::open(archive_path)?)?;
+    std::fs::create_dir_all(dest)?;
+
+    let mut count = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let relative = entry.enclosed_name()
+            .ok_or_else(|| Error::InvalidPath(format!("Unsafe path in backup: {}", entry.name())))?;
+        let target = dest.join(relative);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut out = File::create(&target)?;
+        io::copy(&mut entry, &mut out)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Task;
+    use crate::repository::TaskRepository;
+    use tempfile::TempDir;
+
+    fn sample_workspace(dir: &Path) {
+        let mut repo = TaskRepository::init(dir.to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+        repo.create_task(work.id, Task::new("Ship it".to_string())).unwrap();
+        let archive = repo.create_list("Archive".to_string()).unwrap();
+        repo.create_task(archive.id, Task::new("Old".to_string())).unwrap();
+    }
+
+    #[test]
+    fn test_backup_and_restore_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        let workspace = temp.path().join("ws");
+        sample_workspace(&workspace);
+        let zip_path = temp.path().join("backup.zip");
+
+        let written = create_backup(&workspace, &zip_path, &[]).unwrap();
+        assert!(written >= 5);
+
+        let restored_path = temp.path().join("restored");
+        let restored = restore_backup(&zip_path, &restored_path).unwrap();
+        assert_eq!(restored, written);
+
+        let repo = TaskRepository::new(restored_path).unwrap();
+        let lists = repo.get_lists().unwrap();
+        assert_eq!(lists.len(), 2);
+        let work = lists.iter().find(|l| l.title == "Work").unwrap();
+        assert_eq!(work.tasks[0].title, "Ship it");
+    }
+
+    #[test]
+    fn test_backup_excludes_and_skips_own_output() {
+        let temp = TempDir::new().unwrap();
+        sample_workspace(temp.path());
+        let zip_path = temp.path().join("backup.zip");
+
+        create_backup(temp.path(), &zip_path, &["Archive".to_string()]).unwrap();
+
+        let archive = ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        assert!(names.contains(&"Work/Ship it.md"));
+        assert!(names.contains(&".metadata.json"));
+        assert!(!names.iter().any(|n| n.starts_with("Archive")));
+        assert!(!names.contains(&"backup.zip"));
+    }
+
+    #[test]
+    fn test_restore_into_non_empty_dir_rejected() {
+        let temp = TempDir::new().unwrap();
+        let workspace = temp.path().join("ws");
+        sample_workspace(&workspace);
+        let zip_path = temp.path().join("backup.zip");
+        create_backup(&workspace, &zip_path, &[]).unwrap();
+
+        let err = restore_backup(&zip_path, &workspace).unwrap_err();
+        assert!(matches!(err, Error::InvalidPath(_)));
+    }
+
+    #[test]
+    fn test_restore_rejects_path_traversal() {
+        let temp = TempDir::new().unwrap();
+        let zip_path = temp.path().join("evil.zip");
+        let mut zip = ZipWriter::new(File::create(&zip_path).unwrap());
+        zip.start_file("../escape.md", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"nope").unwrap();
+        zip.finish().unwrap();
+
+        let err = restore_backup(&zip_path, &temp.path().join("out")).unwrap_err();
+        assert!(matches!(err, Error::InvalidPath(_)));
+        assert!(!temp.path().join("escape.md").exists());
+    }
+
+    #[test]
+    fn test_invalid_exclude_pattern() {
+        let temp = TempDir::new().unwrap();
+        let err = create_backup(temp.path(), &temp.path().join("b.zip"), &["[".to_string()]).unwrap_err();
+        assert!(err.is_invalid_data());
+    }
+}
@@ -1,18 +1,80 @@
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use crate::error::{Error, Result};
+use crate::models::TaskStatus;
+use crate::webdav::WebDavOptions;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Environment variable that overrides the config file location.
+pub const CONFIG_PATH_ENV: &str = "ONYX_CONFIG";
+
+/// Environment variable naming the workspace to use when no workspace is given explicitly.
+pub const WORKSPACE_ENV: &str = "ONYX_WORKSPACE";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum WorkspaceMode {
+    #[default]
     Local,
     Webdav,
 }
 
-impl Default for WorkspaceMode {
-    fn default() -> Self {
-        Self::Local
+/// Time of day a date-only due date (e.g. `2026-06-01`) resolves to.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum DateOnlyDue {
+    /// 00:00:00 local time; the task becomes overdue as soon as the day starts.
+    StartOfDay,
+    /// 23:59:59 local time; the task stays due for the whole day.
+    #[default]
+    EndOfDay,
+}
+
+/// Syntax of the frontmatter block at the top of task files.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontmatterFormat {
+    /// YAML between `---` fences.
+    #[default]
+    Yaml,
+    /// TOML between `+++` fences, as used by static site generators like Hugo and Zola.
+    Toml,
+}
+
+/// How task files are named inside their list folder.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum FilenameScheme {
+    /// `Buy milk.md`: readable, but renaming a task renames its file.
+    #[default]
+    Title,
+    /// `<uuid>.md`: never changes, so renames don't show up as delete + create in sync.
+    Uuid,
+    /// `buy-milk-<uuid>.md`: stable ID with a readable hint.
+    SlugUuid,
+}
+
+/// Which tasks list views show by default, judged by completion.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CompletedVisibility {
+    /// Open tasks only.
+    Hide,
+    /// Open and completed tasks together.
+    #[default]
+    Show,
+    /// Completed tasks only.
+    Only,
+}
+
+impl CompletedVisibility {
+    pub fn shows(self, status: TaskStatus) -> bool {
+        match self {
+            CompletedVisibility::Hide => status != TaskStatus::Completed,
+            CompletedVisibility::Show => true,
+            CompletedVisibility::Only => status == TaskStatus::Completed,
+        }
     }
 }
 
@@ -27,11 +89,194 @@ pub struct WorkspaceConfig {
     pub last_sync: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub theme: Option<String>,
+    /// Proxy URL for WebDAV requests.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub webdav_proxy: Option<String>,
+    /// PEM root certificate trusted (alongside system roots) for the WebDAV server.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub webdav_ca_cert: Option<PathBuf>,
+    /// Push to the WebDAV server after every command that changes tasks or lists.
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub auto_sync: bool,
+    /// List of the last task changed by ID; searched first when looking up the next one.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub last_task_list: Option<uuid::Uuid>,
 }
 
 impl WorkspaceConfig {
     pub fn new(path: PathBuf) -> Self {
-        Self { path, mode: WorkspaceMode::Local, webdav_url: None, last_sync: None, theme: None }
+        Self {
+            path,
+            mode: WorkspaceMode::Local,
+            webdav_url: None,
+            last_sync: None,
+            theme: None,
+            webdav_proxy: None,
+            webdav_ca_cert: None,
+            auto_sync: false,
+            last_task_list: None,
+        }
+    }
+
+    /// `path` as an absolute, canonical folder to store in config, resolving a relative
+    /// path against `base` (usually the current directory). The folder is created first
+    /// if needed, since `fs::canonicalize` only works on existing paths.
+    pub fn canonical_path(path: &Path, base: &Path) -> Result<PathBuf> {
+        let path = if path.is_relative() { base.join(path) } else { path.to_path_buf() };
+        std::fs::create_dir_all(&path)?;
+        Ok(std::fs::canonicalize(&path)?)
+    }
+
+    /// Network settings for building this workspace's `WebDavClient`.
+    pub fn webdav_options(&self) -> WebDavOptions {
+        WebDavOptions { proxy: self.webdav_proxy.clone(), ca_cert: self.webdav_ca_cert.clone() }
+    }
+}
+
+/// Per-workspace settings stored as `.workspace.json` in the workspace folder, so they
+/// travel (and sync) with the data. Unset fields fall back to `AppConfig::defaults`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct WorkspaceSettings {
+    /// List that `add` targets when no `--list` is given.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub default_list: Option<String>,
+    /// Glob patterns (relative to the workspace root) excluded from sync.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub sync_ignore: Vec<String>,
+    /// chrono format string used when displaying due dates.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub date_format: Option<String>,
+    /// Show dates relative to now ("in 2 days") instead of with `date_format`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub relative_dates: Option<bool>,
+    /// Time of day that date-only due dates resolve to (defaults to end of day).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub date_only_due: Option<DateOnlyDue>,
+    /// Whether list views include completed tasks (defaults to showing them).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub show_completed: Option<CompletedVisibility>,
+    /// Frontmatter syntax used when writing task files (defaults to YAML). Files in either
+    /// syntax are always readable.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub frontmatter_format: Option<FrontmatterFormat>,
+    /// How task files are named (defaults to the title). Files named by an older scheme stay
+    /// readable; `TaskRepository::rename_task_files` moves them over.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub filename_scheme: Option<FilenameScheme>,
+    /// Maximum number of uploads sync runs at once (defaults to
+    /// `sync::DEFAULT_UPLOAD_CONCURRENCY`).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub sync_concurrency: Option<usize>,
+    /// Tag vocabulary. When not empty, tags outside it are flagged as likely typos when
+    /// added to a task (see [`WorkspaceSettings::check_tags`]).
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub allowed_tags: Vec<String>,
+    /// Refuse tags outside `allowed_tags` instead of only warning (defaults to warning).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub strict_tags: Option<bool>,
+}
+
+impl WorkspaceSettings {
+    pub const FILE_NAME: &'static str = ".workspace.json";
+
+    /// Overlay these settings on top of `defaults`; fields set here win.
+    pub fn merged_over(&self, defaults: &WorkspaceSettings) -> WorkspaceSettings {
+        WorkspaceSettings {
+            default_list: self.default_list.clone().or_else(|| defaults.default_list.clone()),
+            sync_ignore: if self.sync_ignore.is_empty() {
+                defaults.sync_ignore.clone()
+            } else {
+                self.sync_ignore.clone()
+            },
+            date_format: self.date_format.clone().or_else(|| defaults.date_format.clone()),
+            relative_dates: self.relative_dates.or(defaults.relative_dates),
+            date_only_due: self.date_only_due.or(defaults.date_only_due),
+            show_completed: self.show_completed.or(defaults.show_completed),
+            frontmatter_format: self.frontmatter_format.or(defaults.frontmatter_format),
+            filename_scheme: self.filename_scheme.or(defaults.filename_scheme),
+            sync_concurrency: self.sync_concurrency.or(defaults.sync_concurrency),
+            allowed_tags: if self.allowed_tags.is_empty() {
+                defaults.allowed_tags.clone()
+            } else {
+                self.allowed_tags.clone()
+            },
+            strict_tags: self.strict_tags.or(defaults.strict_tags),
+        }
+    }
+
+    /// Tags among `tags` that aren't in `allowed_tags`, compared ignoring case. With no
+    /// vocabulary every tag is allowed.
+    pub fn unknown_tags<'a>(&self, tags: &'a [String]) -> Vec<&'a str> {
+        if self.allowed_tags.is_empty() {
+            return Vec::new();
+        }
+        tags.iter()
+            .filter(|tag| !self.allowed_tags.iter().any(|allowed| allowed.eq_ignore_ascii_case(tag)))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Check tags about to be set on a task against the vocabulary. With `strict_tags`,
+    /// unknown tags are an `InvalidData` error; otherwise they are returned so the caller
+    /// can warn about them.
+    pub fn check_tags<'a>(&self, tags: &'a [String]) -> Result<Vec<&'a str>> {
+        let unknown = self.unknown_tags(tags);
+        if !unknown.is_empty() && self.strict_tags == Some(true) {
+            return Err(Error::InvalidData(format!(
+                "Tag{} not in the workspace's allowed tags: {}",
+                if unknown.len() == 1 { "" } else { "s" },
+                unknown.join(", ")
+            )));
+        }
+        Ok(unknown)
+    }
+
+    /// Add `tags` to the vocabulary, skipping ones already in it (ignoring case). Returns
+    /// the tags that were added.
+    pub fn allow_tags(&mut self, tags: &[String]) -> Vec<String> {
+        let mut added = Vec::new();
+        for tag in tags {
+            let tag = tag.trim().trim_start_matches('#');
+            if !tag.is_empty() && !self.allowed_tags.iter().any(|allowed| allowed.eq_ignore_ascii_case(tag)) {
+                self.allowed_tags.push(tag.to_string());
+                added.push(tag.to_string());
+            }
+        }
+        added
+    }
+
+    /// Remove `tags` from the vocabulary (ignoring case). Returns the tags that were removed.
+    pub fn disallow_tags(&mut self, tags: &[String]) -> Vec<String> {
+        let mut removed = Vec::new();
+        self.allowed_tags.retain(|allowed| {
+            let drop = tags.iter().any(|tag| tag.trim().trim_start_matches('#').eq_ignore_ascii_case(allowed));
+            if drop {
+                removed.push(allowed.clone());
+            }
+            !drop
+        });
+        removed
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == WorkspaceSettings::default()
+    }
+
+    /// Load settings from a workspace folder. A missing file (workspaces created before
+    /// per-workspace settings existed) yields the defaults.
+    pub fn load(workspace_path: &Path) -> Result<Self> {
+        let path = workspace_path.join(Self::FILE_NAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, workspace_path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(workspace_path.join(Self::FILE_NAME), content)?;
+        Ok(())
     }
 }
 
@@ -39,6 +284,23 @@ impl WorkspaceConfig {
 pub struct AppConfig {
     pub workspaces: HashMap<String, WorkspaceConfig>,
     pub current_workspace: Option<String>,
+    /// Editor command used by `onyx edit` (e.g. `code --wait`). Overrides `$EDITOR`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub editor: Option<String>,
+    /// Global defaults for settings not set in a workspace's `.workspace.json`.
+    #[serde(skip_serializing_if = "WorkspaceSettings::is_empty", default)]
+    pub defaults: WorkspaceSettings,
+    /// IANA timezone (e.g. `Europe/Berlin`) used instead of the system timezone for
+    /// resolving date-only due dates and deciding "today"/"overdue".
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub timezone: Option<String>,
+    /// Your name as used in task `assignee` fields; drives `onyx mine`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub my_name: Option<String>,
+    /// Command aliases for the CLI: a first argument matching a key is replaced by the
+    /// value's words (e.g. `"work": "list show Work --open"`).
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub aliases: BTreeMap<String, String>,
 }
 
 impl AppConfig {
@@ -46,13 +308,45 @@ impl AppConfig {
         Self {
             workspaces: HashMap::new(),
             current_workspace: None,
+            editor: None,
+            defaults: WorkspaceSettings::default(),
+            timezone: None,
+            my_name: None,
+            aliases: BTreeMap::new(),
         }
     }
 
+    /// Timezone used for display and day bucketing: the `timezone` override if set,
+    /// otherwise the system timezone, falling back to UTC if it can't be detected.
+    pub fn resolve_timezone(&self) -> Result<Tz> {
+        if let Some(name) = &self.timezone {
+            return name.parse::<Tz>()
+                .map_err(|_| Error::InvalidData(format!("Unknown timezone '{}'", name)));
+        }
+        Ok(iana_time_zone::get_timezone()
+            .ok()
+            .and_then(|name| name.parse::<Tz>().ok())
+            .unwrap_or(Tz::UTC))
+    }
+
     pub fn add_workspace(&mut self, name: String, config: WorkspaceConfig) {
         self.workspaces.insert(name, config);
     }
 
+    /// Another workspace (not `name`) whose folder is the same as `path`. Both sides go
+    /// through `fs::canonicalize`, so symlinks and `..` segments compare equal; a path
+    /// that does not exist yet is compared as given.
+    pub fn workspace_at_path(&self, path: &Path, name: &str) -> Option<&str> {
+        let canonical = |p: &Path| std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+        let target = canonical(path);
+        let mut matches: Vec<&str> = self.workspaces.iter()
+            .filter(|(other, config)| other.as_str() != name && canonical(&config.path) == target)
+            .map(|(other, _)| other.as_str())
+            .collect();
+        matches.sort();
+        matches.into_iter().next()
+    }
+
     pub fn remove_workspace(&mut self, name: &str) -> Option<WorkspaceConfig> {
         if self.current_workspace.as_deref() == Some(name) {
             self.current_workspace = None;
@@ -72,6 +366,29 @@ impl AppConfig {
         Ok((name, config))
     }
 
+    /// The workspace a command should use: `explicit` (e.g. a `--workspace` flag) if given,
+    /// else the one named by `ONYX_WORKSPACE`, else the current workspace. A name from
+    /// either source must be a configured workspace.
+    pub fn resolve_workspace(&self, explicit: Option<&str>) -> Result<(String, &WorkspaceConfig)> {
+        let from_env = std::env::var(WORKSPACE_ENV).ok().filter(|name| !name.is_empty());
+        self.resolve_workspace_from(explicit, from_env.as_deref())
+    }
+
+    fn resolve_workspace_from(&self, explicit: Option<&str>, from_env: Option<&str>) -> Result<(String, &WorkspaceConfig)> {
+        if let Some(name) = explicit {
+            let config = self.get_workspace(name)
+                .ok_or_else(|| Error::WorkspaceNotFound(name.to_string()))?;
+            return Ok((name.to_string(), config));
+        }
+        if let Some(name) = from_env {
+            let config = self.get_workspace(name)
+                .ok_or_else(|| Error::WorkspaceNotFound(format!("{} (set by {})", name, WORKSPACE_ENV)))?;
+            return Ok((name.to_string(), config));
+        }
+        let (name, config) = self.get_current_workspace()?;
+        Ok((name.clone(), config))
+    }
+
     pub fn set_current_workspace(&mut self, name: String) -> Result<()> {
         if !self.workspaces.contains_key(&name) {
             return Err(Error::WorkspaceNotFound(name));
@@ -98,7 +415,16 @@ impl AppConfig {
         Ok(())
     }
 
+    /// Location of the config file. `ONYX_CONFIG` overrides the platform config dir,
+    /// which is useful for portable installs and hermetic tests.
     pub fn get_config_path() -> PathBuf {
+        Self::config_path_from(std::env::var_os(CONFIG_PATH_ENV))
+    }
+
+    fn config_path_from(from_env: Option<std::ffi::OsString>) -> PathBuf {
+        if let Some(path) = from_env.filter(|p| !p.is_empty()) {
+            return PathBuf::from(path);
+        }
         directories::ProjectDirs::from("", "", "onyx")
             .map(|dirs| dirs.config_dir().join("config.json"))
             .unwrap_or_else(|| PathBuf::from("onyx-config.json"))
@@ -130,6 +456,27 @@ mod tests {
         assert!(matches!(result.unwrap_err(), Error::WorkspaceNotFound(_)));
     }
 
+    #[test]
+    fn test_resolve_workspace_precedence() {
+        let mut config = AppConfig::new();
+        for name in ["flag", "env", "current"] {
+            config.add_workspace(name.to_string(), WorkspaceConfig::new(PathBuf::from(format!("/{}", name))));
+        }
+        config.set_current_workspace("current".to_string()).unwrap();
+        let resolved = |explicit, from_env| config.resolve_workspace_from(explicit, from_env).map(|(name, _)| name);
+
+        assert_eq!(resolved(Some("flag"), Some("env")).unwrap(), "flag");
+        assert_eq!(resolved(None, Some("env")).unwrap(), "env");
+        assert_eq!(resolved(None, None).unwrap(), "current");
+
+        // A bad name is an error rather than a silent fallback, and says where it came from
+        let err = resolved(None, Some("ghost")).unwrap_err();
+        assert!(matches!(err, Error::WorkspaceNotFound(_)));
+        assert!(err.to_string().contains("ONYX_WORKSPACE"));
+        assert!(resolved(Some("ghost"), Some("env")).is_err());
+        assert_eq!(resolved(Some("flag"), Some("ghost")).unwrap(), "flag");
+    }
+
     #[test]
     fn test_set_current_workspace_nonexistent() {
         let mut config = AppConfig::new();
@@ -168,6 +515,46 @@ mod tests {
         assert_eq!(config.current_workspace.as_deref(), Some("a"));
     }
 
+    #[test]
+    fn test_add_workspace_with_relative_path_stores_absolute() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("sub")).unwrap();
+
+        // Folder does not exist yet; it is created before canonicalizing
+        let path = WorkspaceConfig::canonical_path(Path::new("sub/../tasks"), temp_dir.path()).unwrap();
+        let mut config = AppConfig::new();
+        config.add_workspace("rel".to_string(), WorkspaceConfig::new(path));
+
+        let stored = &config.get_workspace("rel").unwrap().path;
+        assert!(stored.is_absolute());
+        assert!(stored.is_dir());
+        assert_eq!(stored, &std::fs::canonicalize(temp_dir.path()).unwrap().join("tasks"));
+    }
+
+    #[test]
+    fn test_workspace_at_path_detects_same_folder() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("tasks");
+        std::fs::create_dir(&dir).unwrap();
+
+        let mut config = AppConfig::new();
+        config.add_workspace("a".to_string(), WorkspaceConfig::new(dir.clone()));
+
+        // Same folder via a `..` detour, registered under a second name
+        let detour = dir.join("..").join("tasks");
+        assert_eq!(config.workspace_at_path(&detour, "b"), Some("a"));
+        // Re-pointing a workspace at its own folder is not a clash
+        assert_eq!(config.workspace_at_path(&dir, "a"), None);
+        assert_eq!(config.workspace_at_path(&temp_dir.path().join("other"), "b"), None);
+
+        #[cfg(unix)]
+        {
+            let link = temp_dir.path().join("link");
+            std::os::unix::fs::symlink(&dir, &link).unwrap();
+            assert_eq!(config.workspace_at_path(&link, "b"), Some("a"));
+        }
+    }
+
     #[test]
     fn test_save_and_load_roundtrip() {
         let temp_dir = TempDir::new().unwrap();
@@ -241,6 +628,143 @@ mod tests {
         assert!(ws.last_sync.is_some());
     }
 
+    #[test]
+    fn test_workspace_settings_missing_file_is_default() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = WorkspaceSettings::load(temp_dir.path()).unwrap();
+        assert_eq!(settings, WorkspaceSettings::default());
+    }
+
+    #[test]
+    fn test_workspace_settings_save_load_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = WorkspaceSettings {
+            default_list: Some("Inbox".to_string()),
+            sync_ignore: vec!["Scratch/*".to_string()],
+            date_format: Some("%d/%m/%Y".to_string()),
+            relative_dates: Some(true),
+            date_only_due: Some(DateOnlyDue::EndOfDay),
+            show_completed: Some(CompletedVisibility::Hide),
+            frontmatter_format: Some(FrontmatterFormat::Toml),
+            filename_scheme: Some(FilenameScheme::SlugUuid),
+            sync_concurrency: Some(8),
+            allowed_tags: vec!["work".to_string(), "home".to_string()],
+            strict_tags: Some(true),
+        };
+        settings.save(temp_dir.path()).unwrap();
+        assert!(temp_dir.path().join(".workspace.json").exists());
+        assert_eq!(WorkspaceSettings::load(temp_dir.path()).unwrap(), settings);
+    }
+
+    #[test]
+    fn test_workspace_settings_merged_over_defaults() {
+        let defaults = WorkspaceSettings {
+            default_list: Some("My Tasks".to_string()),
+            sync_ignore: vec!["*.tmp".to_string()],
+            date_format: Some("%Y-%m-%d".to_string()),
+            relative_dates: Some(true),
+            date_only_due: Some(DateOnlyDue::StartOfDay),
+            show_completed: Some(CompletedVisibility::Only),
+            frontmatter_format: Some(FrontmatterFormat::Toml),
+            filename_scheme: Some(FilenameScheme::Uuid),
+            sync_concurrency: Some(2),
+            allowed_tags: vec!["work".to_string()],
+            strict_tags: Some(true),
+        };
+        let local = WorkspaceSettings {
+            default_list: Some("Work".to_string()),
+            show_completed: Some(CompletedVisibility::Hide),
+            ..Default::default()
+        };
+        let merged = local.merged_over(&defaults);
+        assert_eq!(merged.default_list.as_deref(), Some("Work"));
+        assert_eq!(merged.sync_ignore, vec!["*.tmp".to_string()]);
+        assert_eq!(merged.date_format.as_deref(), Some("%Y-%m-%d"));
+        assert_eq!(merged.relative_dates, Some(true));
+        assert_eq!(merged.date_only_due, Some(DateOnlyDue::StartOfDay));
+        assert_eq!(merged.show_completed, Some(CompletedVisibility::Hide));
+        assert_eq!(merged.frontmatter_format, Some(FrontmatterFormat::Toml));
+        assert_eq!(merged.filename_scheme, Some(FilenameScheme::Uuid));
+        assert_eq!(merged.sync_concurrency, Some(2));
+        assert_eq!(merged.allowed_tags, vec!["work".to_string()]);
+        assert_eq!(merged.strict_tags, Some(true));
+    }
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_check_tags_lenient_returns_unknown_tags() {
+        let mut settings = WorkspaceSettings::default();
+        // No vocabulary: anything goes
+        assert!(settings.check_tags(&tags(&["wrk"])).unwrap().is_empty());
+
+        settings.allowed_tags = tags(&["work", "Home"]);
+        let added = tags(&["WORK", "home", "wrk"]);
+        assert_eq!(settings.check_tags(&added).unwrap(), vec!["wrk"]);
+        settings.strict_tags = Some(false);
+        assert_eq!(settings.check_tags(&added).unwrap(), vec!["wrk"]);
+    }
+
+    #[test]
+    fn test_check_tags_strict_refuses_unknown_tags() {
+        let settings = WorkspaceSettings {
+            allowed_tags: tags(&["work", "home"]),
+            strict_tags: Some(true),
+            ..Default::default()
+        };
+        assert!(settings.check_tags(&tags(&["work", "Home"])).unwrap().is_empty());
+        let err = settings.check_tags(&tags(&["wrk", "work", "hme"])).unwrap_err();
+        assert!(matches!(&err, Error::InvalidData(msg) if msg.ends_with("allowed tags: wrk, hme")), "{}", err);
+    }
+
+    #[test]
+    fn test_allow_and_disallow_tags() {
+        let mut settings = WorkspaceSettings::default();
+        assert_eq!(settings.allow_tags(&tags(&["work", "#home", "Work", " "])), tags(&["work", "home"]));
+        assert_eq!(settings.allow_tags(&tags(&["HOME", "errands"])), tags(&["errands"]));
+        assert_eq!(settings.allowed_tags, tags(&["work", "home", "errands"]));
+
+        assert_eq!(settings.disallow_tags(&tags(&["#Home", "nope"])), tags(&["home"]));
+        assert_eq!(settings.allowed_tags, tags(&["work", "errands"]));
+    }
+
+    #[test]
+    fn test_completed_visibility_shows() {
+        use CompletedVisibility::*;
+        assert!(Hide.shows(TaskStatus::Backlog) && !Hide.shows(TaskStatus::Completed));
+        assert!(Show.shows(TaskStatus::Backlog) && Show.shows(TaskStatus::Completed));
+        assert!(!Only.shows(TaskStatus::Backlog) && Only.shows(TaskStatus::Completed));
+
+        let parsed: WorkspaceSettings = serde_json::from_str(r#"{"show_completed": "only"}"#).unwrap();
+        assert_eq!(parsed.show_completed, Some(Only));
+    }
+
+    #[test]
+    fn test_resolve_timezone_override() {
+        let mut config = AppConfig::new();
+        config.timezone = Some("America/New_York".to_string());
+        assert_eq!(config.resolve_timezone().unwrap(), chrono_tz::America::New_York);
+
+        config.timezone = Some("Mars/Olympus".to_string());
+        assert!(config.resolve_timezone().unwrap_err().is_invalid_data());
+
+        config.timezone = None;
+        assert!(config.resolve_timezone().is_ok());
+    }
+
+    #[test]
+    fn test_config_path_env_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let custom = temp_dir.path().join("portable").join("config.json");
+
+        assert_eq!(AppConfig::config_path_from(Some(custom.clone().into_os_string())), custom);
+        // Unset or empty falls back to the platform config dir
+        assert_ne!(AppConfig::config_path_from(None), custom);
+        assert_eq!(AppConfig::config_path_from(Some("".into())), AppConfig::config_path_from(None));
+    }
+
     #[test]
     fn test_backwards_compat_loading_old_format() {
         let temp_dir = TempDir::new().unwrap();
@@ -1,14 +1,21 @@
 use std::io;
 use std::fmt;
 
+/// Errors returned by onyx-core. Match on variants or use the `is_*` predicates;
+/// new variants may be added, so matches need a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     Io(io::Error),
     Serialization(String),
     NotFound(String),
     InvalidData(String),
+    /// A path or file name that is unsafe or not valid UTF-8.
+    InvalidPath(String),
     WorkspaceNotFound(String),
     ListNotFound(String),
+    /// A list name that is taken, or whose folder would clash with another list's.
+    ListAlreadyExists(String),
     TaskNotFound(String),
     WebDav(String),
     Sync(String),
@@ -22,8 +29,10 @@ impl fmt::Display for Error {
             Error::Serialization(msg) => write!(f, "Serialization error: {}", msg),
             Error::NotFound(msg) => write!(f, "Not found: {}", msg),
             Error::InvalidData(msg) => write!(f, "Invalid data: {}", msg),
+            Error::InvalidPath(msg) => write!(f, "Invalid path: {}", msg),
             Error::WorkspaceNotFound(name) => write!(f, "Workspace not found: {}", name),
             Error::ListNotFound(id) => write!(f, "List not found: {}", id),
+            Error::ListAlreadyExists(name) => write!(f, "List '{}' already exists", name),
             Error::TaskNotFound(id) => write!(f, "Task not found: {}", id),
             Error::WebDav(msg) => write!(f, "WebDAV error: {}", msg),
             Error::Sync(msg) => write!(f, "Sync error: {}", msg),
@@ -32,7 +41,38 @@ impl fmt::Display for Error {
     }
 }
 
-impl std::error::Error for Error {}
+impl Error {
+    /// True for any "does not exist" error: workspace, list, task, or other resource.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self,
+            Error::NotFound(_) | Error::WorkspaceNotFound(_) | Error::ListNotFound(_) | Error::TaskNotFound(_))
+    }
+
+    /// True when stored or supplied data could not be parsed or validated, including a
+    /// list name that is already taken.
+    pub fn is_invalid_data(&self) -> bool {
+        matches!(self,
+            Error::InvalidData(_) | Error::Serialization(_) | Error::InvalidPath(_) | Error::ListAlreadyExists(_))
+    }
+
+    /// True for failures talking to the WebDAV server, including auth and sync errors.
+    pub fn is_remote(&self) -> bool {
+        matches!(self, Error::WebDav(_) | Error::Sync(_) | Error::Credential(_))
+    }
+
+    pub fn is_io(&self) -> bool {
+        matches!(self, Error::Io(_))
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
 impl From<io::Error> for Error {
     fn from(err: io::Error) -> Self {
@@ -40,6 +80,15 @@ impl From<io::Error> for Error {
     }
 }
 
+impl From<zip::result::ZipError> for Error {
+    fn from(err: zip::result::ZipError) -> Self {
+        match err {
+            zip::result::ZipError::Io(e) => Error::Io(e),
+            other => Error::InvalidData(format!("Invalid backup archive: {}", other)),
+        }
+    }
+}
+
 impl From<serde_json::Error> for Error {
     fn from(err: serde_json::Error) -> Self {
         Error::Serialization(err.to_string())
@@ -52,6 +101,18 @@ impl From<serde_yaml::Error> for Error {
     }
 }
 
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Self {
+        Error::Serialization(err.to_string())
+    }
+}
+
+impl From<toml::ser::Error> for Error {
+    fn from(err: toml::ser::Error) -> Self {
+        Error::Serialization(err.to_string())
+    }
+}
+
 impl From<reqwest::Error> for Error {
     fn from(err: reqwest::Error) -> Self {
         Error::WebDav(err.to_string())
@@ -59,3 +120,33 @@ impl From<reqwest::Error> for Error {
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_not_found() {
+        assert!(Error::ListNotFound("x".into()).is_not_found());
+        assert!(Error::TaskNotFound("x".into()).is_not_found());
+        assert!(Error::WorkspaceNotFound("x".into()).is_not_found());
+        assert!(Error::NotFound("x".into()).is_not_found());
+        assert!(!Error::InvalidData("x".into()).is_not_found());
+    }
+
+    #[test]
+    fn test_is_invalid_data() {
+        assert!(Error::InvalidPath("x".into()).is_invalid_data());
+        assert!(Error::Serialization("x".into()).is_invalid_data());
+        assert!(Error::ListAlreadyExists("x".into()).is_invalid_data());
+        assert!(!Error::WebDav("x".into()).is_invalid_data());
+    }
+
+    #[test]
+    fn test_io_error_source() {
+        use std::error::Error as _;
+        let err = Error::from(io::Error::other("disk"));
+        assert!(err.is_io());
+        assert!(err.source().is_some());
+    }
+}
@@ -0,0 +1,590 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, Read, Write};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::error::{Error, Result};
+use crate::models::Task;
+use crate::repository::TaskRepository;
+
+/// One exported task together with the list it belongs to. In `ndjson` exports each line
+/// is one record; the task's own fields sit at the top level next to `list_id` and `list`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportRecord {
+    pub list_id: Uuid,
+    /// Title of the list, used on import when no list has `list_id`.
+    pub list: String,
+    #[serde(flatten)]
+    pub task: Task,
+}
+
+/// What [`import_records`] does with a preserved ID that is already taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IdCollision {
+    /// Fail before importing anything.
+    #[default]
+    Error,
+    /// Give the clashing task a fresh ID; the others keep theirs.
+    Rename,
+}
+
+/// What [`import_records`] does with a target list that already exists in the workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExistingList {
+    /// Add the imported tasks after the list's own; clashing titles get numbered.
+    #[default]
+    Merge,
+    /// Leave the list alone and import none of its tasks.
+    Skip,
+    /// Delete the list's tasks first, so it holds exactly the imported ones. The list
+    /// itself (ID, position, settings, sublists) stays.
+    Replace,
+}
+
+/// Options for [`import_records`].
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Put every task in this list instead of matching lists by ID or title.
+    pub into: Option<Uuid>,
+    /// Keep the exported task IDs instead of generating new ones, e.g. when restoring.
+    pub preserve_ids: bool,
+    /// Only used with `preserve_ids`.
+    pub on_collision: IdCollision,
+    /// Applies to lists that existed before the import, `into` included.
+    pub on_existing: ExistingList,
+}
+
+/// Outcome of [`import_records`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    /// IDs given to the imported tasks, in import order.
+    pub imported: Vec<Uuid>,
+    /// Tasks imported under a numbered title (`Title (2)`, ...) because their list already
+    /// had a task with that title.
+    pub renamed: Vec<String>,
+    /// Lists created because no existing list matched the record's ID or title.
+    pub created_lists: Vec<String>,
+    /// Existing lists whose tasks were not imported ([`ExistingList::Skip`]).
+    pub skipped_lists: Vec<String>,
+    /// Existing lists emptied before their tasks were imported ([`ExistingList::Replace`]).
+    pub replaced_lists: Vec<String>,
+    /// Exported IDs that were already taken, with the fresh ID each task got instead
+    /// (only with `preserve_ids` and [`IdCollision::Rename`]).
+    pub reassigned: Vec<(Uuid, Uuid)>,
+}
+
+/// Titles of all lists by ID, read without loading any tasks.
+fn list_titles(repo: &TaskRepository) -> Result<HashMap<Uuid, String>> {
+    Ok(repo.get_list_headers()?
+        .into_iter()
+        .map(|list| (list.id, list.title))
+        .collect())
+}
+
+/// Tasks paired with their list IDs, read as the iterator advances.
+type TaskStream<'a> = Box<dyn Iterator<Item = Result<(Uuid, Task)>> + 'a>;
+
+/// The tasks to export with their list IDs: every task in the workspace, or only those of
+/// list `list` (not its sublists). An unknown `list` is `Error::ListNotFound`.
+fn exported_tasks(repo: &TaskRepository, list: Option<Uuid>) -> Result<TaskStream<'_>> {
+    Ok(match list {
+        Some(list_id) => Box::new(repo.list_tasks(list_id)?.into_iter().map(move |task| Ok((list_id, task)))),
+        None => Box::new(repo.iter_tasks()?),
+    })
+}
+
+/// Write every task, or with `list` only that list's, as one pretty-printed JSON array.
+/// The whole array is built in memory first; use [`export_ndjson`] for large workspaces.
+/// Returns the number of tasks written.
+pub fn export_json(repo: &TaskRepository, list: Option<Uuid>, mut out: impl Write) -> Result<usize> {
+    let titles = list_titles(repo)?;
+    let records = exported_tasks(repo, list)?
+        .map(|item| item.map(|(list_id, task)| ExportRecord {
+            list_id,
+            list: titles.get(&list_id).cloned().unwrap_or_default(),
+            task,
+        }))
+        .collect::<Result<Vec<_>>>()?;
+    serde_json::to_writer_pretty(&mut out, &records)?;
+    out.write_all(b"\n")?;
+    out.flush()?;
+    Ok(records.len())
+}
+
+/// Write every task, or with `list` only that list's, as newline-delimited JSON, one
+/// [`ExportRecord`] per line, in list and task order. A whole workspace streams from
+/// [`TaskRepository::iter_tasks`] straight to `out`, so only one task is in memory at a
+/// time. Returns the number of tasks written.
+pub fn export_ndjson(repo: &TaskRepository, list: Option<Uuid>, mut out: impl Write) -> Result<usize> {
+    let titles = list_titles(repo)?;
+    let mut count = 0;
+    for item in exported_tasks(repo, list)? {
+        let (list_id, task) = item?;
+        let record = ExportRecord {
+            list_id,
+            list: titles.get(&list_id).cloned().unwrap_or_default(),
+            task,
+        };
+        serde_json::to_writer(&mut out, &record)?;
+        out.write_all(b"\n")?;
+        count += 1;
+    }
+    out.flush()?;
+    Ok(count)
+}
+
+/// Parse an export written by [`export_json`].
+pub fn read_json(reader: impl Read) -> Result<Vec<ExportRecord>> {
+    Ok(serde_json::from_reader(reader)?)
+}
+
+/// Lazily parse an export written by [`export_ndjson`], one line at a time. Blank lines are
+/// skipped; a malformed line yields an error naming its line number.
+pub fn read_ndjson(reader: impl BufRead) -> impl Iterator<Item = Result<ExportRecord>> {
+    reader.lines().enumerate().filter_map(|(i, line)| match line {
+        Err(e) => Some(Err(e.into())),
+        Ok(line) if line.trim().is_empty() => None,
+        Ok(line) => Some(serde_json::from_str(&line)
+            .map_err(|e| Error::InvalidData(format!("Line {}: {}", i + 1, e)))),
+    })
+}
+
+/// Create a task for every record. Tasks get fresh IDs unless `options.preserve_ids` is
+/// set, in which case an ID already used by a task in the workspace (or earlier in the
+/// import) is handled per `options.on_collision`. Parent links between imported tasks
+/// follow the tasks to their new IDs. Tasks go into `options.into` when given, otherwise
+/// into the list with the record's `list_id`, else the list with its title, else a new
+/// list with that title. Titles double as filenames, so a task whose title is already
+/// taken in its list gets a numbered title, like in [`TaskRepository::merge_lists`].
+/// Lists that already existed are merged into, skipped or emptied first, per
+/// `options.on_existing`.
+///
+/// All records are read before anything is written, so a malformed record or an ID
+/// collision leaves the workspace untouched. That means they are all held in memory at
+/// once: reading an export with [`read_ndjson`] keeps memory bounded only up to here.
+pub fn import_records(
+    repo: &mut TaskRepository,
+    records: impl IntoIterator<Item = Result<ExportRecord>>,
+    options: &ImportOptions,
+) -> Result<ImportReport> {
+    let mut records = records.into_iter().collect::<Result<Vec<_>>>()?;
+    let mut report = ImportReport::default();
+
+    // Existing lists the records would go into, in the order they are first reached
+    let before = list_titles(repo)?;
+    let existing_target = |record: &ExportRecord| -> Option<Uuid> {
+        options.into.or_else(|| {
+            if before.contains_key(&record.list_id) {
+                Some(record.list_id)
+            } else {
+                before.iter().find(|(_, title)| **title == record.list).map(|(&id, _)| id)
+            }
+        })
+    };
+    let mut reached: Vec<Uuid> = Vec::new();
+    for id in records.iter().filter_map(existing_target) {
+        if !reached.contains(&id) {
+            reached.push(id);
+        }
+    }
+    let to_replace: HashSet<Uuid> = match options.on_existing {
+        ExistingList::Merge => HashSet::new(),
+        ExistingList::Skip => {
+            records.retain(|record| existing_target(record).is_none());
+            report.skipped_lists = reached.iter().map(|id| before[id].clone()).collect();
+            HashSet::new()
+        }
+        ExistingList::Replace => reached.iter().copied().collect(),
+    };
+
+    // The ID each record is imported under, decided up front so a collision aborts early
+    let ids: Vec<Uuid> = if options.preserve_ids {
+        // Tasks of lists about to be emptied give their IDs up
+        let mut used = repo.iter_tasks()?
+            .filter(|item| !item.as_ref().is_ok_and(|(list_id, _)| to_replace.contains(list_id)))
+            .map(|item| item.map(|(_, task)| task.id))
+            .collect::<Result<HashSet<_>>>()?;
+        let mut ids = Vec::with_capacity(records.len());
+        for record in &records {
+            let id = record.task.id;
+            if used.insert(id) {
+                ids.push(id);
+                continue;
+            }
+            match options.on_collision {
+                IdCollision::Error => {
+                    return Err(Error::InvalidData(format!(
+                        "Task \"{}\" has ID {}, which is already in use; nothing was imported",
+                        record.task.title, id
+                    )));
+                }
+                IdCollision::Rename => {
+                    let fresh = Uuid::new_v4();
+                    used.insert(fresh);
+                    report.reassigned.push((id, fresh));
+                    ids.push(fresh);
+                }
+            }
+        }
+        ids
+    } else {
+        records.iter().map(|_| Uuid::new_v4()).collect()
+    };
+
+    for list_id in reached.iter().filter(|id| to_replace.contains(id)) {
+        for task in repo.list_tasks(*list_id)? {
+            repo.delete_task(*list_id, task.id)?;
+        }
+        report.replaced_lists.push(before[list_id].clone());
+    }
+
+    let mut existing = before;
+    let mut targets: HashMap<Uuid, Uuid> = HashMap::new();
+    let mut titles: HashMap<Uuid, HashSet<String>> = HashMap::new();
+    let mut new_ids: HashMap<Uuid, Uuid> = HashMap::new();
+    // Subtasks seen before their parent: (list, new task ID, exported parent ID)
+    let mut unlinked: Vec<(Uuid, Uuid, Uuid)> = Vec::new();
+
+    for (record, id) in records.into_iter().zip(ids) {
+        let list_id = match options.into.or_else(|| targets.get(&record.list_id).copied()) {
+            Some(id) => id,
+            None => {
+                let id = if existing.contains_key(&record.list_id) {
+                    record.list_id
+                } else if let Some((&id, _)) = existing.iter().find(|(_, title)| **title == record.list) {
+                    id
+                } else {
+                    let id = repo.create_list_with_id(record.list.clone())?;
+                    existing.insert(id, record.list.clone());
+                    report.created_lists.push(record.list.clone());
+                    id
+                };
+                targets.insert(record.list_id, id);
+                id
+            }
+        };
+
+        let taken = match titles.entry(list_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(
+                repo.list_tasks(list_id)?.into_iter().map(|task| task.title).collect(),
+            ),
+        };
+
+        let mut task = record.task;
+        let exported_id = task.id;
+        task.id = id;
+        if let Some(parent) = task.parent_id {
+            task.parent_id = new_ids.get(&parent).copied();
+            if task.parent_id.is_none() {
+                unlinked.push((list_id, task.id, parent));
+            }
+        }
+        if taken.contains(&task.title) {
+            task.title = (2..)
+                .map(|n| format!("{} ({})", task.title, n))
+                .find(|candidate| !taken.contains(candidate))
+                .expect("unbounded range yields a free title");
+            report.renamed.push(task.title.clone());
+        }
+        taken.insert(task.title.clone());
+        new_ids.insert(exported_id, task.id);
+        report.imported.push(task.id);
+        repo.create_task(list_id, task)?;
+    }
+
+    for (list_id, task_id, parent) in unlinked {
+        if let Some(&parent_id) = new_ids.get(&parent) {
+            let mut task = repo.get_task(list_id, task_id)?;
+            task.parent_id = Some(parent_id);
+            repo.update_task(list_id, task)?;
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Priority, TaskStatus};
+    use tempfile::TempDir;
+
+    fn titles_by_list(repo: &TaskRepository) -> Vec<(String, Vec<String>)> {
+        repo.get_lists().unwrap()
+            .into_iter()
+            .map(|list| (list.title, list.tasks.into_iter().map(|t| t.title).collect()))
+            .collect()
+    }
+
+    fn sample_repo(dir: &TempDir) -> TaskRepository {
+        let mut repo = TaskRepository::init(dir.path().to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+        let home = repo.create_list("Home".to_string()).unwrap();
+        let report = repo.create_task(work.id, Task::builder("Report").priority(Priority::High).tags(["q3"]).description("Draft\nthen send").build()).unwrap();
+        repo.create_task(work.id, Task::builder("Outline").parent_id(report.id).build()).unwrap();
+        repo.create_task(home.id, Task::builder("Dishes").status(TaskStatus::Completed).build()).unwrap();
+        repo
+    }
+
+    #[test]
+    fn test_ndjson_export_is_one_record_per_line() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = sample_repo(&temp_dir);
+
+        let mut out = Vec::new();
+        assert_eq!(export_ndjson(&repo, None, &mut out).unwrap(), 3);
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["list"], "Work");
+        assert_eq!(first["title"], "Report");
+        assert_eq!(first["priority"], "high");
+    }
+
+    #[test]
+    fn test_export_single_list() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = sample_repo(&temp_dir);
+        let work = repo.find_list_by_name("Work").unwrap();
+
+        let mut out = Vec::new();
+        assert_eq!(export_json(&repo, Some(work.id), &mut out).unwrap(), 2);
+        let records = read_json(out.as_slice()).unwrap();
+        assert_eq!(records.iter().map(|r| r.task.title.as_str()).collect::<Vec<_>>(), vec!["Report", "Outline"]);
+        assert!(records.iter().all(|r| r.list_id == work.id && r.list == "Work"));
+
+        let mut out = Vec::new();
+        assert_eq!(export_ndjson(&repo, Some(work.id), &mut out).unwrap(), 2);
+        let titles: Vec<String> = read_ndjson(out.as_slice()).map(|r| r.unwrap().task.title).collect();
+        assert_eq!(titles, vec!["Report", "Outline"]);
+
+        let err = export_ndjson(&repo, Some(Uuid::new_v4()), &mut Vec::new()).unwrap_err();
+        assert!(matches!(err, Error::ListNotFound(_)));
+    }
+
+    #[test]
+    fn test_ndjson_roundtrip_into_new_workspace() {
+        let source_dir = TempDir::new().unwrap();
+        let source = sample_repo(&source_dir);
+        let mut out = Vec::new();
+        export_ndjson(&source, None, &mut out).unwrap();
+
+        let dest_dir = TempDir::new().unwrap();
+        let mut dest = TaskRepository::init(dest_dir.path().to_path_buf()).unwrap();
+        let report = import_records(&mut dest, read_ndjson(out.as_slice()), &ImportOptions::default()).unwrap();
+
+        assert_eq!(report.imported.len(), 3);
+        assert_eq!(report.created_lists, vec!["Work", "Home"]);
+        assert_eq!(titles_by_list(&dest), titles_by_list(&source));
+
+        let work = dest.find_list_by_name("Work").unwrap();
+        let (report_task, outline) = (&work.tasks[0], &work.tasks[1]);
+        assert_eq!(report_task.priority, Some(Priority::High));
+        assert_eq!(report_task.description, "Draft\nthen send");
+        assert_eq!(outline.parent_id, Some(report_task.id));
+        let dishes = &dest.find_list_by_name("Home").unwrap().tasks[0];
+        assert_eq!(dishes.status, TaskStatus::Completed);
+    }
+
+    #[test]
+    fn test_json_roundtrip_into_same_workspace_renames_clashes() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = sample_repo(&temp_dir);
+        let mut out = Vec::new();
+        assert_eq!(export_json(&repo, None, &mut out).unwrap(), 3);
+
+        let report = import_records(&mut repo, read_json(out.as_slice()).unwrap().into_iter().map(Ok), &ImportOptions::default()).unwrap();
+        assert!(report.created_lists.is_empty());
+        assert_eq!(report.renamed, vec!["Report (2)", "Outline (2)", "Dishes (2)"]);
+        let work = repo.find_list_by_name("Work").unwrap();
+        assert_eq!(work.tasks.len(), 4);
+        // The copy of the subtask points at the copy of its parent
+        assert_eq!(work.tasks[3].parent_id, Some(work.tasks[2].id));
+    }
+
+    #[test]
+    fn test_import_links_subtask_listed_before_parent() {
+        let source_dir = TempDir::new().unwrap();
+        let mut source = sample_repo(&source_dir);
+        let work = source.find_list_by_name("Work").unwrap();
+        source.reorder_task(work.id, work.tasks[1].id, 0).unwrap();
+        let mut out = Vec::new();
+        export_ndjson(&source, None, &mut out).unwrap();
+
+        let dest_dir = TempDir::new().unwrap();
+        let mut dest = TaskRepository::init(dest_dir.path().to_path_buf()).unwrap();
+        let inbox = dest.create_list("Inbox".to_string()).unwrap();
+        import_records(&mut dest, read_ndjson(out.as_slice()), &ImportOptions { into: Some(inbox.id), ..Default::default() }).unwrap();
+
+        let tasks = dest.list_tasks(inbox.id).unwrap();
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].title, "Outline");
+        assert_eq!(tasks[0].parent_id, Some(tasks[1].id));
+    }
+
+    fn export(repo: &TaskRepository) -> Vec<u8> {
+        let mut out = Vec::new();
+        export_ndjson(repo, None, &mut out).unwrap();
+        out
+    }
+
+    fn all_ids(repo: &TaskRepository) -> Vec<Uuid> {
+        repo.iter_tasks().unwrap().map(|item| item.unwrap().1.id).collect()
+    }
+
+    /// An export holding "Work" (Report, Plan) and "Garden" (Weed), to import into
+    /// [`sample_repo`], which already has "Work" (Report, Outline) and "Home" (Dishes).
+    fn overlapping_export() -> Vec<u8> {
+        let dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(dir.path().to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+        let garden = repo.create_list("Garden".to_string()).unwrap();
+        repo.create_task(work.id, Task::new("Report".to_string())).unwrap();
+        repo.create_task(work.id, Task::new("Plan".to_string())).unwrap();
+        repo.create_task(garden.id, Task::new("Weed".to_string())).unwrap();
+        export(&repo)
+    }
+
+    fn import_with(on_existing: ExistingList) -> (Vec<(String, Vec<String>)>, ImportReport) {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = sample_repo(&temp_dir);
+        let options = ImportOptions { on_existing, ..Default::default() };
+        let report = import_records(&mut repo, read_ndjson(overlapping_export().as_slice()), &options).unwrap();
+        (titles_by_list(&repo), report)
+    }
+
+    fn lists(lists: &[(&str, &[&str])]) -> Vec<(String, Vec<String>)> {
+        lists.iter()
+            .map(|(list, tasks)| (list.to_string(), tasks.iter().map(|t| t.to_string()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_import_refuses_tags_a_strict_workspace_does_not_allow() {
+        let source_dir = TempDir::new().unwrap();
+        let records = export(&sample_repo(&source_dir));
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        repo.set_workspace_settings(&crate::config::WorkspaceSettings {
+            allowed_tags: vec!["work".to_string()],
+            strict_tags: Some(true),
+            ..Default::default()
+        }).unwrap();
+        let result = import_records(&mut repo, read_ndjson(records.as_slice()), &ImportOptions::default());
+        assert!(matches!(result, Err(Error::InvalidData(message)) if message.contains("q3")));
+    }
+
+    #[test]
+    fn test_on_existing_merge_appends_to_existing_lists() {
+        let (titles, report) = import_with(ExistingList::Merge);
+        assert_eq!(titles, lists(&[
+            ("Work", &["Report", "Outline", "Report (2)", "Plan"]),
+            ("Home", &["Dishes"]),
+            ("Garden", &["Weed"]),
+        ]));
+        assert_eq!(report.imported.len(), 3);
+        assert_eq!(report.created_lists, vec!["Garden"]);
+        assert!(report.skipped_lists.is_empty() && report.replaced_lists.is_empty());
+    }
+
+    #[test]
+    fn test_on_existing_skip_leaves_existing_lists_alone() {
+        let (titles, report) = import_with(ExistingList::Skip);
+        assert_eq!(titles, lists(&[
+            ("Work", &["Report", "Outline"]),
+            ("Home", &["Dishes"]),
+            ("Garden", &["Weed"]),
+        ]));
+        assert_eq!(report.imported.len(), 1);
+        assert_eq!(report.skipped_lists, vec!["Work"]);
+    }
+
+    #[test]
+    fn test_on_existing_replace_empties_existing_lists_first() {
+        let (titles, report) = import_with(ExistingList::Replace);
+        assert_eq!(titles, lists(&[
+            ("Work", &["Report", "Plan"]),
+            ("Home", &["Dishes"]),
+            ("Garden", &["Weed"]),
+        ]));
+        assert_eq!(report.replaced_lists, vec!["Work"]);
+        assert!(report.renamed.is_empty());
+
+        // Restoring a workspace's own export over it frees the IDs being replaced, and
+        // doing it again changes nothing
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = sample_repo(&temp_dir);
+        let out = export(&repo);
+        let before = (titles_by_list(&repo), all_ids(&repo));
+        let options = ImportOptions { preserve_ids: true, on_existing: ExistingList::Replace, ..Default::default() };
+        for _ in 0..2 {
+            import_records(&mut repo, read_ndjson(out.as_slice()), &options).unwrap();
+            assert_eq!((titles_by_list(&repo), all_ids(&repo)), before);
+        }
+    }
+
+    #[test]
+    fn test_preserve_ids_without_collision() {
+        let source_dir = TempDir::new().unwrap();
+        let source = sample_repo(&source_dir);
+        let out = export(&source);
+
+        let dest_dir = TempDir::new().unwrap();
+        let mut dest = TaskRepository::init(dest_dir.path().to_path_buf()).unwrap();
+        let options = ImportOptions { preserve_ids: true, ..Default::default() };
+        let report = import_records(&mut dest, read_ndjson(out.as_slice()), &options).unwrap();
+
+        assert_eq!(report.imported, all_ids(&source));
+        assert!(report.reassigned.is_empty());
+        assert_eq!(all_ids(&dest), all_ids(&source));
+        let work = dest.find_list_by_name("Work").unwrap();
+        assert_eq!(work.tasks[1].parent_id, Some(work.tasks[0].id));
+    }
+
+    #[test]
+    fn test_preserve_ids_collision_errors_and_imports_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = sample_repo(&temp_dir);
+        let out = export(&repo);
+        let before = all_ids(&repo);
+
+        let options = ImportOptions { preserve_ids: true, ..Default::default() };
+        let err = import_records(&mut repo, read_ndjson(out.as_slice()), &options).unwrap_err();
+        assert!(err.to_string().contains("already in use"));
+        assert_eq!(all_ids(&repo), before);
+    }
+
+    #[test]
+    fn test_preserve_ids_collision_rename_gives_fresh_ids() {
+        let source_dir = TempDir::new().unwrap();
+        let source = sample_repo(&source_dir);
+        let out = export(&source);
+        let exported = all_ids(&source);
+
+        // The destination already has the "Dishes" task, so only that one clashes
+        let dest_dir = TempDir::new().unwrap();
+        let mut dest = TaskRepository::init(dest_dir.path().to_path_buf()).unwrap();
+        let other = dest.create_list("Other".to_string()).unwrap();
+        let dishes = source.find_list_by_name("Home").unwrap().tasks.remove(0);
+        dest.create_task(other.id, dishes).unwrap();
+
+        let options = ImportOptions { preserve_ids: true, on_collision: IdCollision::Rename, ..Default::default() };
+        let report = import_records(&mut dest, read_ndjson(out.as_slice()), &options).unwrap();
+
+        assert_eq!(report.reassigned.len(), 1);
+        let (old, fresh) = report.reassigned[0];
+        assert_eq!(old, exported[2]);
+        assert_eq!(report.imported, vec![exported[0], exported[1], fresh]);
+        assert_eq!(dest.find_list_by_name("Home").unwrap().tasks[0].id, fresh);
+    }
+
+    #[test]
+    fn test_read_ndjson_reports_bad_line() {
+        let input = "\n{\"not\": \"a task\"}\n";
+        let err = read_ndjson(input.as_bytes()).next().unwrap().unwrap_err();
+        assert!(err.to_string().contains("Line 2"));
+    }
+}
@@ -5,8 +5,18 @@ pub mod config;
 pub mod error;
 pub mod webdav;
 pub mod sync;
+pub mod backup;
+pub mod streak;
+pub mod query;
+pub mod export;
+pub mod service;
+pub mod quick;
+pub mod upcoming;
+pub mod search;
 
 pub use models::{Task, TaskStatus, TaskList};
-pub use repository::TaskRepository;
-pub use config::{AppConfig, WorkspaceConfig};
+pub use query::{QuerySort, TaskQuery};
+pub use repository::{AllTasksOptions, DueReminder, FolderContents, GetListsOptions, IntegrityReport, MergePreview, MergeReport, SplitReport, TaskRepository};
+pub use service::TaskService;
+pub use config::{AppConfig, WorkspaceConfig, WorkspaceSettings};
 pub use error::{Error, Result};
@@ -1,6 +1,15 @@
-use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use crate::config::DateOnlyDue;
+use crate::error::{Error, Result};
+
+/// `title` with surrounding whitespace trimmed and every run of whitespace inside it (tabs
+/// and line breaks too) collapsed to a single space, as pasted titles often need.
+pub fn normalize_title(title: &str) -> String {
+    title.split_whitespace().collect::<Vec<_>>().join(" ")
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -9,6 +18,37 @@ pub enum TaskStatus {
     Completed,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+impl std::str::FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(Priority::Low),
+            "medium" | "med" => Ok(Priority::Medium),
+            "high" => Ok(Priority::High),
+            _ => Err(format!("Invalid priority '{}'. Use low, medium, or high", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Priority::Low => write!(f, "low"),
+            Priority::Medium => write!(f, "medium"),
+            Priority::High => write!(f, "high"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     pub id: Uuid,
@@ -21,26 +61,72 @@ pub struct Task {
     pub has_time: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// When the task was last marked complete; cleared when it is reopened.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent_id: Option<Uuid>,
+    /// Tasks that have to be completed before this one can start, in any list; see
+    /// `TaskRepository::blocked_tasks`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Free-form name of the person the task is assigned to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    /// Short free-form marker (usually an emoji) shown before the title to help scanning.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Custom key/value pairs set by the user, e.g. a link to an issue in another tool.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reminders: Vec<DateTime<Utc>>,
+    /// Reminders from `reminders` that have already been delivered.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reminders_sent: Vec<DateTime<Utc>>,
+    /// Frontmatter keys this version doesn't know (written by another tool or a newer
+    /// version), kept so they survive a read/write cycle.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra_fields: BTreeMap<String, serde_yaml::Value>,
 }
 
 impl Task {
+    /// A new open task. The title is normalized with [`normalize_title`]; creating a task
+    /// whose title ends up empty fails in the repository.
     pub fn new(title: String) -> Self {
         let now = Utc::now();
         Self {
             id: Uuid::new_v4(),
-            title,
+            title: normalize_title(&title),
             description: String::new(),
             status: TaskStatus::Backlog,
             due_date: None,
             has_time: false,
             created_at: now,
             updated_at: now,
+            completed_at: None,
             parent_id: None,
+            depends_on: Vec::new(),
+            priority: None,
+            tags: Vec::new(),
+            assignee: None,
+            label: None,
+            fields: BTreeMap::new(),
+            reminders: Vec::new(),
+            reminders_sent: Vec::new(),
+            extra_fields: BTreeMap::new(),
         }
     }
 
+    /// Start building a task with optional fields set fluently.
+    pub fn builder(title: impl Into<String>) -> TaskBuilder {
+        TaskBuilder::new(title)
+    }
+
     pub fn with_description(mut self, description: String) -> Self {
         self.description = description;
         self
@@ -56,17 +142,249 @@ impl Task {
         self
     }
 
+    /// Which day bucket the task falls in at `now`, judged by calendar days in `tz`.
+    pub fn due_bucket<Tz: TimeZone>(&self, now: DateTime<Utc>, tz: &Tz) -> DueBucket {
+        let Some(due) = self.due_date else {
+            return DueBucket::NoDueDate;
+        };
+        if self.is_overdue(now) {
+            return DueBucket::Overdue;
+        }
+        let today = now.with_timezone(tz).date_naive();
+        let due_day = due.with_timezone(tz).date_naive();
+        match due_day.cmp(&today) {
+            std::cmp::Ordering::Less => DueBucket::Earlier,
+            std::cmp::Ordering::Equal => DueBucket::Today,
+            std::cmp::Ordering::Greater => DueBucket::Upcoming,
+        }
+    }
+
+    /// Whether the task is still open and its due date has passed at `now`.
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.status == TaskStatus::Backlog && self.due_date.is_some_and(|due| due < now)
+    }
+
+    /// Whether the task is assigned to `name`, compared case-insensitively and ignoring
+    /// surrounding whitespace.
+    pub fn is_assigned_to(&self, name: &str) -> bool {
+        self.assignee.as_deref()
+            .is_some_and(|assignee| assignee.trim().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Add a reminder at `at`, keeping `reminders` sorted. Returns false if one already exists then.
+    pub fn add_reminder(&mut self, at: DateTime<Utc>) -> bool {
+        if self.reminders.contains(&at) {
+            return false;
+        }
+        let pos = self.reminders.partition_point(|r| *r < at);
+        self.reminders.insert(pos, at);
+        self.updated_at = Utc::now();
+        true
+    }
+
+    /// Reminders that are due at `now` and have not been sent yet. Completed tasks have none.
+    pub fn due_reminders(&self, now: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        if self.status == TaskStatus::Completed {
+            return Vec::new();
+        }
+        self.reminders.iter()
+            .filter(|at| **at <= now && !self.reminders_sent.contains(at))
+            .copied()
+            .collect()
+    }
+
+    /// Record a reminder as delivered so [`Self::due_reminders`] no longer returns it.
+    pub fn mark_reminder_sent(&mut self, at: DateTime<Utc>) {
+        if !self.reminders_sent.contains(&at) {
+            self.reminders_sent.push(at);
+            self.reminders_sent.sort();
+        }
+    }
+
+    /// Change the title, normalized with [`normalize_title`]. Fails with
+    /// `Error::InvalidData` if nothing is left of it.
+    pub fn set_title(&mut self, title: &str) -> Result<()> {
+        let title = normalize_title(title);
+        if title.is_empty() {
+            return Err(Error::InvalidData("Task title can't be empty".to_string()));
+        }
+        self.title = title;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
     pub fn complete(&mut self) {
+        let now = Utc::now();
         self.status = TaskStatus::Completed;
-        self.updated_at = Utc::now();
+        self.completed_at = Some(now);
+        self.updated_at = now;
     }
 
     pub fn uncomplete(&mut self) {
         self.status = TaskStatus::Backlog;
+        self.completed_at = None;
         self.updated_at = Utc::now();
     }
 }
 
+/// Day bucket used for "overdue"/"today" views and grouping by due date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DueBucket {
+    /// Open and past its due date.
+    Overdue,
+    /// Completed, with a due date on an earlier day.
+    Earlier,
+    Today,
+    Upcoming,
+    NoDueDate,
+}
+
+impl std::fmt::Display for DueBucket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DueBucket::Overdue => write!(f, "Overdue"),
+            DueBucket::Earlier => write!(f, "Earlier"),
+            DueBucket::Today => write!(f, "Today"),
+            DueBucket::Upcoming => write!(f, "Upcoming"),
+            DueBucket::NoDueDate => write!(f, "No due date"),
+        }
+    }
+}
+
+/// Resolve a date-only due date to an instant, using the given time of day in `tz`.
+///
+/// A local time that doesn't exist (DST gap) falls back to the earliest valid mapping,
+/// or to UTC midnight/end-of-day if the day has no mapping at all.
+pub fn resolve_date_only_due<Tz: TimeZone>(date: NaiveDate, at: DateOnlyDue, tz: &Tz) -> DateTime<Utc> {
+    let time = match at {
+        DateOnlyDue::StartOfDay => NaiveTime::MIN,
+        DateOnlyDue::EndOfDay => NaiveTime::from_hms_opt(23, 59, 59).expect("valid time"),
+    };
+    let naive = date.and_time(time);
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| naive.and_utc())
+}
+
+/// Fluent builder for [`Task`]. Unset fields take the same defaults as [`Task::new`].
+///
+/// ```
+/// use onyx_core::{Task, models::Priority};
+///
+/// let task = Task::builder("Review PR")
+///     .description("Check the auth changes")
+///     .priority(Priority::High)
+///     .tag("work")
+///     .build();
+/// assert_eq!(task.tags, vec!["work"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TaskBuilder {
+    task: Task,
+}
+
+impl TaskBuilder {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self { task: Task::new(title.into()) }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.task.title = normalize_title(&title.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.task.description = description.into();
+        self
+    }
+
+    /// Set a due date. `has_time` stays false (date-only) unless [`Self::due_time`] is used.
+    pub fn due_date(mut self, due_date: DateTime<Utc>) -> Self {
+        self.task.due_date = Some(due_date);
+        self.task.has_time = false;
+        self
+    }
+
+    /// Set a due date that includes a specific time of day.
+    pub fn due_time(mut self, due: DateTime<Utc>) -> Self {
+        self.task.due_date = Some(due);
+        self.task.has_time = true;
+        self
+    }
+
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.task.priority = Some(priority);
+        self
+    }
+
+    pub fn tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.task.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        if !self.task.tags.contains(&tag) {
+            self.task.tags.push(tag);
+        }
+        self
+    }
+
+    pub fn parent_id(mut self, parent_id: Uuid) -> Self {
+        self.task.parent_id = Some(parent_id);
+        self
+    }
+
+    pub fn depends_on(mut self, task_id: Uuid) -> Self {
+        if !self.task.depends_on.contains(&task_id) {
+            self.task.depends_on.push(task_id);
+        }
+        self
+    }
+
+    pub fn assignee(mut self, assignee: impl Into<String>) -> Self {
+        self.task.assignee = Some(assignee.into());
+        self
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.task.label = Some(label.into());
+        self
+    }
+
+    pub fn field(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.task.fields.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn reminder(mut self, at: DateTime<Utc>) -> Self {
+        if !self.task.reminders.contains(&at) {
+            let pos = self.task.reminders.partition_point(|r| *r < at);
+            self.task.reminders.insert(pos, at);
+        }
+        self
+    }
+
+    /// Set the status. A completed task gets `completed_at` set to its `updated_at`.
+    pub fn status(mut self, status: TaskStatus) -> Self {
+        self.task.status = status;
+        self.task.completed_at = match status {
+            TaskStatus::Completed => Some(self.task.updated_at),
+            TaskStatus::Backlog => None,
+        };
+        self
+    }
+
+    pub fn build(self) -> Task {
+        self.task
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskList {
     pub id: Uuid,
@@ -75,6 +393,16 @@ pub struct TaskList {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub group_by_due_date: bool,
+    /// The list this one is nested under, or `None` for a top-level list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<Uuid>,
+    /// Nesting level: 0 for top-level lists, 1 for their sublists, and so on.
+    #[serde(default)]
+    pub depth: usize,
+    /// Earliest due date among the list's open tasks, or `None` if none has one. Filled
+    /// in when lists are read with their tasks; not stored on disk.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_due: Option<DateTime<Utc>>,
 }
 
 impl TaskList {
@@ -87,18 +415,50 @@ impl TaskList {
             created_at: now,
             updated_at: now,
             group_by_due_date: false,
+            parent_id: None,
+            depth: 0,
+            next_due: None,
+        }
+    }
+
+    /// Recompute `next_due` from `tasks`, ignoring completed ones. The task methods here
+    /// keep it current; call this after changing `tasks` directly.
+    pub fn refresh_next_due(&mut self) {
+        self.next_due = self.tasks.iter()
+            .filter(|t| t.status != TaskStatus::Completed)
+            .filter_map(|t| t.due_date)
+            .min();
+    }
+
+    pub fn completed_count(&self) -> usize {
+        self.tasks.iter().filter(|t| t.status == TaskStatus::Completed).count()
+    }
+
+    pub fn open_count(&self) -> usize {
+        self.tasks.len() - self.completed_count()
+    }
+
+    /// Share of tasks completed, from 0.0 to 1.0; 0.0 for a list without tasks.
+    pub fn completion_ratio(&self) -> f64 {
+        if self.tasks.is_empty() {
+            0.0
+        } else {
+            self.completed_count() as f64 / self.tasks.len() as f64
         }
     }
 
     pub fn add_task(&mut self, task: Task) {
         self.tasks.push(task);
         self.updated_at = Utc::now();
+        self.refresh_next_due();
     }
 
     pub fn remove_task(&mut self, task_id: Uuid) -> Option<Task> {
         if let Some(pos) = self.tasks.iter().position(|t| t.id == task_id) {
             self.updated_at = Utc::now();
-            Some(self.tasks.remove(pos))
+            let task = self.tasks.remove(pos);
+            self.refresh_next_due();
+            Some(task)
         } else {
             None
         }
@@ -116,9 +476,271 @@ impl TaskList {
         if let Some(existing) = self.get_task_mut(task.id) {
             *existing = task;
             self.updated_at = Utc::now();
+            self.refresh_next_due();
             true
         } else {
             false
         }
     }
 }
+
+/// The tasks across `lists` whose ID starts with `prefix` (ignoring case and surrounding
+/// whitespace), each with its list, in list order. `InvalidData` when `prefix` is empty or
+/// has anything besides hex digits and `-`, so can't be part of an ID.
+pub fn tasks_with_id_prefix<'a>(lists: &'a [TaskList], prefix: &str) -> Result<Vec<(&'a TaskList, &'a Task)>> {
+    let prefix = prefix.trim().to_lowercase();
+    if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+        return Err(Error::InvalidData(format!("'{}' is not a task ID or ID prefix", prefix)));
+    }
+    Ok(lists.iter()
+        .flat_map(|list| list.tasks.iter().map(move |task| (list, task)))
+        .filter(|(_, task)| task.id.to_string().starts_with(&prefix))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_builder_defaults_match_new() {
+        let task = Task::builder("Plain").build();
+        assert_eq!(task.title, "Plain");
+        assert!(task.description.is_empty());
+        assert_eq!(task.status, TaskStatus::Backlog);
+        assert!(task.due_date.is_none());
+        assert!(!task.has_time);
+        assert!(task.priority.is_none());
+        assert!(task.tags.is_empty());
+        assert!(task.parent_id.is_none());
+        assert_eq!(task.created_at, task.updated_at);
+    }
+
+    #[test]
+    fn test_titles_are_normalized() {
+        assert_eq!(Task::new("  Buy milk \t".to_string()).title, "Buy milk");
+        assert_eq!(Task::new("Buy\tmilk\r\nand  eggs\n".to_string()).title, "Buy milk and eggs");
+        assert_eq!(Task::builder("x").title("\tCall\n\nmum ").build().title, "Call mum");
+        assert_eq!(Task::new(" \n\t ".to_string()).title, "");
+
+        let mut task = Task::new("Old".to_string());
+        task.set_title(" New\ntitle  ").unwrap();
+        assert_eq!(task.title, "New title");
+        assert!(task.set_title("\t \r\n").unwrap_err().is_invalid_data());
+        assert_eq!(task.title, "New title");
+    }
+
+    #[test]
+    fn test_builder_all_fields() {
+        let parent = Uuid::new_v4();
+        let due = Utc.with_ymd_and_hms(2026, 6, 1, 9, 30, 0).unwrap();
+        let task = Task::builder("Full")
+            .description("Notes")
+            .due_time(due)
+            .priority(Priority::High)
+            .tags(["work", "urgent"])
+            .parent_id(parent)
+            .status(TaskStatus::Completed)
+            .build();
+
+        assert_eq!(task.description, "Notes");
+        assert_eq!(task.due_date, Some(due));
+        assert!(task.has_time);
+        assert_eq!(task.priority, Some(Priority::High));
+        assert_eq!(task.tags, vec!["work", "urgent"]);
+        assert_eq!(task.parent_id, Some(parent));
+        assert_eq!(task.status, TaskStatus::Completed);
+    }
+
+    #[test]
+    fn test_builder_date_only_and_dedup_tags() {
+        let due = Utc.with_ymd_and_hms(2026, 6, 1, 0, 0, 0).unwrap();
+        let task = Task::builder("Tagged")
+            .due_date(due)
+            .tag("home")
+            .tag("home")
+            .tag("errands")
+            .build();
+        assert!(!task.has_time);
+        assert_eq!(task.tags, vec!["home", "errands"]);
+    }
+
+    #[test]
+    fn test_resolve_date_only_end_of_day_local() {
+        let tz = chrono::FixedOffset::west_opt(5 * 3600).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+        let due = resolve_date_only_due(date, DateOnlyDue::EndOfDay, &tz);
+        assert_eq!(due, Utc.with_ymd_and_hms(2026, 6, 2, 4, 59, 59).unwrap());
+
+        let due = resolve_date_only_due(date, DateOnlyDue::StartOfDay, &tz);
+        assert_eq!(due, Utc.with_ymd_and_hms(2026, 6, 1, 5, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_overdue_boundary_end_of_day() {
+        let tz = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+        let task = Task::builder("Due June 1")
+            .due_date(resolve_date_only_due(date, DateOnlyDue::EndOfDay, &tz))
+            .build();
+
+        let local = |h, m, s| tz.with_ymd_and_hms(2026, 6, 1, h, m, s).unwrap().with_timezone(&Utc);
+        assert!(!task.is_overdue(local(0, 1, 0)));
+        assert!(!task.is_overdue(local(23, 59, 59)));
+        let next_day = tz.with_ymd_and_hms(2026, 6, 2, 0, 0, 0).unwrap().with_timezone(&Utc);
+        assert!(task.is_overdue(next_day));
+    }
+
+    #[test]
+    fn test_overdue_boundary_start_of_day_and_completed() {
+        let date = NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+        let mut task = Task::builder("Due June 1")
+            .due_date(resolve_date_only_due(date, DateOnlyDue::StartOfDay, &Utc))
+            .build();
+
+        assert!(!task.is_overdue(Utc.with_ymd_and_hms(2026, 6, 1, 0, 0, 0).unwrap()));
+        assert!(task.is_overdue(Utc.with_ymd_and_hms(2026, 6, 1, 0, 0, 1).unwrap()));
+
+        task.complete();
+        assert!(!task.is_overdue(Utc.with_ymd_and_hms(2026, 7, 1, 0, 0, 0).unwrap()));
+        assert!(!Task::new("No due".into()).is_overdue(Utc::now()));
+    }
+
+    #[test]
+    fn test_due_bucket_uses_local_day() {
+        // 22:00 UTC on June 1 is already June 2 at UTC+3.
+        let tz = chrono::FixedOffset::east_opt(3 * 3600).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 6, 1, 22, 0, 0).unwrap();
+        let june2 = NaiveDate::from_ymd_opt(2026, 6, 2).unwrap();
+
+        let task = Task::builder("Local today")
+            .due_date(resolve_date_only_due(june2, DateOnlyDue::EndOfDay, &tz))
+            .build();
+        assert_eq!(task.due_bucket(now, &tz), DueBucket::Today);
+        assert_eq!(task.due_bucket(now, &Utc), DueBucket::Upcoming);
+
+        let late = Task::builder("Late")
+            .due_time(now - chrono::Duration::hours(1))
+            .build();
+        assert_eq!(late.due_bucket(now, &tz), DueBucket::Overdue);
+
+        let done = Task::builder("Done")
+            .due_time(now - chrono::Duration::days(2))
+            .status(TaskStatus::Completed)
+            .build();
+        assert_eq!(done.due_bucket(now, &tz), DueBucket::Earlier);
+        assert_eq!(Task::new("None".into()).due_bucket(now, &tz), DueBucket::NoDueDate);
+    }
+
+    #[test]
+    fn test_due_reminders_with_fixed_clock() {
+        let at = |h| Utc.with_ymd_and_hms(2026, 6, 1, h, 0, 0).unwrap();
+        let mut task = Task::builder("Call back")
+            .reminder(at(15))
+            .reminder(at(9))
+            .reminder(at(12))
+            .build();
+        assert_eq!(task.reminders, vec![at(9), at(12), at(15)]);
+
+        assert!(task.due_reminders(at(8)).is_empty());
+        assert_eq!(task.due_reminders(at(9)), vec![at(9)]);
+        assert_eq!(task.due_reminders(at(13)), vec![at(9), at(12)]);
+
+        task.mark_reminder_sent(at(9));
+        assert_eq!(task.due_reminders(at(13)), vec![at(12)]);
+        task.mark_reminder_sent(at(12));
+        assert!(task.due_reminders(at(13)).is_empty());
+        assert_eq!(task.due_reminders(at(16)), vec![at(15)]);
+
+        task.complete();
+        assert!(task.due_reminders(at(16)).is_empty());
+    }
+
+    #[test]
+    fn test_add_reminder_sorted_and_deduplicated() {
+        let at = |h| Utc.with_ymd_and_hms(2026, 6, 1, h, 0, 0).unwrap();
+        let mut task = Task::new("Remind me".into());
+        assert!(task.add_reminder(at(10)));
+        assert!(task.add_reminder(at(8)));
+        assert!(!task.add_reminder(at(10)));
+        assert_eq!(task.reminders, vec![at(8), at(10)]);
+    }
+
+    #[test]
+    fn test_complete_sets_and_uncomplete_clears_completed_at() {
+        let mut task = Task::new("Habit".into());
+        assert!(task.completed_at.is_none());
+        task.complete();
+        assert_eq!(task.completed_at, Some(task.updated_at));
+        task.uncomplete();
+        assert!(task.completed_at.is_none());
+
+        let done = Task::builder("Done").status(TaskStatus::Completed).build();
+        assert_eq!(done.completed_at, Some(done.updated_at));
+    }
+
+    #[test]
+    fn test_is_assigned_to() {
+        let task = Task::builder("Shared").assignee("Alex Kim").build();
+        assert!(task.is_assigned_to("Alex Kim"));
+        assert!(task.is_assigned_to(" alex kim "));
+        assert!(!task.is_assigned_to("Alex"));
+        assert!(!Task::new("Nobody".into()).is_assigned_to("Alex Kim"));
+    }
+
+    #[test]
+    fn test_list_counts_empty() {
+        let list = TaskList::new("Empty".into());
+        assert_eq!((list.completed_count(), list.open_count()), (0, 0));
+        assert_eq!(list.completion_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_list_counts_mixed() {
+        let mut list = TaskList::new("Mixed".into());
+        list.add_task(Task::new("Open".into()));
+        list.add_task(Task::builder("Done").status(TaskStatus::Completed).build());
+        list.add_task(Task::builder("Also done").status(TaskStatus::Completed).build());
+        list.add_task(Task::new("Also open".into()));
+        list.add_task(Task::builder("Third done").status(TaskStatus::Completed).build());
+        assert_eq!((list.completed_count(), list.open_count()), (3, 2));
+        assert_eq!(list.completion_ratio(), 0.6);
+
+        for task in &mut list.tasks {
+            task.complete();
+        }
+        assert_eq!((list.completed_count(), list.open_count()), (5, 0));
+        assert_eq!(list.completion_ratio(), 1.0);
+    }
+
+    #[test]
+    fn test_priority_parse_and_display() {
+        assert_eq!("HIGH".parse::<Priority>().unwrap(), Priority::High);
+        assert_eq!("med".parse::<Priority>().unwrap(), Priority::Medium);
+        assert!("urgent".parse::<Priority>().is_err());
+        assert_eq!(Priority::Low.to_string(), "low");
+        assert!(Priority::High > Priority::Low);
+    }
+
+    #[test]
+    fn test_tasks_with_id_prefix() {
+        let mut work = TaskList::new("Work".to_string());
+        let mut home = TaskList::new("Home".to_string());
+        for (list, id) in [(&mut work, "aaaa1111-0000-4000-8000-000000000001"), (&mut home, "AAAA2222-0000-4000-8000-000000000002")] {
+            let mut task = Task::new(list.title.clone());
+            task.id = Uuid::parse_str(id).unwrap();
+            list.add_task(task);
+        }
+        let lists = [work, home];
+
+        let titles = |prefix: &str| -> Vec<String> {
+            tasks_with_id_prefix(&lists, prefix).unwrap().into_iter().map(|(list, _)| list.title.clone()).collect()
+        };
+        assert_eq!(titles(" AAAA "), vec!["Work", "Home"]);
+        assert_eq!(titles("aaaa2"), vec!["Home"]);
+        assert!(titles("b").is_empty());
+        assert!(tasks_with_id_prefix(&lists, "rent").unwrap_err().is_invalid_data());
+        assert!(tasks_with_id_prefix(&lists, " ").unwrap_err().is_invalid_data());
+    }
+}
@@ -0,0 +1,246 @@
+use std::cmp::Ordering;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use crate::models::{Priority, Task, TaskStatus};
+
+/// Order of query results. `ListOrder` keeps lists in workspace order and tasks in
+/// each list's `task_order`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuerySort {
+    #[default]
+    ListOrder,
+    /// Earliest due first; tasks without a due date last.
+    Due,
+    /// Highest priority first; tasks without a priority last.
+    Priority,
+    /// Oldest first.
+    Created,
+    /// Most recently updated first.
+    Updated,
+    /// Case-insensitive alphabetical.
+    Title,
+}
+
+/// Filter over tasks in all lists. Every predicate that is set must match; an empty
+/// query matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct TaskQuery {
+    pub status: Option<TaskStatus>,
+    /// Tasks must carry all of these tags (case-insensitive).
+    pub tags: Vec<String>,
+    pub priority: Option<Priority>,
+    /// See [`Task::is_assigned_to`].
+    pub assignee: Option<String>,
+    /// Only tasks due strictly before this instant.
+    pub due_before: Option<DateTime<Utc>>,
+    /// Only tasks due strictly after this instant.
+    pub due_after: Option<DateTime<Utc>>,
+    /// Case-insensitive substring of the title or description.
+    pub text: Option<String>,
+    /// Restrict to a single list.
+    pub list_id: Option<Uuid>,
+    pub sort: QuerySort,
+}
+
+impl TaskQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `task` in list `list_id` passes every predicate.
+    pub fn matches(&self, list_id: Uuid, task: &Task) -> bool {
+        if self.list_id.is_some_and(|id| id != list_id) {
+            return false;
+        }
+        if self.status.is_some_and(|status| status != task.status) {
+            return false;
+        }
+        if !self.tags.iter().all(|tag| task.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))) {
+            return false;
+        }
+        if self.priority.is_some() && self.priority != task.priority {
+            return false;
+        }
+        if self.assignee.as_deref().is_some_and(|name| !task.is_assigned_to(name)) {
+            return false;
+        }
+        if let Some(before) = self.due_before {
+            if task.due_date.is_none_or(|due| due >= before) {
+                return false;
+            }
+        }
+        if let Some(after) = self.due_after {
+            if task.due_date.is_none_or(|due| due <= after) {
+                return false;
+            }
+        }
+        if let Some(text) = &self.text {
+            let needle = text.to_lowercase();
+            if !task.title.to_lowercase().contains(&needle)
+                && !task.description.to_lowercase().contains(&needle)
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Keep the matching `(list_id, task)` pairs, then sort them by `self.sort`.
+    /// Sorting is stable, so ties keep their list order.
+    pub fn apply(&self, tasks: Vec<(Uuid, Task)>) -> Vec<(Uuid, Task)> {
+        let mut matched: Vec<(Uuid, Task)> = tasks.into_iter()
+            .filter(|(list_id, task)| self.matches(*list_id, task))
+            .collect();
+        match self.sort {
+            QuerySort::ListOrder => {}
+            QuerySort::Due => matched.sort_by(|(_, a), (_, b)| none_last(a.due_date, b.due_date)),
+            QuerySort::Priority => matched.sort_by(|(_, a), (_, b)| {
+                none_last(a.priority.map(std::cmp::Reverse), b.priority.map(std::cmp::Reverse))
+            }),
+            QuerySort::Created => matched.sort_by_key(|(_, task)| task.created_at),
+            QuerySort::Updated => matched.sort_by_key(|(_, task)| std::cmp::Reverse(task.updated_at)),
+            QuerySort::Title => matched.sort_by_key(|(_, task)| task.title.to_lowercase()),
+        }
+        matched
+    }
+}
+
+/// Compare `a` to `b`, putting `None` after every `Some`.
+fn none_last<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, day, 12, 0, 0).unwrap()
+    }
+
+    fn titles(results: &[(Uuid, Task)]) -> Vec<&str> {
+        results.iter().map(|(_, t)| t.title.as_str()).collect()
+    }
+
+    /// Two lists: work (a, b, c) and home (d, e).
+    fn sample() -> (Uuid, Uuid, Vec<(Uuid, Task)>) {
+        let work = Uuid::new_v4();
+        let home = Uuid::new_v4();
+        let tasks = vec![
+            (work, Task::builder("Alpha report").due_time(at(20)).priority(Priority::Medium).tags(["work", "writing"]).assignee("Sam").build()),
+            (work, Task::builder("beta review").due_time(at(5)).priority(Priority::High).tag("work").build()),
+            (work, Task::builder("Gamma cleanup").tag("Work").status(TaskStatus::Completed).build()),
+            (home, Task::builder("Delta groceries").due_time(at(10)).priority(Priority::Low).tag("errands").description("milk and eggs").build()),
+            (home, Task::builder("Epsilon call").due_time(at(1)).status(TaskStatus::Completed).assignee("sam").build()),
+        ];
+        (work, home, tasks)
+    }
+
+    #[test]
+    fn test_empty_query_matches_all_in_list_order() {
+        let (_, _, tasks) = sample();
+        let results = TaskQuery::new().apply(tasks);
+        assert_eq!(titles(&results), vec!["Alpha report", "beta review", "Gamma cleanup", "Delta groceries", "Epsilon call"]);
+    }
+
+    #[test]
+    fn test_status_and_tag_combined() {
+        let (_, _, tasks) = sample();
+        let query = TaskQuery {
+            status: Some(TaskStatus::Backlog),
+            tags: vec!["WORK".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(titles(&query.apply(tasks)), vec!["Alpha report", "beta review"]);
+    }
+
+    #[test]
+    fn test_tags_must_all_match() {
+        let (_, _, tasks) = sample();
+        let query = TaskQuery {
+            tags: vec!["work".to_string(), "writing".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(titles(&query.apply(tasks)), vec!["Alpha report"]);
+    }
+
+    #[test]
+    fn test_due_range_is_exclusive_and_skips_undated() {
+        let (_, _, tasks) = sample();
+        let query = TaskQuery {
+            due_after: Some(at(1)),
+            due_before: Some(at(20)),
+            ..Default::default()
+        };
+        assert_eq!(titles(&query.apply(tasks.clone())), vec!["beta review", "Delta groceries"]);
+
+        let before = TaskQuery { due_before: Some(at(30)), ..Default::default() };
+        assert!(!titles(&before.apply(tasks)).contains(&"Gamma cleanup"));
+    }
+
+    #[test]
+    fn test_status_tag_due_before_sorted_by_due() {
+        let (_, _, tasks) = sample();
+        let query = TaskQuery {
+            status: Some(TaskStatus::Backlog),
+            tags: vec!["work".to_string()],
+            due_before: Some(at(25)),
+            sort: QuerySort::Due,
+            ..Default::default()
+        };
+        assert_eq!(titles(&query.apply(tasks)), vec!["beta review", "Alpha report"]);
+    }
+
+    #[test]
+    fn test_list_priority_assignee_and_text() {
+        let (work, home, tasks) = sample();
+        let in_home = TaskQuery { list_id: Some(home), ..Default::default() };
+        assert_eq!(titles(&in_home.apply(tasks.clone())), vec!["Delta groceries", "Epsilon call"]);
+
+        let high = TaskQuery { priority: Some(Priority::High), ..Default::default() };
+        assert_eq!(titles(&high.apply(tasks.clone())), vec!["beta review"]);
+
+        let sam_at_work = TaskQuery { assignee: Some("SAM".to_string()), list_id: Some(work), ..Default::default() };
+        assert_eq!(titles(&sam_at_work.apply(tasks.clone())), vec!["Alpha report"]);
+
+        let text = TaskQuery { text: Some("EGGS".to_string()), ..Default::default() };
+        assert_eq!(titles(&text.apply(tasks.clone())), vec!["Delta groceries"]);
+
+        let nothing = TaskQuery { text: Some("report".to_string()), list_id: Some(home), ..Default::default() };
+        assert!(nothing.apply(tasks).is_empty());
+    }
+
+    #[test]
+    fn test_sort_orders() {
+        let (_, _, tasks) = sample();
+        let sorted = |sort| titles(&TaskQuery { sort, ..Default::default() }.apply(tasks.clone()))
+            .into_iter().map(String::from).collect::<Vec<_>>();
+
+        assert_eq!(sorted(QuerySort::Due), vec!["Epsilon call", "beta review", "Delta groceries", "Alpha report", "Gamma cleanup"]);
+        assert_eq!(sorted(QuerySort::Priority), vec!["beta review", "Alpha report", "Delta groceries", "Gamma cleanup", "Epsilon call"]);
+        assert_eq!(sorted(QuerySort::Title), vec!["Alpha report", "beta review", "Delta groceries", "Epsilon call", "Gamma cleanup"]);
+    }
+
+    #[test]
+    fn test_sort_by_created_and_updated() {
+        let list = Uuid::new_v4();
+        let mut old = Task::new("Old".to_string());
+        old.created_at = at(1);
+        old.updated_at = at(9);
+        let mut new = Task::new("New".to_string());
+        new.created_at = at(5);
+        new.updated_at = at(6);
+        let tasks = vec![(list, new), (list, old)];
+
+        let created = TaskQuery { sort: QuerySort::Created, ..Default::default() };
+        assert_eq!(titles(&created.apply(tasks.clone())), vec!["Old", "New"]);
+        let updated = TaskQuery { sort: QuerySort::Updated, ..Default::default() };
+        assert_eq!(titles(&updated.apply(tasks)), vec!["Old", "New"]);
+    }
+}
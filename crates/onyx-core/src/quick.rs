@@ -0,0 +1,193 @@
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use crate::models::Priority;
+
+/// What [`parse_quick_entry`] pulled out of a quick-entry line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuickMeta {
+    /// Due day; the time of day is up to the caller (see `models::resolve_date_only_due`).
+    pub due: Option<NaiveDate>,
+    /// Tags from `#tag` tokens, without the `#`, in order of appearance.
+    pub tags: Vec<String>,
+    pub priority: Option<Priority>,
+}
+
+/// Split a quick-entry line like `Buy milk tomorrow #groceries !high` into the task title
+/// and its metadata, taking "today" as `today`.
+///
+/// Recognized tokens, matched case-insensitively as whole whitespace-separated words:
+/// - due: `today`, `tomorrow`, a weekday name (`friday`: the next one after today) or
+///   `YYYY-MM-DD`
+/// - tags: `#` followed by letters, digits, `-`, `_` or `/`, with at least one letter
+///   (so `#42` stays in the title)
+/// - priority: `!low`, `!medium` (or `!med`), `!high`
+///
+/// Anything else stays in the title. So do ambiguous tokens: when more than one due or
+/// priority token appears, none of that kind is taken, and if nothing but tokens is
+/// left the whole line is the title.
+pub fn parse_quick_entry(input: &str, today: NaiveDate) -> (String, QuickMeta) {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let dues: Vec<(usize, NaiveDate)> = words.iter().enumerate()
+        .filter_map(|(i, word)| parse_due(word, today).map(|due| (i, due)))
+        .collect();
+    let priorities: Vec<(usize, Priority)> = words.iter().enumerate()
+        .filter_map(|(i, word)| parse_priority(word).map(|priority| (i, priority)))
+        .collect();
+
+    let mut meta = QuickMeta::default();
+    let mut taken = vec![false; words.len()];
+    if let [(i, due)] = dues[..] {
+        meta.due = Some(due);
+        taken[i] = true;
+    }
+    if let [(i, priority)] = priorities[..] {
+        meta.priority = Some(priority);
+        taken[i] = true;
+    }
+    for (i, word) in words.iter().enumerate() {
+        if let Some(tag) = parse_tag(word) {
+            if !meta.tags.iter().any(|t| t == tag) {
+                meta.tags.push(tag.to_string());
+            }
+            taken[i] = true;
+        }
+    }
+
+    let title: Vec<&str> = words.iter().zip(&taken)
+        .filter(|(_, taken)| !**taken)
+        .map(|(word, _)| *word)
+        .collect();
+    if title.is_empty() {
+        return (words.join(" "), QuickMeta::default());
+    }
+    (title.join(" "), meta)
+}
+
+fn parse_due(word: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let lower = word.to_lowercase();
+    match lower.as_str() {
+        "today" => return Some(today),
+        "tomorrow" => return today.checked_add_days(Days::new(1)),
+        _ => {}
+    }
+    const WEEKDAYS: [(&str, Weekday); 7] = [
+        ("monday", Weekday::Mon), ("tuesday", Weekday::Tue), ("wednesday", Weekday::Wed),
+        ("thursday", Weekday::Thu), ("friday", Weekday::Fri), ("saturday", Weekday::Sat),
+        ("sunday", Weekday::Sun),
+    ];
+    if let Some((_, weekday)) = WEEKDAYS.iter().find(|(name, _)| *name == lower) {
+        let ahead = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+        let ahead = if ahead == 0 { 7 } else { ahead };
+        return today.checked_add_days(Days::new(ahead.into()));
+    }
+    NaiveDate::parse_from_str(word, "%Y-%m-%d").ok().filter(|_| word.len() == 10)
+}
+
+fn parse_priority(word: &str) -> Option<Priority> {
+    word.strip_prefix('!').filter(|rest| !rest.is_empty()).and_then(|rest| rest.parse().ok())
+}
+
+fn parse_tag(word: &str) -> Option<&str> {
+    let tag = word.strip_prefix('#')?;
+    let valid = tag.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/'))
+        && tag.chars().any(char::is_alphabetic);
+    valid.then_some(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A Wednesday
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 6, 3).unwrap()
+    }
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_full_entry() {
+        let (title, meta) = parse_quick_entry("Buy milk tomorrow #groceries !high", today());
+        assert_eq!(title, "Buy milk");
+        assert_eq!(meta, QuickMeta {
+            due: Some(date(6, 4)),
+            tags: vec!["groceries".to_string()],
+            priority: Some(Priority::High),
+        });
+    }
+
+    #[test]
+    fn test_plain_title_is_untouched() {
+        let (title, meta) = parse_quick_entry("Call the plumber", today());
+        assert_eq!(title, "Call the plumber");
+        assert_eq!(meta, QuickMeta::default());
+    }
+
+    #[test]
+    fn test_due_words() {
+        let due = |input: &str| parse_quick_entry(input, today()).1.due;
+        assert_eq!(due("Pay rent today"), Some(today()));
+        assert_eq!(due("Pay rent TOMORROW"), Some(date(6, 4)));
+        assert_eq!(due("Pay rent friday"), Some(date(6, 5)));
+        assert_eq!(due("Pay rent Monday"), Some(date(6, 8)));
+        // Same weekday as today means next week's
+        assert_eq!(due("Pay rent wednesday"), Some(date(6, 10)));
+        assert_eq!(due("Pay rent 2026-07-01"), Some(date(7, 1)));
+    }
+
+    #[test]
+    fn test_tokens_anywhere_and_in_any_order() {
+        let (title, meta) = parse_quick_entry("!low #home Fix #diy the shelf friday", today());
+        assert_eq!(title, "Fix the shelf");
+        assert_eq!(meta.tags, vec!["home", "diy"]);
+        assert_eq!(meta.priority, Some(Priority::Low));
+        assert_eq!(meta.due, Some(date(6, 5)));
+    }
+
+    #[test]
+    fn test_medium_aliases_and_repeated_tags() {
+        let (title, meta) = parse_quick_entry("Plan trip !med #travel #travel", today());
+        assert_eq!(title, "Plan trip");
+        assert_eq!(meta.priority, Some(Priority::Medium));
+        assert_eq!(meta.tags, vec!["travel"]);
+    }
+
+    #[test]
+    fn test_ambiguous_due_stays_in_title() {
+        let (title, meta) = parse_quick_entry("Move meeting from today to friday", today());
+        assert_eq!(title, "Move meeting from today to friday");
+        assert!(meta.due.is_none());
+    }
+
+    #[test]
+    fn test_ambiguous_priority_stays_in_title() {
+        let (title, meta) = parse_quick_entry("Triage !high !low #bugs", today());
+        assert_eq!(title, "Triage !high !low");
+        assert!(meta.priority.is_none());
+        assert_eq!(meta.tags, vec!["bugs"]);
+    }
+
+    #[test]
+    fn test_lookalikes_stay_in_title() {
+        let (title, meta) = parse_quick_entry(
+            "Fix #42 before tomorrow, ask Tuesday's team !urgent ! # 2026-13-01 todays",
+            today(),
+        );
+        assert_eq!(title, "Fix #42 before tomorrow, ask Tuesday's team !urgent ! # 2026-13-01 todays");
+        assert_eq!(meta, QuickMeta::default());
+    }
+
+    #[test]
+    fn test_only_tokens_keeps_everything_as_title() {
+        let (title, meta) = parse_quick_entry("tomorrow #groceries", today());
+        assert_eq!(title, "tomorrow #groceries");
+        assert_eq!(meta, QuickMeta::default());
+    }
+
+    #[test]
+    fn test_whitespace_is_collapsed() {
+        let (title, _) = parse_quick_entry("  Water   plants  #home ", today());
+        assert_eq!(title, "Water plants");
+    }
+}
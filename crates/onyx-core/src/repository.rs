@@ -1,11 +1,148 @@
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
+use crate::config::WorkspaceSettings;
 use crate::error::{Error, Result};
-use crate::models::{Task, TaskList};
-use crate::storage::{FileSystemStorage, Storage};
+use crate::models::{Task, TaskList, TaskStatus};
+use crate::query::{QuerySort, TaskQuery};
+use crate::storage::{FileSystemStorage, ListMetadata, RootMetadata, Storage, TaskFileRename};
+
+/// `task_order` fixes for a single list found by [`TaskRepository::verify_and_repair`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ListRepair {
+    pub list_id: Uuid,
+    pub title: String,
+    /// Entries in `task_order` with no matching task file.
+    pub removed: Vec<Uuid>,
+    /// Task files that were missing from `task_order`.
+    pub added: Vec<Uuid>,
+}
+
+/// Result of a workspace integrity check.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntegrityReport {
+    pub lists: Vec<ListRepair>,
+    /// Entries in the root `list_order` with no matching list.
+    pub removed_lists: Vec<Uuid>,
+    /// Lists that were missing from the root `list_order`.
+    pub added_lists: Vec<Uuid>,
+    /// Directories without `.listdata.json` that won't be adopted; reported only, never
+    /// modified.
+    pub unregistered_dirs: Vec<String>,
+    /// Hand-made folders holding only task files. A repair adopts them as lists; a check
+    /// only reports them.
+    pub adoptable_dirs: Vec<String>,
+}
+
+impl IntegrityReport {
+    /// True when nothing needed fixing (unregistered directories are not counted).
+    pub fn is_clean(&self) -> bool {
+        self.lists.is_empty() && self.removed_lists.is_empty() && self.added_lists.is_empty()
+            && self.adoptable_dirs.is_empty()
+    }
+}
+
+/// A reminder that is due and not yet sent, returned by [`TaskRepository::due_reminders`].
+#[derive(Debug, Clone)]
+pub struct DueReminder {
+    pub list_id: Uuid,
+    pub list_title: String,
+    pub task: Task,
+    pub at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Outcome of [`TaskRepository::merge_lists`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeReport {
+    /// Tasks moved into the destination, in their original order.
+    pub moved: Vec<Uuid>,
+    /// Tasks dropped because the destination already had the same task (see
+    /// [`TaskRepository::merge_lists`]).
+    pub skipped: Vec<Uuid>,
+    /// Tasks moved under a new title (`Title (2)`, ...) to avoid a clash, with that title.
+    pub renamed: Vec<(Uuid, String)>,
+}
+
+/// Outcome of [`TaskRepository::split_list`].
+#[derive(Debug, Clone)]
+pub struct SplitReport {
+    /// The new list, as created (without tasks).
+    pub list: TaskList,
+    /// Tasks moved into it, in their order in the source list.
+    pub moved: Vec<Uuid>,
+}
+
+/// What [`TaskRepository::merge_lists`] would do, from [`TaskRepository::preview_merge`].
+/// Nothing has been changed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergePreview {
+    /// Tasks that would move into the destination, in their original order (including
+    /// the renamed ones).
+    pub moved: Vec<Uuid>,
+    /// Tasks that would be dropped because the destination already has the same task.
+    pub skipped: Vec<Uuid>,
+    /// Tasks that would move under a numbered title to avoid a clash, with that title.
+    pub renamed: Vec<(Uuid, String)>,
+    /// Whether the source list would be deleted. A merge always deletes it once every task
+    /// is across; this is spelled out for callers showing the preview.
+    pub deletes_source: bool,
+}
+
+/// Options for [`TaskRepository::get_lists_with`]. The default returns what
+/// [`TaskRepository::get_lists`] does. Lists have no archived state, so there is no
+/// `include_archived`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetListsOptions {
+    /// Read each list's tasks. Without them `tasks` is empty and no task file is opened,
+    /// which is all a list picker or sidebar needs.
+    pub include_tasks: bool,
+    /// Only lists whose title contains this text (case-insensitive).
+    pub filter_name: Option<String>,
+    /// Skip this many lists (after filtering).
+    pub offset: usize,
+    /// Return at most this many lists.
+    pub limit: Option<usize>,
+}
+
+impl Default for GetListsOptions {
+    fn default() -> Self {
+        Self { include_tasks: true, filter_name: None, offset: 0, limit: None }
+    }
+}
+
+/// Options for [`TaskRepository::all_tasks`]. The default returns every task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllTasksOptions {
+    /// Include completed tasks. Tasks have no archived state, so nothing else is filtered.
+    pub include_completed: bool,
+}
+
+impl Default for AllTasksOptions {
+    fn default() -> Self {
+        Self { include_completed: true }
+    }
+}
+
+/// What a folder holds, as reported by [`TaskRepository::inspect_folder`] before
+/// [`TaskRepository::init`] runs on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FolderContents {
+    /// Missing or empty.
+    Empty,
+    /// Already a workspace (it has `.metadata.json`). `init` adopts it as is and leaves
+    /// its lists and tasks alone; unreadable metadata makes `init` fail rather than
+    /// overwrite it.
+    Workspace,
+    /// Files that are not part of a workspace, by name (sorted). `init` adds workspace
+    /// metadata next to them without touching them.
+    Unrelated(Vec<String>),
+}
 
 pub struct TaskRepository {
     storage: Box<dyn Storage + Send + Sync>,
+    allow_duplicate_list_names: bool,
+    /// Global defaults for the settings `.workspace.json` leaves unset.
+    settings_defaults: WorkspaceSettings,
 }
 
 impl TaskRepository {
@@ -13,18 +150,69 @@ impl TaskRepository {
         let storage = FileSystemStorage::new(tasks_folder)?;
         Ok(Self {
             storage: Box::new(storage),
+            allow_duplicate_list_names: false,
+            settings_defaults: WorkspaceSettings::default(),
         })
     }
 
+    /// Look at `tasks_folder` without changing anything, so a frontend can tell a fresh
+    /// folder from one it would adopt or share with other files.
+    pub fn inspect_folder(tasks_folder: &Path) -> Result<FolderContents> {
+        if !tasks_folder.exists() {
+            return Ok(FolderContents::Empty);
+        }
+        if tasks_folder.join(".metadata.json").exists() {
+            return Ok(FolderContents::Workspace);
+        }
+        let mut names = std::fs::read_dir(tasks_folder)?
+            .map(|entry| entry.map(|e| e.file_name().to_string_lossy().into_owned()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        if names.is_empty() {
+            return Ok(FolderContents::Empty);
+        }
+        names.sort();
+        Ok(FolderContents::Unrelated(names))
+    }
+
+    /// Check that `tasks_folder` is safe to empty or delete wholesale, as moving a
+    /// workspace does: it must hold a workspace (`.metadata.json`) and must not be the
+    /// filesystem root or the user's home directory. Guards against a hand-edited config.
+    pub fn check_removable_folder(tasks_folder: &Path) -> Result<()> {
+        let home = directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+        Self::check_removable_folder_with(tasks_folder, home.as_deref())
+    }
+
+    fn check_removable_folder_with(tasks_folder: &Path, home: Option<&Path>) -> Result<()> {
+        let canonical = std::fs::canonicalize(tasks_folder)?;
+        let is_home = home
+            .and_then(|home| std::fs::canonicalize(home).ok())
+            .is_some_and(|home| home == canonical);
+        if canonical.parent().is_none() || is_home {
+            return Err(Error::InvalidPath(format!(
+                "{} is a root or home directory, not a workspace folder", tasks_folder.display()
+            )));
+        }
+        if Self::inspect_folder(&canonical)? != FolderContents::Workspace {
+            return Err(Error::InvalidPath(format!(
+                "{} is not a workspace folder (no .metadata.json)", tasks_folder.display()
+            )));
+        }
+        Ok(())
+    }
+
     pub fn init(tasks_folder: PathBuf) -> Result<Self> {
         let storage = FileSystemStorage::init(tasks_folder)?;
         Ok(Self {
             storage: Box::new(storage),
+            allow_duplicate_list_names: false,
+            settings_defaults: WorkspaceSettings::default(),
         })
     }
 
     // Task operations
     pub fn create_task(&mut self, list_id: Uuid, task: Task) -> Result<Task> {
+        ensure_title(&task)?;
+        self.ensure_allowed_tags(&task, None)?;
         self.storage.write_task(list_id, &task)?;
         Ok(task)
     }
@@ -33,30 +221,160 @@ impl TaskRepository {
         self.storage.read_task(list_id, task_id)
     }
 
+    /// The task with `task_id`, wherever it is, and its list (with `tasks` left empty). Only
+    /// the `id` line of the other task files is read, so this is much cheaper than
+    /// searching [`Self::get_lists`].
+    pub fn find_task(&self, task_id: Uuid) -> Result<(TaskList, Task)> {
+        let list_id = self.storage.find_task_list(task_id)?;
+        self.find_task_in(list_id, task_id)
+    }
+
+    /// [`Self::find_task`] when the list is known or can be guessed: only that list is
+    /// searched, and `Error::TaskNotFound` means the task is not in it.
+    pub fn find_task_in(&self, list_id: Uuid, task_id: Uuid) -> Result<(TaskList, Task)> {
+        let list = self.storage.get_list_headers()?
+            .into_iter()
+            .find(|list| list.id == list_id)
+            .ok_or_else(|| Error::ListNotFound(list_id.to_string()))?;
+        let task = self.storage.read_task(list_id, task_id)?;
+        Ok((list, task))
+    }
+
+    /// Raw markdown (frontmatter included) as stored on disk.
+    pub fn get_task_source(&self, list_id: Uuid, task_id: Uuid) -> Result<String> {
+        self.storage.read_task_source(list_id, task_id)
+    }
+
     pub fn update_task(&mut self, list_id: Uuid, task: Task) -> Result<()> {
+        ensure_title(&task)?;
         // Verify task exists first
-        let _ = self.storage.read_task(list_id, task.id)?;
+        let previous = self.storage.read_task(list_id, task.id)?;
+        self.ensure_allowed_tags(&task, Some(&previous))?;
         self.storage.write_task(list_id, &task)?;
         Ok(())
     }
 
+    /// With `strict_tags` on, refuse the tags of `task` that aren't in the workspace's
+    /// allowed tags. Tags it already had (in `previous`) are let through, so a task stays
+    /// editable after its tag was dropped from the vocabulary.
+    fn ensure_allowed_tags(&self, task: &Task, previous: Option<&Task>) -> Result<()> {
+        let added: Vec<String> = task.tags.iter()
+            .filter(|tag| previous.is_none_or(|previous| !previous.tags.contains(tag)))
+            .cloned()
+            .collect();
+        if added.is_empty() {
+            return Ok(());
+        }
+        self.effective_workspace_settings()?.check_tags(&added)?;
+        Ok(())
+    }
+
     pub fn delete_task(&mut self, list_id: Uuid, task_id: Uuid) -> Result<()> {
         self.storage.delete_task(list_id, task_id)
     }
 
+    /// Mark every open task of `list_id` completed, returning their IDs in list order.
+    /// Tasks that are already completed are left exactly as they are.
+    pub fn complete_all(&mut self, list_id: Uuid) -> Result<Vec<Uuid>> {
+        let mut completed = Vec::new();
+        for mut task in self.storage.list_tasks(list_id)? {
+            if task.status == TaskStatus::Completed {
+                continue;
+            }
+            task.complete();
+            self.storage.write_task(list_id, &task)?;
+            completed.push(task.id);
+        }
+        Ok(completed)
+    }
+
+    /// Set every completed task of `list_id` back to open, returning their IDs in list
+    /// order: the reverse of [`Self::complete_all`], for checklists that are reused.
+    pub fn reopen_all(&mut self, list_id: Uuid) -> Result<Vec<Uuid>> {
+        let mut reopened = Vec::new();
+        for mut task in self.storage.list_tasks(list_id)? {
+            if task.status != TaskStatus::Completed {
+                continue;
+            }
+            task.uncomplete();
+            self.storage.write_task(list_id, &task)?;
+            reopened.push(task.id);
+        }
+        Ok(reopened)
+    }
+
     pub fn list_tasks(&self, list_id: Uuid) -> Result<Vec<Task>> {
         self.storage.list_tasks(list_id)
     }
 
     // List operations
+    /// Create a top-level list. Fails if another list already has this name, ignoring case
+    /// (see [`Self::allow_duplicate_list_names`]).
     pub fn create_list(&mut self, name: String) -> Result<TaskList> {
+        self.ensure_list_name_free(&name, None)?;
         self.storage.create_list(name)
     }
 
+    /// Like [`Self::create_list`], for callers that only need the new list's ID.
+    pub fn create_list_with_id(&mut self, name: String) -> Result<Uuid> {
+        Ok(self.create_list(name)?.id)
+    }
+
+    /// Create a list nested under `parent_id`. Lists nest at most
+    /// [`MAX_LIST_DEPTH`](crate::storage::MAX_LIST_DEPTH) levels deep. Names must be unique
+    /// like for [`Self::create_list`].
+    pub fn create_sublist(&mut self, parent_id: Uuid, name: String) -> Result<TaskList> {
+        self.ensure_list_name_free(&name, None)?;
+        self.storage.create_sublist(parent_id, name)
+    }
+
+    /// Let `create_list`, `create_sublist` and `rename_list` reuse an existing list name.
+    /// Lookups by name then return the first match.
+    pub fn allow_duplicate_list_names(&mut self, allow: bool) {
+        self.allow_duplicate_list_names = allow;
+    }
+
+    /// Error unless no list other than `except` is called `name` (case-insensitive).
+    fn ensure_list_name_free(&self, name: &str, except: Option<Uuid>) -> Result<()> {
+        if self.allow_duplicate_list_names {
+            return Ok(());
+        }
+        let lowered = name.to_lowercase();
+        match self.get_lists()?.into_iter().find(|l| Some(l.id) != except && l.title.to_lowercase() == lowered) {
+            Some(existing) => Err(Error::ListAlreadyExists(existing.title)),
+            None => Ok(()),
+        }
+    }
+
+    /// All lists, each sublist placed right after its parent (see `TaskList::depth`).
     pub fn get_lists(&self) -> Result<Vec<TaskList>> {
         self.storage.get_lists()
     }
 
+    /// [`Self::get_lists`] without reading any tasks: every list's `tasks` is empty.
+    pub fn get_list_headers(&self) -> Result<Vec<TaskList>> {
+        self.storage.get_list_headers()
+    }
+
+    /// [`Self::get_lists`], filtered and paged. Tasks are only read for the lists that end
+    /// up in the result, and not at all without `include_tasks`.
+    pub fn get_lists_with(&self, options: &GetListsOptions) -> Result<Vec<TaskList>> {
+        let filter = options.filter_name.as_deref().map(str::to_lowercase);
+        let mut lists: Vec<TaskList> = self.storage.get_list_headers()?
+            .into_iter()
+            .filter(|list| filter.as_deref().is_none_or(|f| list.title.to_lowercase().contains(f)))
+            .skip(options.offset)
+            .take(options.limit.unwrap_or(usize::MAX))
+            .collect();
+        if options.include_tasks {
+            for list in &mut lists {
+                list.tasks = self.storage.list_tasks(list.id)?;
+                list.refresh_next_due();
+            }
+        }
+        Ok(lists)
+    }
+
     pub fn get_list(&self, list_id: Uuid) -> Result<TaskList> {
         let lists = self.get_lists()?;
         lists.into_iter()
@@ -64,23 +382,235 @@ impl TaskRepository {
             .ok_or_else(|| Error::ListNotFound(list_id.to_string()))
     }
 
+    /// Look up a list by its exact title. Returns `Error::ListNotFound` if absent.
+    pub fn find_list_by_name(&self, name: &str) -> Result<TaskList> {
+        self.get_lists()?
+            .into_iter()
+            .find(|list| list.title == name)
+            .ok_or_else(|| Error::ListNotFound(name.to_string()))
+    }
+
+    /// Resolve a list from user input: an exact title, or `@N` for the N-th list (0-based)
+    /// in [`Self::get_lists`] order. A list whose title is literally `@N` wins over the index.
+    pub fn resolve_list(&self, spec: &str) -> Result<TaskList> {
+        let mut lists = self.get_lists()?;
+        if let Some(pos) = lists.iter().position(|list| list.title == spec) {
+            return Ok(lists.swap_remove(pos));
+        }
+        let Some(index) = spec.strip_prefix('@').and_then(|i| i.parse::<usize>().ok()) else {
+            return Err(Error::ListNotFound(spec.to_string()));
+        };
+        if index >= lists.len() {
+            return Err(Error::ListNotFound(format!(
+                "{} (index out of range, workspace has {} list{})",
+                spec, lists.len(), if lists.len() == 1 { "" } else { "s" }
+            )));
+        }
+        Ok(lists.swap_remove(index))
+    }
+
+    /// Task files not named by the workspace's `filename_scheme` (after the setting changed),
+    /// renamed to match unless `dry_run`. A file whose new name is already taken is left
+    /// alone and reported as `blocked`.
+    pub fn rename_task_files(&mut self, dry_run: bool) -> Result<Vec<TaskFileRename>> {
+        self.storage.rename_task_files(dry_run)
+    }
+
+    /// Register a hand-made folder without `.listdata.json` as a list. Folders holding task
+    /// files are adopted automatically on read; this also covers empty ones.
+    pub fn adopt_list(&mut self, name: &str) -> Result<TaskList> {
+        self.storage.adopt_list(name)
+    }
+
+    /// The list the user last viewed or added to, if recorded.
+    pub fn get_last_opened_list(&self) -> Result<Option<Uuid>> {
+        Ok(self.storage.read_root_metadata()?.last_opened_list)
+    }
+
+    /// Remember `list_id` as the last-opened list. Only writes when the value changes, so
+    /// viewing the same list repeatedly doesn't touch `.metadata.json`.
+    pub fn set_last_opened_list(&mut self, list_id: Uuid) -> Result<()> {
+        let mut root = self.storage.read_root_metadata()?;
+        if root.last_opened_list == Some(list_id) {
+            return Ok(());
+        }
+        self.storage.read_list_metadata(list_id)?;
+        root.last_opened_list = Some(list_id);
+        self.storage.write_root_metadata(&root)
+    }
+
+    /// The workspace's inbox list, if one is designated.
+    pub fn get_inbox_list(&self) -> Result<Option<Uuid>> {
+        Ok(self.storage.read_root_metadata()?.inbox_list)
+    }
+
+    /// Designate `list_id` as the inbox, or clear the inbox with `None`.
+    pub fn set_inbox_list(&mut self, list_id: Option<Uuid>) -> Result<()> {
+        let mut root = self.storage.read_root_metadata()?;
+        if root.inbox_list == list_id {
+            return Ok(());
+        }
+        if let Some(list_id) = list_id {
+            self.storage.read_list_metadata(list_id)?;
+        }
+        root.inbox_list = list_id;
+        self.storage.write_root_metadata(&root)
+    }
+
     pub fn delete_list(&mut self, list_id: Uuid) -> Result<()> {
         self.storage.delete_list(list_id)
     }
 
+    /// Rename a list. Fails if another list already has the new name (case-insensitive).
     pub fn rename_list(&mut self, list_id: Uuid, new_name: String) -> Result<()> {
+        self.ensure_list_name_free(&new_name, Some(list_id))?;
         self.storage.rename_list(list_id, new_name)
     }
 
+    /// Move a task to another list, at the end of its task order. The stored file is moved
+    /// as it is (see [`Storage::move_task`]), so the task keeps its timestamps and any
+    /// frontmatter fields onyx doesn't know. On failure it stays in the source list.
     pub fn move_task(&mut self, from_list_id: Uuid, to_list_id: Uuid, task_id: Uuid) -> Result<()> {
-        let task = self.storage.read_task(from_list_id, task_id)?;
-        self.storage.write_task(to_list_id, &task)?;
-        // If delete from source fails, roll back by removing the copy from destination
-        if let Err(e) = self.storage.delete_task(from_list_id, task_id) {
-            let _ = self.storage.delete_task(to_list_id, task_id);
-            return Err(e);
+        self.storage.move_task(from_list_id, to_list_id, task_id)
+    }
+
+    /// Move every task of `source_id` into `dest_id`, then delete the source list. Tasks are
+    /// appended after the destination's own tasks in the source's `task_order`. A task whose
+    /// title already exists in the destination is moved under a numbered title, since titles
+    /// double as filenames. With `skip_duplicates` it is dropped instead, but only when it is
+    /// a copy of the destination's task: same description, status, dates, priority, tags,
+    /// assignee, label, fields, dependencies and reminders. Anything else would be lost with
+    /// the source list.
+    ///
+    /// Tasks keeping their title are moved file and all, like [`Self::move_task`]; renamed
+    /// ones are written anew. The source list is deleted only once every task is across.
+    /// If one fails, the moved tasks go back, the renamed copies are removed, and the source
+    /// list is left as it was.
+    pub fn merge_lists(&mut self, source_id: Uuid, dest_id: Uuid, skip_duplicates: bool) -> Result<MergeReport> {
+        let (source, plan) = self.plan_merge(source_id, dest_id, skip_duplicates)?;
+
+        let source_metadata = self.storage.read_list_metadata(source_id)?;
+        let mut report = MergeReport::default();
+        for task in &source.tasks {
+            if plan.skipped.contains(&task.id) {
+                report.skipped.push(task.id);
+                continue;
+            }
+            let result = match plan.renamed.iter().find(|(id, _)| *id == task.id) {
+                Some((_, title)) => {
+                    let renamed = Task { title: title.clone(), ..task.clone() };
+                    let result = self.storage.write_task(dest_id, &renamed);
+                    if result.is_ok() {
+                        report.renamed.push((task.id, renamed.title));
+                    }
+                    result
+                }
+                None => self.storage.move_task(source_id, dest_id, task.id),
+            };
+            if let Err(e) = result {
+                self.undo_merge(source_id, dest_id, &report, task.id, &source_metadata);
+                return Err(e);
+            }
+            report.moved.push(task.id);
         }
-        Ok(())
+
+        self.storage.delete_list(source_id)?;
+        Ok(report)
+    }
+
+    /// What [`Self::merge_lists`] with the same arguments would do, without changing
+    /// anything. Fails in the same cases the merge would fail up front.
+    pub fn preview_merge(&self, source_id: Uuid, dest_id: Uuid, skip_duplicates: bool) -> Result<MergePreview> {
+        Ok(self.plan_merge(source_id, dest_id, skip_duplicates)?.1)
+    }
+
+    /// The source list of a merge, with its tasks, and what would happen to each of them.
+    fn plan_merge(&self, source_id: Uuid, dest_id: Uuid, skip_duplicates: bool) -> Result<(TaskList, MergePreview)> {
+        if source_id == dest_id {
+            return Err(Error::InvalidData("Cannot merge a list into itself".to_string()));
+        }
+        let lists = self.get_lists()?;
+        let source = lists.iter().find(|l| l.id == source_id)
+            .ok_or_else(|| Error::ListNotFound(source_id.to_string()))?;
+        let dest = lists.iter().find(|l| l.id == dest_id)
+            .ok_or_else(|| Error::ListNotFound(dest_id.to_string()))?;
+        if lists.iter().any(|l| l.parent_id == Some(source_id)) {
+            return Err(Error::InvalidData(format!(
+                "List \"{}\" has sublists; move or delete them before merging it", source.title
+            )));
+        }
+
+        let mut titles: Vec<String> = dest.tasks.iter().map(|t| t.title.clone()).collect();
+        let mut plan = MergePreview { deletes_source: true, ..Default::default() };
+        for task in &source.tasks {
+            if titles.contains(&task.title) {
+                if skip_duplicates && dest.tasks.iter().any(|t| same_content(t, task)) {
+                    plan.skipped.push(task.id);
+                    continue;
+                }
+                let title = (2..)
+                    .map(|n| format!("{} ({})", task.title, n))
+                    .find(|candidate| !titles.contains(candidate))
+                    .expect("unbounded range yields a free title");
+                plan.renamed.push((task.id, title.clone()));
+                titles.push(title);
+            } else {
+                titles.push(task.title.clone());
+            }
+            plan.moved.push(task.id);
+        }
+        Ok((source.clone(), plan))
+    }
+
+    /// Best-effort rollback of a failed [`Self::merge_lists`]: bring the moved tasks back,
+    /// remove renamed copies (and whatever of `failed` made it across), and restore the
+    /// source's task order.
+    fn undo_merge(&mut self, source_id: Uuid, dest_id: Uuid, report: &MergeReport, failed: Uuid, source_metadata: &ListMetadata) {
+        let renamed: Vec<Uuid> = report.renamed.iter().map(|(id, _)| *id).collect();
+        for id in &report.moved {
+            if renamed.contains(id) {
+                let _ = self.storage.delete_task(dest_id, *id);
+            } else {
+                let _ = self.storage.move_task(dest_id, source_id, *id);
+            }
+        }
+        let _ = match self.storage.read_task(source_id, failed) {
+            Ok(_) => self.storage.delete_task(dest_id, failed),
+            Err(_) => self.storage.move_task(dest_id, source_id, failed),
+        };
+        let _ = self.storage.write_list_metadata(source_metadata);
+    }
+
+    /// Move the tasks of `source_id` that match `query` into a new top-level list `name`,
+    /// keeping their order. `query.list_id` and `query.sort` are ignored. Tasks are moved
+    /// file and all, like [`Self::move_task`]. Nothing is created when no task matches;
+    /// if a move fails, the moved tasks go back and the new list is deleted again.
+    pub fn split_list(&mut self, source_id: Uuid, name: String, query: &TaskQuery) -> Result<SplitReport> {
+        let source = self.get_list(source_id)?;
+        let query = TaskQuery { list_id: Some(source_id), sort: QuerySort::ListOrder, ..query.clone() };
+        let matching: Vec<Uuid> = query.apply(source.tasks.into_iter().map(|task| (source_id, task)).collect())
+            .into_iter()
+            .map(|(_, task)| task.id)
+            .collect();
+        if matching.is_empty() {
+            return Err(Error::InvalidData(format!("No tasks in \"{}\" match", source.title)));
+        }
+
+        let list = self.create_list(name)?;
+        let source_metadata = self.storage.read_list_metadata(source_id)?;
+        let mut moved = Vec::new();
+        for task_id in matching {
+            if let Err(e) = self.storage.move_task(source_id, list.id, task_id) {
+                for id in &moved {
+                    let _ = self.storage.move_task(list.id, source_id, *id);
+                }
+                let _ = self.storage.write_list_metadata(&source_metadata);
+                let _ = self.storage.delete_list(list.id);
+                return Err(e);
+            }
+            moved.push(task_id);
+        }
+        Ok(SplitReport { list, moved })
     }
 
     // Task ordering
@@ -109,6 +639,217 @@ impl TaskRepository {
         Ok(metadata.task_order)
     }
 
+    /// Tasks across all lists updated at or after `since`, most recently updated first.
+    /// Each task is paired with the title of its list.
+    pub fn recent_tasks(&self, since: chrono::DateTime<chrono::Utc>) -> Result<Vec<(String, Task)>> {
+        let mut recent: Vec<(String, Task)> = self.storage.get_lists()?
+            .into_iter()
+            .flat_map(|list| {
+                let title = list.title;
+                list.tasks.into_iter().map(move |task| (title.clone(), task))
+            })
+            .filter(|(_, task)| task.updated_at >= since)
+            .collect();
+        recent.sort_by_key(|(_, task)| std::cmp::Reverse(task.updated_at));
+        Ok(recent)
+    }
+
+    /// Every task in the workspace as one flat list, in list and task order, each paired
+    /// with the title of its list.
+    pub fn all_tasks(&self, options: &AllTasksOptions) -> Result<Vec<(String, Task)>> {
+        Ok(self.storage.get_lists()?
+            .into_iter()
+            .flat_map(|list| {
+                let title = list.title;
+                list.tasks.into_iter().map(move |task| (title.clone(), task))
+            })
+            .filter(|(_, task)| options.include_completed || task.status != TaskStatus::Completed)
+            .collect())
+    }
+
+    /// Tasks across all lists assigned to `name` (see [`Task::is_assigned_to`]), in list and
+    /// task order, each paired with its list title. With `include_unassigned`, tasks without
+    /// an assignee are included too.
+    pub fn assigned_tasks(&self, name: &str, include_unassigned: bool) -> Result<Vec<(String, Task)>> {
+        Ok(self.storage.get_lists()?
+            .into_iter()
+            .flat_map(|list| {
+                let title = list.title;
+                list.tasks.into_iter().map(move |task| (title.clone(), task))
+            })
+            .filter(|(_, task)| {
+                task.is_assigned_to(name) || (include_unassigned && task.assignee.is_none())
+            })
+            .collect())
+    }
+
+    /// Tasks across all lists matching `query`, each paired with its list ID, sorted by
+    /// `query.sort`. With `query.text` set, the search index (see [`Self::reindex`]) is
+    /// used to read only the task files that can match, if there is one.
+    pub fn query(&self, query: &TaskQuery) -> Result<Vec<(Uuid, Task)>> {
+        if let Some(text) = &query.text {
+            if let Some(candidates) = self.storage.search_candidates(text)? {
+                return Ok(query.apply(candidates));
+            }
+        }
+        let tasks = self.storage.get_lists()?
+            .into_iter()
+            .flat_map(|list| {
+                let list_id = list.id;
+                list.tasks.into_iter().map(move |task| (list_id, task))
+            })
+            .collect();
+        Ok(query.apply(tasks))
+    }
+
+    /// Build the search index (`.search-index.json` in the workspace folder) from every
+    /// task file, returning how many tasks it holds. Once built, each search keeps it up
+    /// to date by re-reading only the task files modified since they were indexed, whether
+    /// by onyx, sync or an editor.
+    pub fn reindex(&mut self) -> Result<usize> {
+        self.storage.rebuild_search_index()
+    }
+
+    /// Every task in the workspace paired with its list ID, in list and task order. Tasks
+    /// are read from disk as the iterator advances, so memory use stays flat however large
+    /// the workspace is; prefer this over [`Self::get_lists`] for whole-workspace passes.
+    pub fn iter_tasks(&self) -> Result<impl Iterator<Item = Result<(Uuid, Task)>> + '_> {
+        let list_ids: Vec<Uuid> = self.storage.get_list_headers()?
+            .into_iter()
+            .map(|list| list.id)
+            .collect();
+        Ok(list_ids.into_iter().flat_map(move |list_id| {
+            let tasks: Box<dyn Iterator<Item = Result<Task>>> = match self.storage.iter_list_tasks(list_id) {
+                Ok(tasks) => tasks,
+                Err(e) => Box::new(std::iter::once(Err(e))),
+            };
+            tasks.map(move |task| task.map(|task| (list_id, task)))
+        }))
+    }
+
+    // Dependencies
+    /// IDs of the open tasks that are blocked: at least one task in their `depends_on` is
+    /// still open. A dependency that has been completed or no longer exists counts as
+    /// satisfied, and completed tasks are never blocked.
+    pub fn blocked_tasks(&self) -> Result<HashSet<Uuid>> {
+        let mut open = HashSet::new();
+        let mut waiting = Vec::new();
+        for entry in self.iter_tasks()? {
+            let (_, task) = entry?;
+            if task.status == TaskStatus::Completed {
+                continue;
+            }
+            open.insert(task.id);
+            if !task.depends_on.is_empty() {
+                waiting.push((task.id, task.depends_on));
+            }
+        }
+        Ok(waiting.into_iter()
+            .filter(|(_, depends_on)| depends_on.iter().any(|id| open.contains(id)))
+            .map(|(id, _)| id)
+            .collect())
+    }
+
+    /// The loop that making `task_id` wait on `new_dep` would close, as task IDs from
+    /// `task_id` round to itself again (`[a, b, c, a]`), or `None` when the dependency is
+    /// safe to add. Found by a depth-first search over the dependencies of every task in the
+    /// workspace.
+    pub fn has_cycle(&self, task_id: Uuid, new_dep: Uuid) -> Result<Option<Vec<Uuid>>> {
+        let mut graph = HashMap::new();
+        for entry in self.iter_tasks()? {
+            let (_, task) = entry?;
+            if !task.depends_on.is_empty() {
+                graph.insert(task.id, task.depends_on);
+            }
+        }
+        Ok(dependency_path(&graph, new_dep, task_id, &mut HashSet::new()).map(|path| {
+            std::iter::once(task_id).chain(path).collect()
+        }))
+    }
+
+    /// Make `task_id` wait for `on_id` to be completed, returning the updated task. Both
+    /// tasks must exist, and a dependency that would close a loop (so none of the tasks in
+    /// it could ever start) is refused with an error naming the loop; see
+    /// [`Self::has_cycle`]. Adding a dependency twice changes nothing.
+    pub fn add_dependency(&mut self, task_id: Uuid, on_id: Uuid) -> Result<Task> {
+        if task_id == on_id {
+            return Err(Error::InvalidData("A task can't depend on itself".to_string()));
+        }
+        let (list, mut task) = self.find_task(task_id)?;
+        let (_, on) = self.find_task(on_id)?;
+        if task.depends_on.contains(&on_id) {
+            return Ok(task);
+        }
+
+        if let Some(cycle) = self.has_cycle(task_id, on_id)? {
+            let titles = cycle.iter()
+                .map(|id| self.find_task(*id).map(|(_, t)| t.title))
+                .collect::<Result<Vec<_>>>()?;
+            return Err(Error::InvalidData(format!(
+                "Making \"{}\" wait on \"{}\" would create a dependency cycle: {}",
+                task.title, on.title, titles.join(" -> ")
+            )));
+        }
+
+        task.depends_on.push(on_id);
+        task.updated_at = chrono::Utc::now();
+        self.storage.write_task(list.id, &task)?;
+        Ok(task)
+    }
+
+    /// Stop `task_id` waiting for `on_id`. Returns the updated task, or `None` when `on_id`
+    /// wasn't one of its dependencies (nothing is written then). `on_id` may name a task
+    /// that has since been deleted.
+    pub fn remove_dependency(&mut self, task_id: Uuid, on_id: Uuid) -> Result<Option<Task>> {
+        let (list, mut task) = self.find_task(task_id)?;
+        let before = task.depends_on.len();
+        task.depends_on.retain(|id| *id != on_id);
+        if task.depends_on.len() == before {
+            return Ok(None);
+        }
+        task.updated_at = chrono::Utc::now();
+        self.storage.write_task(list.id, &task)?;
+        Ok(Some(task))
+    }
+
+    // Reminders
+    /// Unsent reminders due at `now` across all lists, oldest first. Delivery is up to the
+    /// caller, which should then call [`Self::mark_reminder_sent`] for each one.
+    pub fn due_reminders(&self, now: chrono::DateTime<chrono::Utc>) -> Result<Vec<DueReminder>> {
+        let mut due = Vec::new();
+        for list in self.storage.get_lists()? {
+            for task in &list.tasks {
+                for at in task.due_reminders(now) {
+                    due.push(DueReminder {
+                        list_id: list.id,
+                        list_title: list.title.clone(),
+                        task: task.clone(),
+                        at,
+                    });
+                }
+            }
+        }
+        due.sort_by_key(|r| r.at);
+        Ok(due)
+    }
+
+    /// Record a reminder as delivered. Leaves `updated_at` alone so it doesn't count as an edit.
+    pub fn mark_reminder_sent(&mut self, reminder: &DueReminder) -> Result<()> {
+        let mut task = self.storage.read_task(reminder.list_id, reminder.task.id)?;
+        task.mark_reminder_sent(reminder.at);
+        self.storage.write_task(reminder.list_id, &task)
+    }
+
+    /// Completion timestamps of the list's completed tasks, oldest first.
+    pub fn completion_history(&self, list_id: Uuid) -> Result<Vec<chrono::DateTime<chrono::Utc>>> {
+        let mut history: Vec<_> = self.storage.list_tasks(list_id)?
+            .into_iter()
+            .filter_map(|task| task.completed_at)
+            .collect();
+        history.sort();
+        Ok(history)
+    }
+
     // Grouping preference
     pub fn set_group_by_due_date(&mut self, list_id: Uuid, enabled: bool) -> Result<()> {
         let mut metadata = self.storage.read_list_metadata(list_id)?;
@@ -122,13 +863,299 @@ impl TaskRepository {
         let metadata = self.storage.read_list_metadata(list_id)?;
         Ok(metadata.group_by_due_date)
     }
+
+    /// Root metadata (`.metadata.json`), including when the workspace was
+    /// created and when its metadata last changed.
+    pub fn get_root_metadata(&self) -> Result<RootMetadata> {
+        self.storage.read_root_metadata()
+    }
+
+    // Workspace settings
+    /// The settings stored in `.workspace.json`, without the global defaults; read these to
+    /// change and save them. See [`Self::effective_workspace_settings`] for the ones in effect.
+    pub fn get_workspace_settings(&self) -> Result<WorkspaceSettings> {
+        self.storage.read_workspace_settings()
+    }
+
+    /// The settings in effect: `.workspace.json` merged over the defaults given to
+    /// [`Self::set_settings_defaults`].
+    pub fn effective_workspace_settings(&self) -> Result<WorkspaceSettings> {
+        Ok(self.storage.read_workspace_settings()?.merged_over(&self.settings_defaults))
+    }
+
+    /// Fall back to `defaults` (normally `AppConfig::defaults`) for every setting the
+    /// workspace leaves unset, including how task files are written.
+    pub fn set_settings_defaults(&mut self, defaults: WorkspaceSettings) {
+        self.storage.set_settings_defaults(defaults.clone());
+        self.settings_defaults = defaults;
+    }
+
+    pub fn set_workspace_settings(&mut self, settings: &WorkspaceSettings) -> Result<()> {
+        self.storage.write_workspace_settings(settings)
+    }
+
+    // Integrity
+    /// Check that `task_order` and `list_order` match the files on disk. Orphaned entries
+    /// are pruned and missing ones appended (oldest first, ties by ID: the order reads already
+    /// show them in), and hand-made folders holding task files are adopted. With
+    /// `check_only` all of this is only reported and nothing is written.
+    pub fn verify_and_repair(&mut self, check_only: bool) -> Result<IntegrityReport> {
+        // Reading lists adopts hand-made folders, which a check must not do
+        let adoptable_dirs = self.storage.adoptable_list_dirs()?;
+        let lists = if check_only {
+            self.storage.get_registered_lists()?
+        } else {
+            self.storage.get_lists()?
+        };
+        let mut report = IntegrityReport {
+            unregistered_dirs: self.storage.unregistered_list_dirs()?.into_iter()
+                .filter(|dir| !adoptable_dirs.contains(dir))
+                .collect(),
+            adoptable_dirs,
+            ..Default::default()
+        };
+
+        for list in &lists {
+            let mut metadata = self.storage.read_list_metadata(list.id)?;
+            let on_disk: HashSet<Uuid> = list.tasks.iter().map(|t| t.id).collect();
+
+            let removed: Vec<Uuid> = metadata.task_order.iter()
+                .filter(|id| !on_disk.contains(id))
+                .copied()
+                .collect();
+            let mut missing: Vec<&Task> = list.tasks.iter()
+                .filter(|t| !metadata.task_order.contains(&t.id))
+                .collect();
+            missing.sort_by_key(|t| (t.created_at, t.id));
+            let added: Vec<Uuid> = missing.iter().map(|t| t.id).collect();
+
+            if removed.is_empty() && added.is_empty() {
+                continue;
+            }
+            if !check_only {
+                metadata.task_order.retain(|id| on_disk.contains(id));
+                metadata.task_order.extend(&added);
+                metadata.updated_at = chrono::Utc::now();
+                self.storage.write_list_metadata(&metadata)?;
+            }
+            report.lists.push(ListRepair { list_id: list.id, title: list.title.clone(), removed, added });
+        }
+
+        let mut root = self.storage.read_root_metadata()?;
+        let list_ids: Vec<Uuid> = lists.iter().map(|l| l.id).collect();
+        report.removed_lists = root.list_order.iter()
+            .filter(|id| !list_ids.contains(id))
+            .copied()
+            .collect();
+        report.added_lists = list_ids.iter()
+            .filter(|id| !root.list_order.contains(id))
+            .copied()
+            .collect();
+
+        if !check_only && (!report.removed_lists.is_empty() || !report.added_lists.is_empty()) {
+            root.list_order.retain(|id| list_ids.contains(id));
+            root.list_order.extend(&report.added_lists);
+            if root.last_opened_list.is_some_and(|id| !list_ids.contains(&id)) {
+                root.last_opened_list = root.list_order.first().copied();
+            }
+            if root.inbox_list.is_some_and(|id| !list_ids.contains(&id)) {
+                root.inbox_list = None;
+            }
+            self.storage.write_root_metadata(&root)?;
+        }
+
+        Ok(report)
+    }
+}
+
+/// Depth-first search for a chain of dependencies leading from `from` to `to` in `graph`
+/// (task ID to the IDs it depends on). The path includes both ends; `seen` keeps shared
+/// dependencies from being searched twice.
+fn dependency_path(graph: &HashMap<Uuid, Vec<Uuid>>, from: Uuid, to: Uuid, seen: &mut HashSet<Uuid>) -> Option<Vec<Uuid>> {
+    if from == to {
+        return Some(vec![to]);
+    }
+    if !seen.insert(from) {
+        return None;
+    }
+    for next in graph.get(&from).into_iter().flatten() {
+        if let Some(mut path) = dependency_path(graph, *next, to, seen) {
+            path.insert(0, from);
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Whether `a` and `b` hold the same task as far as the user is concerned: everything but
+/// the ID, the bookkeeping timestamps and the parent, which point into their own lists.
+fn same_content(a: &Task, b: &Task) -> bool {
+    a.title == b.title
+        && a.description == b.description
+        && a.status == b.status
+        && a.due_date == b.due_date
+        && a.has_time == b.has_time
+        && a.depends_on == b.depends_on
+        && a.priority == b.priority
+        && a.tags == b.tags
+        && a.assignee == b.assignee
+        && a.label == b.label
+        && a.fields == b.fields
+        && a.reminders == b.reminders
+}
+
+/// Titles double as file names and are shown everywhere, so a blank one is refused.
+fn ensure_title(task: &Task) -> Result<()> {
+    if task.title.trim().is_empty() {
+        return Err(Error::InvalidData("Task title can't be empty".to_string()));
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::search::SearchIndex;
+    use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_get_lists_with_options() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        for name in ["Work", "Home", "Homework"] {
+            let list = repo.create_list(name.to_string()).unwrap();
+            repo.create_task(list.id, Task::new(format!("{} task", name))).unwrap();
+        }
+
+        let all = repo.get_lists_with(&GetListsOptions::default()).unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all.iter().all(|list| list.tasks.len() == 1));
+
+        let headers = repo.get_lists_with(&GetListsOptions { include_tasks: false, ..Default::default() }).unwrap();
+        assert_eq!(headers.iter().map(|l| l.id).collect::<Vec<_>>(), all.iter().map(|l| l.id).collect::<Vec<_>>());
+        assert!(headers.iter().all(|list| list.tasks.is_empty()));
+
+        let titles = |options: GetListsOptions| -> Vec<String> {
+            repo.get_lists_with(&options).unwrap().into_iter().map(|l| l.title).collect()
+        };
+        assert_eq!(titles(GetListsOptions { filter_name: Some("HOME".into()), ..Default::default() }), vec!["Home", "Homework"]);
+        assert_eq!(titles(GetListsOptions { offset: 1, limit: Some(1), ..Default::default() }), vec!["Home"]);
+        assert!(titles(GetListsOptions { offset: 5, ..Default::default() }).is_empty());
+    }
+
+    #[test]
+    fn test_blocked_until_every_dependency_is_done() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+        let home = repo.create_list("Home".to_string()).unwrap();
+        let design = repo.create_task(work.id, Task::new("Design".to_string())).unwrap();
+        let budget = repo.create_task(home.id, Task::new("Budget".to_string())).unwrap();
+        let build = repo.create_task(work.id, Task::new("Build".to_string())).unwrap();
+        let free = repo.create_task(work.id, Task::new("Free".to_string())).unwrap();
+
+        repo.add_dependency(build.id, design.id).unwrap();
+        // Dependencies can point into other lists
+        let build = repo.add_dependency(build.id, budget.id).unwrap();
+        assert_eq!(build.depends_on, vec![design.id, budget.id]);
+        assert_eq!(repo.blocked_tasks().unwrap(), HashSet::from([build.id]));
+
+        // One open dependency is enough to stay blocked
+        let mut design = design;
+        design.complete();
+        repo.update_task(work.id, design).unwrap();
+        assert_eq!(repo.blocked_tasks().unwrap(), HashSet::from([build.id]));
+
+        // A deleted dependency counts as satisfied
+        repo.delete_task(home.id, budget.id).unwrap();
+        assert!(repo.blocked_tasks().unwrap().is_empty());
+
+        // A completed task is never blocked, whatever it waits on
+        let mut free = repo.add_dependency(free.id, build.id).unwrap();
+        assert_eq!(repo.blocked_tasks().unwrap(), HashSet::from([free.id]));
+        free.complete();
+        repo.update_task(work.id, free).unwrap();
+        assert!(repo.blocked_tasks().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_add_and_remove_dependency() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let list = repo.create_list("Work".to_string()).unwrap();
+        let a = repo.create_task(list.id, Task::new("A".to_string())).unwrap();
+        let b = repo.create_task(list.id, Task::new("B".to_string())).unwrap();
+        let c = repo.create_task(list.id, Task::new("C".to_string())).unwrap();
+
+        repo.add_dependency(b.id, a.id).unwrap();
+        repo.add_dependency(c.id, b.id).unwrap();
+        assert!(matches!(repo.add_dependency(a.id, a.id), Err(Error::InvalidData(_))));
+        assert!(repo.add_dependency(a.id, Uuid::new_v4()).unwrap_err().is_not_found());
+        assert!(repo.get_task(list.id, a.id).unwrap().depends_on.is_empty());
+
+        // Adding twice is a no-op; removing frees the task
+        assert_eq!(repo.add_dependency(c.id, b.id).unwrap().depends_on, vec![b.id]);
+        assert!(repo.remove_dependency(c.id, b.id).unwrap().is_some());
+        assert!(repo.remove_dependency(c.id, b.id).unwrap().is_none());
+        assert_eq!(repo.blocked_tasks().unwrap(), HashSet::from([b.id]));
+    }
+
+    #[test]
+    fn test_has_cycle_finds_direct_and_transitive_loops() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+        let home = repo.create_list("Home".to_string()).unwrap();
+        let a = repo.create_task(work.id, Task::new("A".to_string())).unwrap();
+        let b = repo.create_task(work.id, Task::new("B".to_string())).unwrap();
+        let c = repo.create_task(home.id, Task::new("C".to_string())).unwrap();
+        let d = repo.create_task(home.id, Task::new("D".to_string())).unwrap();
+
+        // Direct: A -> B, then B -> A
+        repo.add_dependency(a.id, b.id).unwrap();
+        assert_eq!(repo.has_cycle(b.id, a.id).unwrap(), Some(vec![b.id, a.id, b.id]));
+
+        // Transitive, across lists: A -> B -> C, then C -> A
+        repo.add_dependency(b.id, c.id).unwrap();
+        assert_eq!(repo.has_cycle(c.id, a.id).unwrap(), Some(vec![c.id, a.id, b.id, c.id]));
+        let err = repo.add_dependency(c.id, a.id).unwrap_err().to_string();
+        assert!(err.contains("C -> A -> B -> C"), "{}", err);
+
+        // Edges that only share dependencies are fine
+        assert_eq!(repo.has_cycle(d.id, c.id).unwrap(), None);
+        assert_eq!(repo.has_cycle(a.id, c.id).unwrap(), None);
+        repo.add_dependency(d.id, b.id).unwrap();
+        assert_eq!(repo.has_cycle(c.id, d.id).unwrap(), Some(vec![c.id, d.id, b.id, c.id]));
+    }
+
+    #[test]
+    fn test_all_tasks_flattens_lists() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+        let home = repo.create_list("Home".to_string()).unwrap();
+        let q3 = repo.create_sublist(work.id, "Q3".to_string()).unwrap();
+        repo.create_task(home.id, Task::new("Dishes".to_string())).unwrap();
+        repo.create_task(work.id, Task::new("Report".to_string())).unwrap();
+        repo.create_task(q3.id, Task::new("Plan".to_string())).unwrap();
+        let mut done = Task::new("Invoice".to_string());
+        done.complete();
+        repo.create_task(work.id, done).unwrap();
+        repo.create_list("Empty".to_string()).unwrap();
+
+        let pairs = |options: AllTasksOptions| -> Vec<(String, String)> {
+            repo.all_tasks(&options).unwrap().into_iter().map(|(list, task)| (list, task.title)).collect()
+        };
+        let pair = |list: &str, task: &str| (list.to_string(), task.to_string());
+        assert_eq!(pairs(AllTasksOptions::default()), vec![
+            pair("Work", "Report"), pair("Work", "Invoice"), pair("Q3", "Plan"), pair("Home", "Dishes"),
+        ]);
+        assert_eq!(pairs(AllTasksOptions { include_completed: false }), vec![
+            pair("Work", "Report"), pair("Q3", "Plan"), pair("Home", "Dishes"),
+        ]);
+    }
+
     #[test]
     fn test_init_repository() {
         let temp_dir = TempDir::new().unwrap();
@@ -136,6 +1163,107 @@ mod tests {
         assert!(repo.is_ok());
     }
 
+    #[test]
+    fn test_inspect_folder() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tasks");
+        assert_eq!(TaskRepository::inspect_folder(&path).unwrap(), FolderContents::Empty);
+
+        std::fs::create_dir(&path).unwrap();
+        assert_eq!(TaskRepository::inspect_folder(&path).unwrap(), FolderContents::Empty);
+
+        std::fs::write(path.join("notes.txt"), "hi").unwrap();
+        std::fs::create_dir(path.join("Photos")).unwrap();
+        assert_eq!(
+            TaskRepository::inspect_folder(&path).unwrap(),
+            FolderContents::Unrelated(vec!["Photos".to_string(), "notes.txt".to_string()])
+        );
+
+        TaskRepository::init(path.clone()).unwrap();
+        assert_eq!(TaskRepository::inspect_folder(&path).unwrap(), FolderContents::Workspace);
+        assert_eq!(std::fs::read_to_string(path.join("notes.txt")).unwrap(), "hi");
+    }
+
+    #[test]
+    fn test_check_removable_folder() {
+        let temp_dir = TempDir::new().unwrap();
+        let plain = temp_dir.path().join("plain");
+        std::fs::create_dir(&plain).unwrap();
+        std::fs::write(plain.join("notes.txt"), "hi").unwrap();
+        let err = TaskRepository::check_removable_folder_with(&plain, None).unwrap_err();
+        assert!(matches!(err, Error::InvalidPath(_)));
+
+        let workspace = temp_dir.path().join("workspace");
+        TaskRepository::init(workspace.clone()).unwrap();
+        TaskRepository::check_removable_folder_with(&workspace, None).unwrap();
+
+        // A workspace at the home directory itself is still refused
+        assert!(TaskRepository::check_removable_folder_with(&workspace, Some(&workspace)).is_err());
+        let root = workspace.ancestors().last().unwrap();
+        assert!(matches!(TaskRepository::check_removable_folder_with(root, None), Err(Error::InvalidPath(_))));
+        assert!(TaskRepository::check_removable_folder_with(&temp_dir.path().join("missing"), None).is_err());
+    }
+
+    #[test]
+    fn test_init_adopts_existing_workspace() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let list = repo.create_list("Groceries".to_string()).unwrap();
+        repo.create_task(list.id, Task::new("Milk".to_string())).unwrap();
+        let created_at = repo.get_root_metadata().unwrap().created_at;
+
+        let adopted = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let lists = adopted.get_lists().unwrap();
+        assert_eq!(lists.len(), 1);
+        assert_eq!(lists[0].id, list.id);
+        assert_eq!(lists[0].tasks[0].title, "Milk");
+        assert_eq!(adopted.get_root_metadata().unwrap().created_at, created_at);
+    }
+
+    #[test]
+    fn test_init_refuses_unreadable_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let metadata = temp_dir.path().join(".metadata.json");
+        std::fs::write(&metadata, "not json {").unwrap();
+
+        assert_eq!(TaskRepository::inspect_folder(temp_dir.path()).unwrap(), FolderContents::Workspace);
+        assert!(TaskRepository::init(temp_dir.path().to_path_buf()).is_err());
+        assert_eq!(std::fs::read_to_string(&metadata).unwrap(), "not json {");
+    }
+
+    #[test]
+    fn test_get_lists_next_due() {
+        use chrono::TimeZone;
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let early = chrono::Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+        let late = chrono::Utc.with_ymd_and_hms(2026, 5, 1, 9, 0, 0).unwrap();
+
+        let mixed = repo.create_list("Mixed".to_string()).unwrap();
+        repo.create_task(mixed.id, Task::new("No due".to_string())).unwrap();
+        repo.create_task(mixed.id, Task::builder("Late").due_time(late).build()).unwrap();
+        repo.create_task(mixed.id, Task::builder("Early but done").due_time(early).status(TaskStatus::Completed).build()).unwrap();
+
+        let done = repo.create_list("Done".to_string()).unwrap();
+        repo.create_task(done.id, Task::builder("Finished").due_time(early).status(TaskStatus::Completed).build()).unwrap();
+
+        let empty = repo.create_list("Empty".to_string()).unwrap();
+        assert!(empty.next_due.is_none());
+
+        let lists = repo.get_lists().unwrap();
+        let next_due = |id: Uuid| lists.iter().find(|l| l.id == id).unwrap().next_due;
+        assert_eq!(next_due(mixed.id), Some(late));
+        assert_eq!(next_due(done.id), None);
+        assert_eq!(next_due(empty.id), None);
+
+        let headers = repo.get_lists_with(&GetListsOptions { include_tasks: false, ..Default::default() }).unwrap();
+        assert!(headers.iter().all(|l| l.next_due.is_none()));
+
+        let mut list = lists.into_iter().find(|l| l.id == mixed.id).unwrap();
+        list.add_task(Task::builder("Earlier").due_time(early).build());
+        assert_eq!(list.next_due, Some(early));
+    }
+
     #[test]
     fn test_create_and_list_tasks() {
         let temp_dir = TempDir::new().unwrap();
@@ -146,7 +1274,7 @@ mod tests {
 
         // Create a task
         let task = Task::new("Test Task".to_string());
-        let created_task = repo.create_task(list.id, task).unwrap();
+        repo.create_task(list.id, task).unwrap();
 
         // List tasks
         let tasks = repo.list_tasks(list.id).unwrap();
@@ -221,6 +1349,123 @@ mod tests {
         assert!(!repo.get_group_by_due_date(list.id).unwrap());
     }
 
+    #[test]
+    fn test_find_task_parses_only_the_match() {
+        use crate::storage::FRONTMATTER_PARSES;
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let mut ids = Vec::new();
+        for name in ["Work", "Home", "Errands"] {
+            let list = repo.create_list(name.to_string()).unwrap();
+            for i in 0..4 {
+                ids.push((list.id, repo.create_task(list.id, Task::new(format!("{} {}", name, i))).unwrap().id));
+            }
+        }
+        let parses = |f: &dyn Fn()| {
+            FRONTMATTER_PARSES.with(|count| count.set(0));
+            f();
+            FRONTMATTER_PARSES.with(|count| count.get())
+        };
+
+        assert_eq!(parses(&|| { repo.get_lists().unwrap(); }), 12);
+        let (home_id, task_id) = ids[6];
+        assert_eq!(parses(&|| {
+            let (list, task) = repo.find_task(task_id).unwrap();
+            assert_eq!((list.title.as_str(), task.title.as_str()), ("Home", "Home 2"));
+        }), 1);
+        assert_eq!(parses(&|| { repo.find_task_in(home_id, task_id).unwrap(); }), 1);
+
+        // Wrong list, or no such task: nothing is parsed at all
+        assert_eq!(parses(&|| {
+            assert!(repo.find_task_in(ids[0].0, task_id).unwrap_err().is_not_found());
+            assert!(repo.find_task(Uuid::new_v4()).unwrap_err().is_not_found());
+        }), 0);
+    }
+
+    #[test]
+    fn test_text_query_reads_only_indexed_candidates() {
+        use crate::storage::FRONTMATTER_PARSES;
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+        let home = repo.create_list("Home".to_string()).unwrap();
+        for i in 0..5 {
+            repo.create_task(work.id, Task::new(format!("Report {}", i))).unwrap();
+        }
+        repo.create_task(home.id, Task::new("Pay rent".to_string())).unwrap();
+        repo.create_task(work.id, Task::new("Call landlord".to_string()).with_description("About the rent".to_string())).unwrap();
+        // Titles found for "rent", and how many task files were parsed to find them
+        let search = |repo: &TaskRepository| -> (Vec<String>, usize) {
+            FRONTMATTER_PARSES.with(|count| count.set(0));
+            let query = TaskQuery { text: Some("rent".to_string()), ..Default::default() };
+            let titles = repo.query(&query).unwrap().into_iter().map(|(_, t)| t.title).collect();
+            (titles, FRONTMATTER_PARSES.with(|count| count.get()))
+        };
+        let titles = |titles: &[&str]| -> Vec<String> { titles.iter().map(|t| t.to_string()).collect() };
+
+        // No index yet: every task is read
+        assert_eq!(search(&repo), (titles(&["Call landlord", "Pay rent"]), 7));
+
+        assert_eq!(repo.reindex().unwrap(), 7);
+        assert!(temp_dir.path().join(SearchIndex::FILE_NAME).exists());
+        assert_eq!(search(&repo), (titles(&["Call landlord", "Pay rent"]), 2));
+
+        // A written task is indexed by the next search, which reads it only once
+        let added = repo.create_task(home.id, Task::new("Rent a van".to_string())).unwrap();
+        assert_eq!(search(&repo), (titles(&["Call landlord", "Pay rent", "Rent a van"]), 3));
+        assert_eq!(search(&repo).1, 3);
+        repo.delete_task(home.id, added.id).unwrap();
+        assert_eq!(search(&repo), (titles(&["Call landlord", "Pay rent"]), 2));
+
+        // So is a file changed behind the index's back
+        let path = temp_dir.path().join("Work").join("Report 3.md");
+        let content = fs::read_to_string(&path).unwrap();
+        fs::write(&path, content.trim_end().to_string() + "\n\nRent is due").unwrap();
+        fs::File::options().write(true).open(&path).unwrap()
+            .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(60)).unwrap();
+        assert_eq!(search(&repo), (titles(&["Report 3", "Call landlord", "Pay rent"]), 3));
+        assert_eq!(search(&repo).1, 3);
+        assert_eq!(SearchIndex::load(temp_dir.path()).unwrap().files.len(), 7);
+
+        // Even when the rewrite keeps the old modification time, as on a file system with
+        // coarse timestamps: the size gives it away
+        let path = temp_dir.path().join("Work").join("Report 1.md");
+        let modified = fs::metadata(&path).unwrap().modified().unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        fs::write(&path, content.trim_end().to_string() + "\n\nAsk about the rent").unwrap();
+        fs::File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+        assert_eq!(search(&repo), (titles(&["Report 1", "Report 3", "Call landlord", "Pay rent"]), 4));
+        assert_eq!(search(&repo).1, 4);
+
+        // Renaming a list moves its files in the index, deleting one drops them
+        repo.rename_list(work.id, "Office".to_string()).unwrap();
+        assert_eq!(search(&repo), (titles(&["Report 1", "Report 3", "Call landlord", "Pay rent"]), 4));
+        repo.delete_list(home.id).unwrap();
+        assert_eq!(search(&repo), (titles(&["Report 1", "Report 3", "Call landlord"]), 3));
+        let index = SearchIndex::load(temp_dir.path()).unwrap();
+        assert!(index.files.keys().all(|key| key.starts_with("Office/")), "{:?}", index.files);
+    }
+
+    #[test]
+    fn test_find_task_reads_ids_from_toml_and_quoted_frontmatter() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let list = repo.create_list("Notes".to_string()).unwrap();
+        let toml_id = Uuid::new_v4();
+        let quoted_id = Uuid::new_v4();
+        std::fs::write(temp_dir.path().join("Notes").join("Toml.md"), format!(
+            "+++\nid = \"{}\"\nstatus = \"backlog\"\ncreated = 2025-01-01T00:00:00Z\nupdated = 2025-01-01T00:00:00Z\n+++\n", toml_id
+        )).unwrap();
+        std::fs::write(temp_dir.path().join("Notes").join("Quoted.md"), format!(
+            "\u{feff}---\r\nstatus: backlog\r\nid: '{}'\r\ncreated: 2025-01-01T00:00:00Z\r\nupdated: 2025-01-01T00:00:00Z\r\n---\r\n", quoted_id
+        )).unwrap();
+
+        assert_eq!(repo.find_task(toml_id).unwrap().1.title, "Toml");
+        assert_eq!(repo.find_task(quoted_id).unwrap().0.id, list.id);
+    }
+
     // --- Error path tests ---
 
     #[test]
@@ -234,6 +1479,21 @@ mod tests {
         assert!(matches!(result.unwrap_err(), Error::TaskNotFound(_)));
     }
 
+    #[test]
+    fn test_blank_titles_are_refused() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let list = repo.create_list("Test".to_string()).unwrap();
+
+        assert!(repo.create_task(list.id, Task::new(" \t\n".to_string())).unwrap_err().is_invalid_data());
+        assert!(repo.list_tasks(list.id).unwrap().is_empty());
+
+        let mut task = repo.create_task(list.id, Task::new(" Pasted\n title ".to_string())).unwrap();
+        assert_eq!(repo.get_task(list.id, task.id).unwrap().title, "Pasted title");
+        task.title = "   ".to_string();
+        assert!(repo.update_task(list.id, task).unwrap_err().is_invalid_data());
+    }
+
     #[test]
     fn test_update_nonexistent_task() {
         let temp_dir = TempDir::new().unwrap();
@@ -267,6 +1527,41 @@ mod tests {
         assert!(matches!(result.unwrap_err(), Error::ListNotFound(_)));
     }
 
+    #[test]
+    fn test_find_list_by_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let list = repo.create_list("Work".to_string()).unwrap();
+
+        assert_eq!(repo.find_list_by_name("Work").unwrap().id, list.id);
+
+        let err = repo.find_list_by_name("Play").unwrap_err();
+        assert!(matches!(err, Error::ListNotFound(_)));
+        assert!(err.is_not_found());
+    }
+
+    #[test]
+    fn test_resolve_list_by_name_or_index() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+        let home = repo.create_list("Home".to_string()).unwrap();
+
+        assert_eq!(repo.resolve_list("Home").unwrap().id, home.id);
+        assert_eq!(repo.resolve_list("@0").unwrap().id, work.id);
+        assert_eq!(repo.resolve_list("@1").unwrap().id, home.id);
+
+        let err = repo.resolve_list("@2").unwrap_err();
+        assert!(matches!(err, Error::ListNotFound(_)));
+        assert!(err.to_string().contains("out of range"));
+        assert!(repo.resolve_list("@x").unwrap_err().is_not_found());
+        assert!(repo.resolve_list("Play").unwrap_err().is_not_found());
+
+        // A list literally named "@0" is matched by name first
+        let literal = repo.create_list("@0".to_string()).unwrap();
+        assert_eq!(repo.resolve_list("@0").unwrap().id, literal.id);
+    }
+
     #[test]
     fn test_delete_nonexistent_list() {
         let temp_dir = TempDir::new().unwrap();
@@ -323,7 +1618,7 @@ mod tests {
 
         let result = repo.create_list("Dupes".to_string());
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), Error::InvalidData(_)));
+        assert!(matches!(result.unwrap_err(), Error::ListAlreadyExists(_)));
     }
 
     #[test]
@@ -354,6 +1649,333 @@ mod tests {
         assert_eq!(tasks_b[0].title, "Movable");
     }
 
+    #[test]
+    fn test_move_task_keeps_source_when_write_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let list_a = repo.create_list("List A".to_string()).unwrap();
+        let list_b = repo.create_list("List B".to_string()).unwrap();
+        let task = repo.create_task(list_a.id, Task::new("Movable".to_string())).unwrap();
+
+        // A directory where the destination file would go makes the write fail
+        std::fs::create_dir(temp_dir.path().join("List B").join("Movable.md")).unwrap();
+        assert!(repo.move_task(list_a.id, list_b.id, task.id).is_err());
+
+        assert_eq!(repo.get_task(list_a.id, task.id).unwrap().title, "Movable");
+        assert_eq!(repo.get_task_order(list_a.id).unwrap(), vec![task.id]);
+        assert!(repo.list_tasks(list_b.id).unwrap().is_empty());
+        assert!(repo.get_task_order(list_b.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_move_task_within_same_list_is_noop() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let list = repo.create_list("List".to_string()).unwrap();
+        let task = repo.create_task(list.id, Task::new("Stay".to_string())).unwrap();
+
+        repo.move_task(list.id, list.id, task.id).unwrap();
+        assert_eq!(repo.get_task(list.id, task.id).unwrap().title, "Stay");
+    }
+
+    #[test]
+    fn test_merge_lists_rolls_back_when_write_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let src = repo.create_list("Source".to_string()).unwrap();
+        let dest = repo.create_list("Dest".to_string()).unwrap();
+        let kept = repo.create_task(dest.id, Task::new("d1".to_string())).unwrap();
+        let ids: Vec<Uuid> = ["s1", "s2", "s3"].iter()
+            .map(|title| repo.create_task(src.id, Task::new(title.to_string())).unwrap().id)
+            .collect();
+
+        // s1 copies fine, then s2 cannot be written
+        std::fs::create_dir(temp_dir.path().join("Dest").join("s2.md")).unwrap();
+        assert!(repo.merge_lists(src.id, dest.id, false).is_err());
+
+        let source_titles: Vec<String> = repo.list_tasks(src.id).unwrap().into_iter().map(|t| t.title).collect();
+        assert_eq!(source_titles, vec!["s1", "s2", "s3"]);
+        assert_eq!(repo.get_task_order(src.id).unwrap(), ids);
+        let dest_tasks = repo.list_tasks(dest.id).unwrap();
+        assert_eq!(dest_tasks.iter().map(|t| t.id).collect::<Vec<_>>(), vec![kept.id]);
+        assert_eq!(repo.get_task_order(dest.id).unwrap(), vec![kept.id]);
+    }
+
+    #[test]
+    fn test_merge_lists_preserves_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let src = repo.create_list("Source".to_string()).unwrap();
+        let dest = repo.create_list("Dest".to_string()).unwrap();
+
+        let titles = |repo: &TaskRepository, id| -> Vec<String> {
+            repo.list_tasks(id).unwrap().into_iter().map(|t| t.title).collect()
+        };
+        for title in ["d1", "d2"] {
+            repo.create_task(dest.id, Task::new(title.to_string())).unwrap();
+        }
+        let s1 = repo.create_task(src.id, Task::new("s1".to_string())).unwrap();
+        let s2 = repo.create_task(src.id, Task::new("s2".to_string())).unwrap();
+        let s3 = repo.create_task(src.id, Task::new("s3".to_string())).unwrap();
+        // Source order s3, s1, s2 differs from creation order
+        repo.reorder_task(src.id, s3.id, 0).unwrap();
+
+        let report = repo.merge_lists(src.id, dest.id, true).unwrap();
+        assert_eq!(report.moved, vec![s3.id, s1.id, s2.id]);
+        assert!(report.skipped.is_empty());
+        assert_eq!(titles(&repo, dest.id), vec!["d1", "d2", "s3", "s1", "s2"]);
+        assert_eq!(repo.get_task_order(dest.id).unwrap().len(), 5);
+        assert!(repo.get_list(src.id).unwrap_err().is_not_found());
+        assert_eq!(repo.get_lists().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_complete_all_leaves_completed_tasks_alone() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let list = repo.create_list("Launch".to_string()).unwrap();
+        let other = repo.create_list("Other".to_string()).unwrap();
+        let open: Vec<Uuid> = ["a", "b"].iter()
+            .map(|title| repo.create_task(list.id, Task::new(title.to_string())).unwrap().id)
+            .collect();
+        let mut done = Task::new("done".to_string());
+        done.complete();
+        done.completed_at = Some(chrono::Utc::now() - chrono::Duration::days(3));
+        let done = repo.create_task(list.id, done).unwrap();
+        let elsewhere = repo.create_task(other.id, Task::new("elsewhere".to_string())).unwrap();
+
+        assert_eq!(repo.complete_all(list.id).unwrap(), open);
+        let tasks = repo.list_tasks(list.id).unwrap();
+        assert!(tasks.iter().all(|t| t.status == TaskStatus::Completed && t.completed_at.is_some()));
+        let stored = tasks.iter().find(|t| t.id == done.id).unwrap();
+        assert_eq!((stored.completed_at, stored.updated_at), (done.completed_at, done.updated_at));
+        assert_eq!(repo.get_task(other.id, elsewhere.id).unwrap().status, TaskStatus::Backlog);
+
+        // Nothing left to complete
+        assert!(repo.complete_all(list.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reopen_all_resets_a_completed_checklist() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let list = repo.create_list("Packing".to_string()).unwrap();
+        let ids: Vec<Uuid> = ["Passport", "Charger", "Socks"].iter()
+            .map(|title| repo.create_task(list.id, Task::new(title.to_string())).unwrap().id)
+            .collect();
+        assert_eq!(repo.complete_all(list.id).unwrap(), ids);
+
+        assert_eq!(repo.reopen_all(list.id).unwrap(), ids);
+        let tasks = repo.list_tasks(list.id).unwrap();
+        assert_eq!(tasks.iter().map(|t| t.id).collect::<Vec<_>>(), ids);
+        assert!(tasks.iter().all(|t| t.status == TaskStatus::Backlog && t.completed_at.is_none()));
+        assert!(repo.reopen_all(list.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_split_list_by_status() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let src = repo.create_list("Chores".to_string()).unwrap();
+        let mut ids = Vec::new();
+        for (title, done) in [("a", true), ("b", false), ("c", true), ("d", true)] {
+            let mut task = Task::new(title.to_string());
+            if done {
+                task.complete();
+            }
+            ids.push(repo.create_task(src.id, task).unwrap().id);
+        }
+        repo.reorder_task(src.id, ids[3], 0).unwrap();
+
+        let query = TaskQuery { status: Some(TaskStatus::Completed), ..Default::default() };
+        let report = repo.split_list(src.id, "Done".to_string(), &query).unwrap();
+        assert_eq!(report.list.title, "Done");
+        assert_eq!(report.moved, vec![ids[3], ids[0], ids[2]]);
+
+        let titles = |id| -> Vec<String> { repo.list_tasks(id).unwrap().into_iter().map(|t| t.title).collect() };
+        assert_eq!(titles(src.id), vec!["b"]);
+        assert_eq!(titles(report.list.id), vec!["d", "a", "c"]);
+        assert_eq!(repo.get_task_order(src.id).unwrap(), vec![ids[1]]);
+        assert_eq!(repo.get_task_order(report.list.id).unwrap(), report.moved);
+    }
+
+    #[test]
+    fn test_split_list_by_tag() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let src = repo.create_list("Inbox".to_string()).unwrap();
+        let other = repo.create_list("Other".to_string()).unwrap();
+        let tagged = repo.create_task(src.id, Task { tags: vec!["DIY".to_string()], ..Task::new("Buy paint".to_string()) }).unwrap();
+        repo.create_task(src.id, Task::new("Call mum".to_string())).unwrap();
+        // Only the source list is split
+        repo.create_task(other.id, Task { tags: vec!["diy".to_string()], ..Task::new("Fix shelf".to_string()) }).unwrap();
+
+        let query = TaskQuery { tags: vec!["diy".to_string()], ..Default::default() };
+        let report = repo.split_list(src.id, "DIY".to_string(), &query).unwrap();
+        assert_eq!(report.moved, vec![tagged.id]);
+        assert_eq!(repo.get_task(report.list.id, tagged.id).unwrap().title, "Buy paint");
+        assert_eq!(repo.list_tasks(src.id).unwrap().len(), 1);
+        assert_eq!(repo.list_tasks(other.id).unwrap().len(), 1);
+
+        // No match, or a taken name: nothing is created or moved
+        let none = TaskQuery { tags: vec!["garden".to_string()], ..Default::default() };
+        assert!(repo.split_list(src.id, "Garden".to_string(), &none).unwrap_err().is_invalid_data());
+        assert!(matches!(repo.split_list(src.id, "other".to_string(), &TaskQuery::new()), Err(Error::ListAlreadyExists(_))));
+        assert_eq!(repo.get_lists().unwrap().len(), 3);
+        assert_eq!(repo.list_tasks(src.id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_merge_lists_duplicate_titles() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let titles = |repo: &TaskRepository, id| -> Vec<String> {
+            repo.list_tasks(id).unwrap().into_iter().map(|t| t.title).collect()
+        };
+
+        // Skipping: the destination's copy wins over an identical one
+        let src = repo.create_list("A".to_string()).unwrap();
+        let dest = repo.create_list("B".to_string()).unwrap();
+        let kept = repo.create_task(dest.id, Task::new("Milk".to_string())).unwrap();
+        let dup = repo.create_task(src.id, Task::new("Milk".to_string())).unwrap();
+        repo.create_task(src.id, Task::new("Eggs".to_string())).unwrap();
+        let report = repo.merge_lists(src.id, dest.id, true).unwrap();
+        assert_eq!(report.skipped, vec![dup.id]);
+        assert_eq!(titles(&repo, dest.id), vec!["Milk", "Eggs"]);
+        assert_eq!(repo.list_tasks(dest.id).unwrap()[0].id, kept.id);
+
+        // Keeping: the incoming copy gets a numbered title instead of overwriting
+        let src = repo.create_list("C".to_string()).unwrap();
+        repo.create_task(src.id, Task::new("Milk".to_string())).unwrap();
+        repo.create_task(src.id, Task::new("Milk (2)".to_string())).unwrap();
+        let report = repo.merge_lists(src.id, dest.id, false).unwrap();
+        assert_eq!(report.moved.len(), 2);
+        assert_eq!(report.renamed.len(), 2);
+        assert_eq!(titles(&repo, dest.id), vec!["Milk", "Eggs", "Milk (2)", "Milk (2) (2)"]);
+    }
+
+    #[test]
+    fn test_merge_lists_keeps_same_title_tasks_that_differ() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let src = repo.create_list("A".to_string()).unwrap();
+        let dest = repo.create_list("B".to_string()).unwrap();
+        repo.create_task(dest.id, Task::new("Milk".to_string())).unwrap();
+        let mut oat = Task::new("Milk".to_string());
+        oat.description = "Oat, not dairy".to_string();
+        let oat = repo.create_task(src.id, oat).unwrap();
+
+        let report = repo.merge_lists(src.id, dest.id, true).unwrap();
+        assert!(report.skipped.is_empty());
+        assert_eq!(report.renamed, vec![(oat.id, "Milk (2)".to_string())]);
+        let kept = repo.get_task(dest.id, oat.id).unwrap();
+        assert_eq!(kept.title, "Milk (2)");
+        assert_eq!(kept.description, "Oat, not dairy");
+        assert!(repo.get_list(src.id).unwrap_err().is_not_found());
+    }
+
+    #[test]
+    fn test_preview_merge_matches_merge() {
+        for skip_duplicates in [true, false] {
+            let temp_dir = TempDir::new().unwrap();
+            let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+            let src = repo.create_list("Source".to_string()).unwrap();
+            let dest = repo.create_list("Dest".to_string()).unwrap();
+            repo.create_task(dest.id, Task::new("Milk".to_string())).unwrap();
+            for title in ["Milk", "Eggs", "Milk (2)", "Bread"] {
+                repo.create_task(src.id, Task::new(title.to_string())).unwrap();
+            }
+
+            let preview = repo.preview_merge(src.id, dest.id, skip_duplicates).unwrap();
+            assert!(preview.deletes_source);
+            assert_eq!(repo.list_tasks(src.id).unwrap().len(), 4);
+            assert_eq!(repo.list_tasks(dest.id).unwrap().len(), 1);
+
+            let report = repo.merge_lists(src.id, dest.id, skip_duplicates).unwrap();
+            assert_eq!(preview.moved, report.moved);
+            assert_eq!(preview.skipped, report.skipped);
+            assert_eq!(preview.renamed, report.renamed);
+            assert_eq!(repo.list_tasks(dest.id).unwrap().len(), 1 + preview.moved.len());
+            assert!(repo.get_list(src.id).unwrap_err().is_not_found());
+        }
+    }
+
+    #[test]
+    fn test_merge_lists_rejects_self_and_parents() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let a = repo.create_list("A".to_string()).unwrap();
+        let b = repo.create_list("B".to_string()).unwrap();
+        repo.create_sublist(a.id, "Child".to_string()).unwrap();
+
+        assert!(repo.merge_lists(b.id, b.id, true).unwrap_err().is_invalid_data());
+        assert!(repo.merge_lists(a.id, b.id, true).unwrap_err().is_invalid_data());
+        assert!(repo.merge_lists(Uuid::new_v4(), b.id, true).unwrap_err().is_not_found());
+        assert!(repo.preview_merge(a.id, b.id, true).unwrap_err().is_invalid_data());
+    }
+
+    #[test]
+    fn test_created_list_round_trips_through_get_list() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+
+        let created = repo.create_list("Fresh".to_string()).unwrap();
+        let stored = repo.get_list(created.id).unwrap();
+        assert_eq!(stored.title, created.title);
+        assert_eq!(stored.created_at, created.created_at);
+        assert_eq!(stored.updated_at, created.updated_at);
+        assert_eq!(stored.group_by_due_date, created.group_by_due_date);
+        assert_eq!(stored.parent_id, created.parent_id);
+        assert_eq!(stored.depth, created.depth);
+        assert!(stored.tasks.is_empty());
+
+        let sub = repo.create_sublist(created.id, "Nested".to_string()).unwrap();
+        let stored = repo.get_list(sub.id).unwrap();
+        assert_eq!((stored.parent_id, stored.depth), (Some(created.id), 1));
+        assert_eq!((sub.parent_id, sub.depth), (Some(created.id), 1));
+
+        let id = repo.create_list_with_id("By ID".to_string()).unwrap();
+        assert_eq!(repo.get_list(id).unwrap().title, "By ID");
+    }
+
+    #[test]
+    fn test_create_list_rejects_duplicate_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+
+        let err = repo.create_list("work".to_string()).unwrap_err();
+        assert!(matches!(err, Error::ListAlreadyExists(_)));
+        assert!(err.is_invalid_data());
+        assert!(err.to_string().contains("'Work' already exists"));
+        assert!(repo.create_sublist(work.id, "WORK".to_string()).unwrap_err().is_invalid_data());
+        assert_eq!(repo.get_lists().unwrap().len(), 1);
+
+        repo.allow_duplicate_list_names(true);
+        repo.create_list("work".to_string()).unwrap();
+        repo.create_sublist(work.id, "Work".to_string()).unwrap();
+        assert_eq!(repo.get_lists().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_rename_list_rejects_duplicate_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        repo.create_list("Work".to_string()).unwrap();
+        let home = repo.create_list("Home".to_string()).unwrap();
+
+        assert!(repo.rename_list(home.id, "WORK".to_string()).unwrap_err().is_invalid_data());
+        assert_eq!(repo.get_list(home.id).unwrap().title, "Home");
+
+        // Changing only the case of a list's own name is fine
+        repo.rename_list(home.id, "HOME".to_string()).unwrap();
+        assert_eq!(repo.get_list(home.id).unwrap().title, "HOME");
+
+        repo.allow_duplicate_list_names(true);
+        repo.rename_list(home.id, "work".to_string()).unwrap();
+        assert_eq!(repo.get_list(home.id).unwrap().title, "work");
+    }
+
     #[test]
     fn test_rename_list() {
         let temp_dir = TempDir::new().unwrap();
@@ -380,7 +2002,7 @@ mod tests {
 
         let result = repo.rename_list(list_b.id, "A".to_string());
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), Error::InvalidData(_)));
+        assert!(matches!(result.unwrap_err(), Error::ListAlreadyExists(_)));
     }
 
     #[test]
@@ -438,6 +2060,114 @@ mod tests {
         assert!(!old_path.exists());
     }
 
+    #[test]
+    fn test_get_task_source_parses_back() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let list = repo.create_list("Test".to_string()).unwrap();
+
+        let task = repo.create_task(list.id, Task::new("Raw".to_string())
+            .with_description("Body text".to_string())).unwrap();
+
+        let source = repo.get_task_source(list.id, task.id).unwrap();
+        assert!(source.starts_with("---\n"));
+
+        let parsed = crate::storage::parse_task_file(&source, "Raw").unwrap();
+        assert_eq!(parsed.id, task.id);
+        assert_eq!(parsed.description, "Body text");
+    }
+
+    #[test]
+    fn test_workspace_settings_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+
+        assert_eq!(repo.get_workspace_settings().unwrap(), WorkspaceSettings::default());
+
+        let settings = WorkspaceSettings {
+            default_list: Some("Work".to_string()),
+            ..Default::default()
+        };
+        repo.set_workspace_settings(&settings).unwrap();
+
+        let reopened = TaskRepository::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(reopened.get_workspace_settings().unwrap(), settings);
+    }
+
+    #[test]
+    fn test_global_defaults_decide_how_task_files_are_written() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let list = repo.create_list("Work".to_string()).unwrap();
+        let defaults = WorkspaceSettings {
+            frontmatter_format: Some(crate::config::FrontmatterFormat::Toml),
+            default_list: Some("Home".to_string()),
+            ..Default::default()
+        };
+
+        let mut reopened = TaskRepository::new(temp_dir.path().to_path_buf()).unwrap();
+        reopened.set_settings_defaults(defaults.clone());
+        reopened.create_task(list.id, Task::new("Global".to_string())).unwrap();
+        let content = std::fs::read_to_string(temp_dir.path().join("Work").join("Global.md")).unwrap();
+        assert!(content.starts_with("+++\n"), "{}", content);
+        assert_eq!(reopened.effective_workspace_settings().unwrap().default_list.as_deref(), Some("Home"));
+        assert_eq!(reopened.get_workspace_settings().unwrap(), WorkspaceSettings::default());
+
+        // The workspace's own setting wins, also once it is changed
+        reopened.set_workspace_settings(&WorkspaceSettings {
+            frontmatter_format: Some(crate::config::FrontmatterFormat::Yaml),
+            ..Default::default()
+        }).unwrap();
+        reopened.create_task(list.id, Task::new("Local".to_string())).unwrap();
+        let content = std::fs::read_to_string(temp_dir.path().join("Work").join("Local.md")).unwrap();
+        assert!(content.starts_with("---\n"), "{}", content);
+    }
+
+    #[test]
+    fn test_global_filename_scheme_names_and_renames_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let list = repo.create_list("Work".to_string()).unwrap();
+        let old = repo.create_task(list.id, Task::new("Old".to_string())).unwrap();
+
+        repo.set_settings_defaults(WorkspaceSettings {
+            filename_scheme: Some(crate::config::FilenameScheme::Uuid),
+            ..Default::default()
+        });
+        let new = repo.create_task(list.id, Task::new("New".to_string())).unwrap();
+        assert!(temp_dir.path().join("Work").join(format!("{}.md", new.id)).exists());
+
+        let renames = repo.rename_task_files(false).unwrap();
+        assert_eq!(renames.iter().map(|r| r.task_id).collect::<Vec<_>>(), vec![old.id]);
+        assert!(temp_dir.path().join("Work").join(format!("{}.md", old.id)).exists());
+        assert_eq!(repo.get_task(list.id, old.id).unwrap().title, "Old");
+    }
+
+    #[test]
+    fn test_strict_tags_are_enforced_on_every_write() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let list = repo.create_list("Work".to_string()).unwrap();
+        let mut task = repo.create_task(list.id, Task::builder("Report").tags(["q3"]).build()).unwrap();
+
+        // Strict from the global defaults, without a vocabulary of the workspace's own
+        repo.set_settings_defaults(WorkspaceSettings {
+            allowed_tags: vec!["work".to_string()],
+            strict_tags: Some(true),
+            ..Default::default()
+        });
+        let refused = Task::builder("Plan").tags(["wrk"]).build();
+        assert!(matches!(repo.create_task(list.id, refused), Err(Error::InvalidData(_))));
+        repo.create_task(list.id, Task::builder("Plan").tags(["Work"]).build()).unwrap();
+
+        // "q3" predates the vocabulary: the task stays editable, but no new tag gets in
+        task.title = "Quarterly report".to_string();
+        repo.update_task(list.id, task.clone()).unwrap();
+        task.tags.push("misc".to_string());
+        assert!(matches!(repo.update_task(list.id, task), Err(Error::InvalidData(_))));
+        assert_eq!(repo.list_tasks(list.id).unwrap().len(), 2);
+    }
+
     #[test]
     fn test_task_order_after_delete() {
         let temp_dir = TempDir::new().unwrap();
@@ -455,4 +2185,287 @@ mod tests {
         assert_eq!(order[0], t1.id);
         assert_eq!(order[1], t3.id);
     }
+
+    #[test]
+    fn test_verify_and_repair_inconsistent_workspace() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let mut repo = TaskRepository::init(root.to_path_buf()).unwrap();
+        let list = repo.create_list("Work".to_string()).unwrap();
+        let kept = repo.create_task(list.id, Task::new("Kept".to_string())).unwrap();
+        let gone = repo.create_task(list.id, Task::new("Gone".to_string())).unwrap();
+
+        // Delete a task file behind the repository's back, and drop in one it doesn't know.
+        std::fs::remove_file(root.join("Work").join("Gone.md")).unwrap();
+        let stray = Task::new("Stray".to_string());
+        std::fs::write(
+            root.join("Work").join("Stray.md"),
+            crate::storage::serialize_task(&stray).unwrap(),
+        ).unwrap();
+        // A list id in root metadata that no longer exists, and a folder with no metadata.
+        let mut root_meta = repo.storage.read_root_metadata().unwrap();
+        let ghost = Uuid::new_v4();
+        root_meta.list_order.insert(0, ghost);
+        repo.storage.write_root_metadata(&root_meta).unwrap();
+        std::fs::create_dir(root.join("Loose")).unwrap();
+        std::fs::create_dir(root.join(".git")).unwrap();
+
+        let check = repo.verify_and_repair(true).unwrap();
+        assert!(!check.is_clean());
+        assert_eq!(check.lists[0].removed, vec![gone.id]);
+        assert_eq!(check.lists[0].added, vec![stray.id]);
+        assert_eq!(check.removed_lists, vec![ghost]);
+        assert_eq!(check.unregistered_dirs, vec!["Loose".to_string()]);
+        // Check-only leaves metadata untouched.
+        assert_eq!(repo.get_task_order(list.id).unwrap(), vec![kept.id, gone.id]);
+
+        let repaired = repo.verify_and_repair(false).unwrap();
+        assert_eq!(repaired, check);
+        assert_eq!(repo.get_task_order(list.id).unwrap(), vec![kept.id, stray.id]);
+        assert_eq!(repo.storage.read_root_metadata().unwrap().list_order, vec![list.id]);
+
+        let again = repo.verify_and_repair(false).unwrap();
+        assert!(again.is_clean());
+        assert_eq!(again.unregistered_dirs, vec!["Loose".to_string()]);
+    }
+
+    #[test]
+    fn test_verify_check_only_writes_nothing() {
+        fn snapshot(dir: &Path, files: &mut std::collections::BTreeMap<PathBuf, Vec<u8>>) {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    snapshot(&path, files);
+                } else {
+                    files.insert(path.clone(), std::fs::read(&path).unwrap());
+                }
+            }
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let mut repo = TaskRepository::init(root.to_path_buf()).unwrap();
+        let list = repo.create_list("Work".to_string()).unwrap();
+        repo.create_task(list.id, Task::new("Kept".to_string())).unwrap();
+        std::fs::write(
+            root.join("Work").join("Stray.md"),
+            crate::storage::serialize_task(&Task::new("Stray".to_string())).unwrap(),
+        ).unwrap();
+        // A hand-made folder that reading the lists would adopt
+        std::fs::create_dir(root.join("Inbox")).unwrap();
+        std::fs::write(
+            root.join("Inbox").join("Loose.md"),
+            crate::storage::serialize_task(&Task::new("Loose".to_string())).unwrap(),
+        ).unwrap();
+
+        let mut before = std::collections::BTreeMap::new();
+        snapshot(root, &mut before);
+        let check = repo.verify_and_repair(true).unwrap();
+        let mut after = std::collections::BTreeMap::new();
+        snapshot(root, &mut after);
+        assert_eq!(before, after);
+        assert_eq!(check.adoptable_dirs, vec!["Inbox".to_string()]);
+        assert!(check.unregistered_dirs.is_empty());
+        assert!(!check.is_clean());
+
+        let repaired = repo.verify_and_repair(false).unwrap();
+        assert_eq!(repaired.adoptable_dirs, vec!["Inbox".to_string()]);
+        assert!(root.join("Inbox").join(".listdata.json").exists());
+        assert!(repo.verify_and_repair(true).unwrap().is_clean());
+    }
+
+    #[test]
+    fn test_recent_tasks_sorted_and_windowed() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+        let home = repo.create_list("Home".to_string()).unwrap();
+        let now = chrono::Utc::now();
+
+        let mut old = Task::new("Old".to_string());
+        old.updated_at = now - chrono::Duration::days(10);
+        let mut yesterday = Task::new("Yesterday".to_string());
+        yesterday.updated_at = now - chrono::Duration::days(1);
+        let mut hour_ago = Task::new("Hour ago".to_string());
+        hour_ago.updated_at = now - chrono::Duration::hours(1);
+        repo.create_task(work.id, old).unwrap();
+        repo.create_task(work.id, yesterday).unwrap();
+        repo.create_task(home.id, hour_ago).unwrap();
+
+        let recent = repo.recent_tasks(now - chrono::Duration::days(7)).unwrap();
+        let titles: Vec<(&str, &str)> = recent.iter()
+            .map(|(list, task)| (list.as_str(), task.title.as_str()))
+            .collect();
+        assert_eq!(titles, vec![("Home", "Hour ago"), ("Work", "Yesterday")]);
+    }
+
+    #[test]
+    fn test_query_across_lists_pairs_list_ids() {
+        use crate::query::QuerySort;
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+        let home = repo.create_list("Home".to_string()).unwrap();
+        let now = chrono::Utc::now();
+
+        repo.create_task(work.id, Task::builder("Later").tag("work").due_time(now + chrono::Duration::days(3)).build()).unwrap();
+        repo.create_task(work.id, Task::builder("Soon").tag("work").due_time(now + chrono::Duration::days(1)).build()).unwrap();
+        repo.create_task(home.id, Task::builder("Done").tag("work").status(TaskStatus::Completed).build()).unwrap();
+        repo.create_task(home.id, Task::builder("Chores").tag("home").build()).unwrap();
+
+        let query = TaskQuery {
+            status: Some(TaskStatus::Backlog),
+            tags: vec!["work".to_string()],
+            sort: QuerySort::Due,
+            ..Default::default()
+        };
+        let found: Vec<(Uuid, String)> = repo.query(&query).unwrap()
+            .into_iter()
+            .map(|(list_id, task)| (list_id, task.title))
+            .collect();
+        assert_eq!(found, vec![(work.id, "Soon".to_string()), (work.id, "Later".to_string())]);
+        assert_eq!(repo.query(&TaskQuery::new()).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_iter_tasks_matches_get_lists_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+        let home = repo.create_list("Home".to_string()).unwrap();
+        let sub = repo.create_sublist(work.id, "Reviews".to_string()).unwrap();
+        for (list, title) in [(home.id, "Dishes"), (work.id, "Report"), (sub.id, "PR 12"), (work.id, "Email")] {
+            repo.create_task(list, Task::new(title.to_string())).unwrap();
+        }
+        let email = repo.list_tasks(work.id).unwrap()[1].id;
+        repo.reorder_task(work.id, email, 0).unwrap();
+
+        let streamed: Vec<(Uuid, String)> = repo.iter_tasks().unwrap()
+            .map(|item| item.map(|(list_id, task)| (list_id, task.title)))
+            .collect::<Result<_>>()
+            .unwrap();
+        let expected: Vec<(Uuid, String)> = repo.get_lists().unwrap()
+            .into_iter()
+            .flat_map(|list| list.tasks.into_iter().map(move |task| (list.id, task.title)))
+            .collect();
+        assert_eq!(streamed, expected);
+        assert_eq!(streamed[0], (work.id, "Email".to_string()));
+        assert!(repo.get_list_headers().unwrap().iter().all(|list| list.tasks.is_empty()));
+    }
+
+    #[test]
+    fn test_due_reminders_across_lists_and_mark_sent() {
+        use chrono::TimeZone;
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+        let home = repo.create_list("Home".to_string()).unwrap();
+        let at = |h| chrono::Utc.with_ymd_and_hms(2026, 6, 1, h, 0, 0).unwrap();
+
+        let report = repo.create_task(work.id, Task::builder("Report").reminder(at(11)).reminder(at(18)).build()).unwrap();
+        repo.create_task(home.id, Task::builder("Laundry").reminder(at(9)).build()).unwrap();
+        repo.create_task(home.id, Task::builder("Done").reminder(at(8)).status(crate::TaskStatus::Completed).build()).unwrap();
+
+        let due = repo.due_reminders(at(12)).unwrap();
+        let titles: Vec<(&str, &str)> = due.iter()
+            .map(|r| (r.list_title.as_str(), r.task.title.as_str()))
+            .collect();
+        assert_eq!(titles, vec![("Home", "Laundry"), ("Work", "Report")]);
+
+        for reminder in &due {
+            repo.mark_reminder_sent(reminder).unwrap();
+        }
+        assert!(repo.due_reminders(at(12)).unwrap().is_empty());
+        let stored = repo.get_task(work.id, report.id).unwrap();
+        assert_eq!(stored.reminders_sent, vec![at(11)]);
+        assert_eq!(stored.updated_at, report.updated_at);
+        assert_eq!(repo.due_reminders(at(19)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_completion_history_sorted_and_skips_open_tasks() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let list = repo.create_list("Habits".to_string()).unwrap();
+        let now = chrono::Utc::now();
+
+        let mut later = Task::new("Later".to_string());
+        later.complete();
+        let mut earlier = Task::new("Earlier".to_string());
+        earlier.complete();
+        earlier.completed_at = Some(now - chrono::Duration::days(2));
+        repo.create_task(list.id, later.clone()).unwrap();
+        repo.create_task(list.id, earlier.clone()).unwrap();
+        repo.create_task(list.id, Task::new("Open".to_string())).unwrap();
+
+        let history = repo.completion_history(list.id).unwrap();
+        assert_eq!(history, vec![earlier.completed_at.unwrap(), later.completed_at.unwrap()]);
+    }
+
+    #[test]
+    fn test_assigned_tasks_across_lists() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+        let home = repo.create_list("Home".to_string()).unwrap();
+
+        repo.create_task(work.id, Task::builder("Mine at work").assignee("Sam").build()).unwrap();
+        repo.create_task(work.id, Task::builder("Theirs").assignee("Alex").build()).unwrap();
+        repo.create_task(home.id, Task::builder("Mine at home").assignee("sam").build()).unwrap();
+        repo.create_task(home.id, Task::new("Anyone".to_string())).unwrap();
+
+        let titles = |tasks: Vec<(String, Task)>| -> Vec<(String, String)> {
+            tasks.into_iter().map(|(list, task)| (list, task.title)).collect()
+        };
+        assert_eq!(titles(repo.assigned_tasks("Sam", false).unwrap()), vec![
+            ("Work".to_string(), "Mine at work".to_string()),
+            ("Home".to_string(), "Mine at home".to_string()),
+        ]);
+        let with_unassigned = repo.assigned_tasks("Sam", true).unwrap();
+        assert_eq!(with_unassigned.len(), 3);
+        assert!(with_unassigned.iter().any(|(_, t)| t.title == "Anyone"));
+    }
+
+    #[test]
+    fn test_last_opened_list() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+        let home = repo.create_list("Home".to_string()).unwrap();
+
+        // The first list created becomes the last-opened one
+        assert_eq!(repo.get_last_opened_list().unwrap(), Some(work.id));
+        repo.set_last_opened_list(home.id).unwrap();
+        assert_eq!(repo.get_last_opened_list().unwrap(), Some(home.id));
+
+        let err = repo.set_last_opened_list(Uuid::new_v4()).unwrap_err();
+        assert!(matches!(err, Error::ListNotFound(_)));
+        assert_eq!(repo.get_last_opened_list().unwrap(), Some(home.id));
+    }
+
+    #[test]
+    fn test_inbox_list() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = TaskRepository::init(temp_dir.path().to_path_buf()).unwrap();
+        let work = repo.create_list("Work".to_string()).unwrap();
+        let inbox = repo.create_list("Inbox".to_string()).unwrap();
+        assert_eq!(repo.get_inbox_list().unwrap(), None);
+
+        repo.set_inbox_list(Some(inbox.id)).unwrap();
+        assert_eq!(repo.get_inbox_list().unwrap(), Some(inbox.id));
+        // Independent of the last-opened list
+        repo.set_last_opened_list(work.id).unwrap();
+        assert_eq!(repo.get_inbox_list().unwrap(), Some(inbox.id));
+
+        assert!(matches!(repo.set_inbox_list(Some(Uuid::new_v4())).unwrap_err(), Error::ListNotFound(_)));
+        assert_eq!(repo.get_inbox_list().unwrap(), Some(inbox.id));
+
+        repo.set_inbox_list(None).unwrap();
+        assert_eq!(repo.get_inbox_list().unwrap(), None);
+
+        // Deleting the inbox list unsets it
+        repo.set_inbox_list(Some(inbox.id)).unwrap();
+        repo.delete_list(inbox.id).unwrap();
+        assert_eq!(repo.get_inbox_list().unwrap(), None);
+    }
 }
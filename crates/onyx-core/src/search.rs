@@ -0,0 +1,253 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::Path;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::error::Result;
+use crate::models::Task;
+
+/// Inverted index over task titles and descriptions, so a text search only has to read
+/// the task files that can match. Saved as [`SearchIndex::FILE_NAME`] in the workspace
+/// root; it is local to this machine (sync leaves it alone). Each search first re-indexes
+/// the task files modified since they were indexed, whoever changed them: those whose
+/// modification time or size differs from the indexed one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchIndex {
+    pub version: u32,
+    /// Every indexed task file, by path relative to the workspace root (`/`-separated).
+    pub files: BTreeMap<String, IndexedFile>,
+    /// Each word of a title or description (see [`terms`]) and the tasks that contain it.
+    pub terms: BTreeMap<String, BTreeSet<Uuid>>,
+}
+
+/// What the index knows about one task file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexedFile {
+    pub id: Uuid,
+    pub list_id: Uuid,
+    /// The file's modification time when it was indexed; any other time means it was
+    /// changed behind the index's back.
+    pub modified: DateTime<Utc>,
+    /// The file's size in bytes when it was indexed. Checked along with `modified`, since
+    /// a file system with coarse timestamps can rewrite a file without changing its time.
+    /// Indexes saved before this was recorded read it as 0, so their files are re-indexed.
+    #[serde(default)]
+    pub size: u64,
+}
+
+impl Default for SearchIndex {
+    fn default() -> Self {
+        Self {
+            version: Self::VERSION,
+            files: BTreeMap::new(),
+            terms: BTreeMap::new(),
+        }
+    }
+}
+
+impl SearchIndex {
+    pub const FILE_NAME: &'static str = ".search-index.json";
+    const VERSION: u32 = 1;
+
+    /// The saved index of the workspace at `root`. `None` when there is none, or it can't
+    /// be read or was written by another version: the index is only a cache, so callers
+    /// scan the task files instead.
+    pub fn load(root: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(root.join(Self::FILE_NAME)).ok()?;
+        let index: Self = serde_json::from_str(&content).ok()?;
+        (index.version == Self::VERSION).then_some(index)
+    }
+
+    /// Write the index via a temporary file and rename, so a reader never sees half of it.
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = root.join(Self::FILE_NAME);
+        let tmp_path = root.join(format!("{}.tmp", Self::FILE_NAME));
+        std::fs::write(&tmp_path, serde_json::to_string(self)?)?;
+        std::fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+
+    /// Index `task`, stored at `path` (`size` bytes, last modified at `modified`) in list
+    /// `list_id`, replacing whatever was indexed for it before (under this path or another).
+    pub fn insert(&mut self, path: String, list_id: Uuid, task: &Task, modified: DateTime<Utc>, size: u64) {
+        self.remove(task.id);
+        self.add(path, list_id, task, modified, size);
+    }
+
+    /// Forget the task with `task_id`, if it is indexed.
+    pub fn remove(&mut self, task_id: Uuid) {
+        self.retain_files(|_, file| file.id != task_id);
+    }
+
+    /// Forget every file in the folder `folder` (a path like the keys of
+    /// [`Self::files`]) and its subfolders, as deleting a list removes them.
+    pub fn remove_folder(&mut self, folder: &str) {
+        let prefix = format!("{}/", folder);
+        self.retain_files(|path, _| !path.starts_with(&prefix));
+    }
+
+    /// Move every file indexed in the folder `from` and its subfolders to the same place
+    /// under `to`, as renaming a list does. The files themselves are unchanged.
+    pub fn rename_folder(&mut self, from: &str, to: &str) {
+        let from = format!("{}/", from);
+        let moved: Vec<String> = self.files.keys().filter(|path| path.starts_with(&from)).cloned().collect();
+        for path in moved {
+            if let Some(file) = self.files.remove(&path) {
+                self.files.insert(format!("{}/{}", to, &path[from.len()..]), file);
+            }
+        }
+    }
+
+    /// Bring the index up to date in one pass: keep only the indexed files `fresh`
+    /// accepts, then index `changed`, the tasks re-read from the other files on disk
+    /// (each with its path, list, modification time and size).
+    pub fn refresh<'a>(
+        &mut self,
+        fresh: impl Fn(&str, &IndexedFile) -> bool,
+        changed: impl IntoIterator<Item = (String, Uuid, &'a Task, DateTime<Utc>, u64)>,
+    ) {
+        let changed: Vec<_> = changed.into_iter().collect();
+        let reread: HashSet<Uuid> = changed.iter().map(|(_, _, task, _, _)| task.id).collect();
+        self.retain_files(|path, file| fresh(path, file) && !reread.contains(&file.id));
+        for (path, list_id, task, modified, size) in changed {
+            self.add(path, list_id, task, modified, size);
+        }
+    }
+
+    /// Drop the files `keep` rejects, and the terms of the tasks in them, in one pass
+    /// over the terms however many files go.
+    fn retain_files(&mut self, mut keep: impl FnMut(&str, &IndexedFile) -> bool) {
+        let mut dropped = HashSet::new();
+        self.files.retain(|path, file| {
+            let kept = keep(path, file);
+            if !kept {
+                dropped.insert(file.id);
+            }
+            kept
+        });
+        if dropped.is_empty() {
+            return;
+        }
+        self.terms.retain(|_, ids| {
+            ids.retain(|id| !dropped.contains(id));
+            !ids.is_empty()
+        });
+    }
+
+    /// Index `task` at `path`, which must not be indexed already.
+    fn add(&mut self, path: String, list_id: Uuid, task: &Task, modified: DateTime<Utc>, size: u64) {
+        for term in terms(&task.title).chain(terms(&task.description)) {
+            self.terms.entry(term).or_default().insert(task.id);
+        }
+        self.files.insert(path, IndexedFile { id: task.id, list_id, modified, size });
+    }
+
+    /// Tasks that may contain `text`, compared the way [`crate::TaskQuery::text`] is (a
+    /// case-insensitive substring): each word of `text` is part of one of the task's
+    /// words. A superset of the real matches, which still have to be checked. `None` when
+    /// `text` has no words, so the index can't narrow the search down.
+    pub fn candidates(&self, text: &str) -> Option<HashSet<Uuid>> {
+        let mut result: Option<HashSet<Uuid>> = None;
+        for word in terms(text) {
+            let ids: HashSet<Uuid> = self.terms.iter()
+                .filter(|(term, _)| term.contains(&word))
+                .flat_map(|(_, ids)| ids.iter().copied())
+                .collect();
+            result = Some(match result {
+                Some(found) => found.intersection(&ids).copied().collect(),
+                None => ids,
+            });
+        }
+        result
+    }
+}
+
+/// The words of `text` as the index stores them: lowercased runs of letters and digits.
+pub fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(tasks: &[&Task]) -> SearchIndex {
+        let mut index = SearchIndex::default();
+        for task in tasks {
+            index.insert(format!("Work/{}.md", task.title), Uuid::nil(), task, Utc::now(), 0);
+        }
+        index
+    }
+
+    #[test]
+    fn test_terms_are_lowercased_words() {
+        let words: Vec<String> = terms("Pay rent (ASAP!), café-bill #2").collect();
+        assert_eq!(words, vec!["pay", "rent", "asap", "café", "bill", "2"]);
+        assert_eq!(terms(" -- ").count(), 0);
+    }
+
+    #[test]
+    fn test_candidates_cover_every_substring_match() {
+        let rent = Task::new("Pay rent".to_string());
+        let call = Task::new("Call landlord".to_string()).with_description("About the rent".to_string());
+        let milk = Task::new("Buy milk".to_string());
+        let index = index(&[&rent, &call, &milk]);
+
+        assert_eq!(index.candidates("RENT"), Some(HashSet::from([rent.id, call.id])));
+        // Partial words at either end, spanning a word break
+        assert_eq!(index.candidates("ay re"), Some(HashSet::from([rent.id])));
+        assert_eq!(index.candidates("land"), Some(HashSet::from([call.id])));
+        assert_eq!(index.candidates("bread"), Some(HashSet::new()));
+        assert_eq!(index.candidates("  "), None);
+    }
+
+    #[test]
+    fn test_reinserting_a_task_replaces_its_terms() {
+        let mut task = Task::new("Pay rent".to_string());
+        let mut index = index(&[&task]);
+        task.title = "Pay bills".to_string();
+        index.insert("Work/Pay bills.md".to_string(), Uuid::nil(), &task, Utc::now(), 0);
+
+        assert_eq!(index.candidates("rent"), Some(HashSet::new()));
+        assert_eq!(index.candidates("bills"), Some(HashSet::from([task.id])));
+        assert_eq!(index.files.keys().collect::<Vec<_>>(), vec!["Work/Pay bills.md"]);
+
+        index.remove(task.id);
+        assert_eq!(index, SearchIndex::default());
+    }
+
+    #[test]
+    fn test_folders_move_and_go_with_their_files() {
+        let rent = Task::new("Pay rent".to_string());
+        let milk = Task::new("Buy milk".to_string());
+        let mut index = index(&[&rent, &milk]);
+        index.insert("Workshop/Tools.md".to_string(), Uuid::nil(), &Task::new("Tools".to_string()), Utc::now(), 0);
+
+        // Only whole folder names match, not "Workshop"
+        index.rename_folder("Work", "Office/Work");
+        let keys: Vec<&String> = index.files.keys().collect();
+        assert_eq!(keys, vec!["Office/Work/Buy milk.md", "Office/Work/Pay rent.md", "Workshop/Tools.md"]);
+
+        index.remove_folder("Office");
+        assert_eq!(index.files.keys().collect::<Vec<_>>(), vec!["Workshop/Tools.md"]);
+        assert_eq!(index.candidates("rent"), Some(HashSet::new()));
+        assert_eq!(index.candidates("tool").map(|ids| ids.len()), Some(1));
+    }
+
+    #[test]
+    fn test_refresh_replaces_stale_files() {
+        let mut rent = Task::new("Pay rent".to_string());
+        let milk = Task::new("Buy milk".to_string());
+        let mut index = index(&[&rent, &milk]);
+
+        // "Pay rent" was renamed on disk, "Buy milk" deleted
+        rent.title = "Pay bills".to_string();
+        index.refresh(|_, _| false, [("Work/Pay bills.md".to_string(), Uuid::nil(), &rent, Utc::now(), 0)]);
+        assert_eq!(index.files.keys().collect::<Vec<_>>(), vec!["Work/Pay bills.md"]);
+        assert_eq!(index.candidates("bills"), Some(HashSet::from([rent.id])));
+        assert_eq!(index.candidates("milk"), Some(HashSet::new()));
+        assert_eq!(index.terms.len(), 2);
+    }
+}
@@ -0,0 +1,325 @@
+//! A higher-level facade over [`TaskRepository`] for apps embedding onyx-core.
+//!
+//! [`TaskService`] addresses lists by name and tasks by (a prefix of) their ID, returns
+//! plain value types instead of whole [`TaskList`]s, and takes `&self` everywhere: the
+//! repository sits behind a mutex, so one service can be shared between threads (for
+//! example as Tauri state) without wrapping it again.
+//!
+//! ```
+//! use onyx_core::service::{NewTask, TaskService};
+//!
+//! # let dir = tempfile::TempDir::new()?;
+//! let service = TaskService::init(dir.path().to_path_buf())?;
+//! service.create_list("My Tasks")?;
+//! let task = service.add_task("My Tasks", "Buy milk", NewTask::default())?;
+//!
+//! // The first few characters of an ID are enough when they are unique
+//! let prefix = &task.id.to_string()[..8];
+//! assert!(service.complete_by_prefix(prefix)?.completed);
+//!
+//! let overview = service.lists_overview()?;
+//! assert_eq!((overview[0].title.as_str(), overview[0].completed), ("My Tasks", 1));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Anything the facade doesn't cover is still available on the repository itself through
+//! [`TaskService::with_repository`].
+
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use crate::error::{Error, Result};
+use crate::models::{tasks_with_id_prefix, Priority, Task, TaskList, TaskStatus};
+use crate::repository::TaskRepository;
+
+/// Most matches listed when an ID prefix is ambiguous.
+const MAX_PREFIX_MATCHES: usize = 5;
+
+/// Optional fields for [`TaskService::add_task`].
+#[derive(Debug, Clone, Default)]
+pub struct NewTask {
+    pub description: String,
+    pub due_date: Option<DateTime<Utc>>,
+    /// Whether `due_date` carries a time of day, rather than only a date.
+    pub has_time: bool,
+    pub priority: Option<Priority>,
+    pub tags: Vec<String>,
+    pub assignee: Option<String>,
+}
+
+/// A task as returned by [`TaskService`], with the list it is in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskSummary {
+    pub id: Uuid,
+    pub list_id: Uuid,
+    pub list: String,
+    pub title: String,
+    pub completed: bool,
+    pub due_date: Option<DateTime<Utc>>,
+    pub priority: Option<Priority>,
+    pub tags: Vec<String>,
+    pub assignee: Option<String>,
+}
+
+impl TaskSummary {
+    fn new(list: &TaskList, task: &Task) -> Self {
+        Self {
+            id: task.id,
+            list_id: list.id,
+            list: list.title.clone(),
+            title: task.title.clone(),
+            completed: task.status == TaskStatus::Completed,
+            due_date: task.due_date,
+            priority: task.priority,
+            tags: task.tags.clone(),
+            assignee: task.assignee.clone(),
+        }
+    }
+}
+
+/// Task counts for one list, from [`TaskService::lists_overview`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListOverview {
+    pub id: Uuid,
+    pub title: String,
+    /// Nesting level: 0 for top-level lists, 1 for their sublists, and so on.
+    pub depth: usize,
+    pub open: usize,
+    pub completed: usize,
+    /// Open tasks whose due date has passed.
+    pub overdue: usize,
+}
+
+impl ListOverview {
+    fn new(list: &TaskList, now: DateTime<Utc>) -> Self {
+        Self {
+            id: list.id,
+            title: list.title.clone(),
+            depth: list.depth,
+            open: list.open_count(),
+            completed: list.completed_count(),
+            overdue: list.tasks.iter().filter(|t| t.is_overdue(now)).count(),
+        }
+    }
+}
+
+/// Thread-safe, name-addressed facade over a [`TaskRepository`]. See the
+/// [module docs](self) for an example.
+pub struct TaskService {
+    repo: Mutex<TaskRepository>,
+}
+
+impl TaskService {
+    /// Open an existing workspace folder.
+    pub fn open(tasks_folder: PathBuf) -> Result<Self> {
+        Ok(Self::from_repository(TaskRepository::new(tasks_folder)?))
+    }
+
+    /// Create an empty workspace in `tasks_folder` and open it.
+    pub fn init(tasks_folder: PathBuf) -> Result<Self> {
+        Ok(Self::from_repository(TaskRepository::init(tasks_folder)?))
+    }
+
+    pub fn from_repository(repo: TaskRepository) -> Self {
+        Self { repo: Mutex::new(repo) }
+    }
+
+    pub fn into_repository(self) -> TaskRepository {
+        self.repo.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Run `f` with the underlying repository, for operations the facade doesn't offer.
+    ///
+    /// ```
+    /// # use onyx_core::service::TaskService;
+    /// # let dir = tempfile::TempDir::new()?;
+    /// # let service = TaskService::init(dir.path().to_path_buf())?;
+    /// let work = service.create_list("Work")?;
+    /// service.with_repository(|repo| repo.create_sublist(work.id, "Q3".to_string()))?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_repository<R>(&self, f: impl FnOnce(&mut TaskRepository) -> R) -> R {
+        f(&mut self.lock())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TaskRepository> {
+        // A panic mid-operation can't leave the repository itself inconsistent; every write
+        // goes straight to disk
+        self.repo.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Create a top-level list. Names must be unique, ignoring case.
+    pub fn create_list(&self, name: &str) -> Result<ListOverview> {
+        let list = self.lock().create_list(name.to_string())?;
+        Ok(ListOverview::new(&list, Utc::now()))
+    }
+
+    /// Add a task at the end of `list`, given by title or `@N` position.
+    pub fn add_task(&self, list: &str, title: &str, options: NewTask) -> Result<TaskSummary> {
+        let mut repo = self.lock();
+        let list = repo.resolve_list(list)?;
+        let mut task = Task::new(title.to_string()).with_description(options.description);
+        task.due_date = options.due_date;
+        task.has_time = options.due_date.is_some() && options.has_time;
+        task.priority = options.priority;
+        task.tags = options.tags;
+        task.assignee = options.assignee;
+        let task = repo.create_task(list.id, task)?;
+        Ok(TaskSummary::new(&list, &task))
+    }
+
+    /// Tasks of `list` (title or `@N`) in list order.
+    pub fn list_tasks(&self, list: &str) -> Result<Vec<TaskSummary>> {
+        let list = self.lock().resolve_list(list)?;
+        Ok(list.tasks.iter().map(|task| TaskSummary::new(&list, task)).collect())
+    }
+
+    /// The task whose ID is or starts with `prefix`, in any list.
+    pub fn find_by_prefix(&self, prefix: &str) -> Result<TaskSummary> {
+        let lists = self.lock().get_lists()?;
+        let (list, task) = find_unique(&lists, prefix)?;
+        Ok(TaskSummary::new(list, task))
+    }
+
+    /// Mark the task whose ID is or starts with `prefix` completed. Completing a task that
+    /// already is leaves it untouched.
+    pub fn complete_by_prefix(&self, prefix: &str) -> Result<TaskSummary> {
+        let mut repo = self.lock();
+        let lists = repo.get_lists()?;
+        let (list, task) = find_unique(&lists, prefix)?;
+        let mut task = task.clone();
+        if task.status != TaskStatus::Completed {
+            task.complete();
+            repo.update_task(list.id, task.clone())?;
+        }
+        Ok(TaskSummary::new(list, &task))
+    }
+
+    /// Every list in workspace order (sublists after their parent) with its task counts.
+    pub fn lists_overview(&self) -> Result<Vec<ListOverview>> {
+        let now = Utc::now();
+        Ok(self.lock().get_lists()?.iter().map(|list| ListOverview::new(list, now)).collect())
+    }
+}
+
+/// The one task across `lists` whose ID starts with `prefix` (case-insensitive).
+fn find_unique<'a>(lists: &'a [TaskList], prefix: &str) -> Result<(&'a TaskList, &'a Task)> {
+    let matches = tasks_with_id_prefix(lists, prefix)?;
+    match matches.as_slice() {
+        [] => Err(Error::TaskNotFound(prefix.trim().to_lowercase())),
+        [only] => Ok(*only),
+        _ => {
+            let listed: Vec<String> = matches.iter()
+                .take(MAX_PREFIX_MATCHES)
+                .map(|(list, task)| format!("{} {} ({})", task.id, task.title, list.title))
+                .collect();
+            Err(Error::InvalidData(format!(
+                "ID prefix '{}' matches {} tasks: {}{}",
+                prefix.trim().to_lowercase(),
+                matches.len(),
+                listed.join("; "),
+                if matches.len() > MAX_PREFIX_MATCHES { "; ..." } else { "" }
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, TaskService) {
+        let temp_dir = TempDir::new().unwrap();
+        let service = TaskService::init(temp_dir.path().to_path_buf()).unwrap();
+        service.create_list("My Tasks").unwrap();
+        (temp_dir, service)
+    }
+
+    #[test]
+    fn test_add_and_list_by_name() {
+        let (_dir, service) = setup();
+        let work = service.create_list("Work").unwrap();
+        assert_eq!((work.title.as_str(), work.open), ("Work", 0));
+        let added = service.add_task("Work", "Write report", NewTask {
+            priority: Some(Priority::High),
+            tags: vec!["q3".to_string()],
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(added.list, "Work");
+        assert!(!added.completed);
+
+        let tasks = service.list_tasks("Work").unwrap();
+        assert_eq!(tasks, vec![added]);
+        assert!(service.add_task("Nope", "x", NewTask::default()).unwrap_err().is_not_found());
+    }
+
+    #[test]
+    fn test_complete_by_prefix() {
+        let (_dir, service) = setup();
+        let task = service.add_task("@0", "Buy milk", NewTask::default()).unwrap();
+        let prefix = task.id.to_string()[..8].to_uppercase();
+
+        let done = service.complete_by_prefix(&prefix).unwrap();
+        assert!(done.completed);
+        assert!(service.find_by_prefix(&prefix).unwrap().completed);
+        // Already complete: still fine
+        assert!(service.complete_by_prefix(&task.id.to_string()).unwrap().completed);
+
+        assert!(service.complete_by_prefix("zzz").unwrap_err().is_invalid_data());
+        let missing = if task.id.to_string().starts_with('0') { "f" } else { "0" };
+        assert!(service.complete_by_prefix(&format!("{}{}", missing, &prefix[1..]))
+            .unwrap_err().is_not_found());
+    }
+
+    #[test]
+    fn test_ambiguous_prefix_lists_matches() {
+        let (_dir, service) = setup();
+        service.with_repository(|repo| {
+            let list = repo.resolve_list("My Tasks")?;
+            for (id, title) in [("aaaa1111-0000-4000-8000-000000000001", "A"), ("aaaa2222-0000-4000-8000-000000000002", "B")] {
+                let mut task = Task::new(title.to_string());
+                task.id = Uuid::parse_str(id).unwrap();
+                repo.create_task(list.id, task)?;
+            }
+            Ok::<_, Error>(())
+        }).unwrap();
+
+        let err = service.complete_by_prefix("aaaa").unwrap_err();
+        assert!(err.is_invalid_data());
+        assert!(err.to_string().contains("matches 2 tasks"));
+        assert_eq!(service.complete_by_prefix("aaaa2").unwrap().title, "B");
+        assert!(!service.find_by_prefix("aaaa1").unwrap().completed);
+    }
+
+    #[test]
+    fn test_add_task_refuses_tags_outside_a_strict_vocabulary() {
+        let (_dir, service) = setup();
+        service.with_repository(|repo| repo.set_workspace_settings(&crate::config::WorkspaceSettings {
+            allowed_tags: vec!["work".to_string()],
+            strict_tags: Some(true),
+            ..Default::default()
+        })).unwrap();
+
+        let tagged = |tag: &str| NewTask { tags: vec![tag.to_string()], ..Default::default() };
+        assert!(service.add_task("My Tasks", "Report", tagged("wrk")).unwrap_err().is_invalid_data());
+        assert!(service.add_task("My Tasks", "Report", tagged("work")).is_ok());
+    }
+
+    #[test]
+    fn test_lists_overview_counts() {
+        let (_dir, service) = setup();
+        service.add_task("My Tasks", "Open", NewTask::default()).unwrap();
+        service.add_task("My Tasks", "Late", NewTask {
+            due_date: Some(Utc::now() - chrono::Duration::days(1)),
+            ..Default::default()
+        }).unwrap();
+        let done = service.add_task("My Tasks", "Done", NewTask::default()).unwrap();
+        service.complete_by_prefix(&done.id.to_string()).unwrap();
+
+        let overview = service.lists_overview().unwrap();
+        assert_eq!(overview.len(), 1);
+        assert_eq!((overview[0].open, overview[0].completed, overview[0].overdue), (2, 1, 1));
+    }
+}
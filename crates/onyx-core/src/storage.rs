@@ -1,11 +1,18 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use crate::config::{FilenameScheme, FrontmatterFormat, WorkspaceSettings};
 use crate::error::{Error, Result};
-use crate::models::{Task, TaskList, TaskStatus};
+use crate::models::{Priority, Task, TaskList, TaskStatus};
+use crate::search::{IndexedFile, SearchIndex};
+
+/// Deepest nesting allowed for lists: top-level lists are depth 0, so with 2 a list can
+/// hold sublists that hold sublists of their own.
+pub const MAX_LIST_DEPTH: usize = 2;
 
 /// Metadata stored in root .metadata.json
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,14 +21,32 @@ pub struct RootMetadata {
     pub list_order: Vec<Uuid>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_opened_list: Option<Uuid>,
+    /// List that `onyx inbox` and `add --inbox` capture into, whichever list is current.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inbox_list: Option<Uuid>,
+    /// When the workspace was created. Workspaces from before version 3 get the metadata
+    /// file's modification time.
+    pub created_at: DateTime<Utc>,
+    /// Last write to this file (list order, last-opened list, ...); not bumped by task edits.
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RootMetadata {
+    /// Version 2 added nested lists (`parent_list` in `.listdata.json`); version 3 added
+    /// `created_at` and `updated_at`.
+    pub const CURRENT_VERSION: u32 = 3;
 }
 
 impl Default for RootMetadata {
     fn default() -> Self {
+        let now = Utc::now();
         Self {
-            version: 1,
+            version: Self::CURRENT_VERSION,
             list_order: Vec::new(),
             last_opened_list: None,
+            inbox_list: None,
+            created_at: now,
+            updated_at: now,
         }
     }
 }
@@ -34,6 +59,9 @@ pub struct ListMetadata {
     pub updated_at: DateTime<Utc>,
     pub group_by_due_date: bool,
     pub task_order: Vec<Uuid>,
+    /// Parent list for a sublist; `None` for top-level lists and lists from version 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_list: Option<Uuid>,
 }
 
 impl ListMetadata {
@@ -45,23 +73,71 @@ impl ListMetadata {
             updated_at: now,
             group_by_due_date: false,
             task_order: Vec::new(),
+            parent_list: None,
         }
     }
 }
 
-/// Frontmatter for task markdown files
+/// Frontmatter for task markdown files. Fields are written in declaration order, so
+/// this order is part of the file format: keep it stable to avoid churn in git and sync,
+/// and add new fields at the end.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskFrontmatter {
     pub id: Uuid,
     pub status: TaskStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub due: Option<DateTime<Utc>>,
     #[serde(default)]
     pub has_time: bool,
     pub created: DateTime<Utc>,
     pub updated: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<Uuid>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reminders: Vec<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reminders_sent: Vec<DateTime<Utc>>,
+    /// Only written when the filename doesn't spell out the title (see `FilenameScheme`);
+    /// otherwise the title is the file stem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty", deserialize_with = "deserialize_fields")]
+    pub fields: BTreeMap<String, String>,
+    /// Unknown keys, re-emitted after the known ones (sorted, so output stays stable).
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+/// Read a task's `fields` map, taking numbers and booleans as their text so a hand-written
+/// `issue: 42` doesn't make the whole file unreadable.
+fn deserialize_fields<'de, D>(deserializer: D) -> std::result::Result<BTreeMap<String, String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    BTreeMap::<String, serde_yaml::Value>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                serde_yaml::Value::String(s) => s,
+                serde_yaml::Value::Number(n) => n.to_string(),
+                serde_yaml::Value::Bool(b) => b.to_string(),
+                _ => return Err(serde::de::Error::custom(format!("field \"{}\" must be a string, number or boolean", key))),
+            };
+            Ok((key, value))
+        })
+        .collect()
 }
 
 impl From<&Task> for TaskFrontmatter {
@@ -69,23 +145,347 @@ impl From<&Task> for TaskFrontmatter {
         Self {
             id: task.id,
             status: task.status,
+            priority: task.priority,
             due: task.due_date,
             has_time: task.has_time,
             created: task.created_at,
             updated: task.updated_at,
+            completed: task.completed_at,
             parent: task.parent_id,
+            depends_on: task.depends_on.clone(),
+            tags: task.tags.clone(),
+            assignee: task.assignee.clone(),
+            reminders: task.reminders.clone(),
+            reminders_sent: task.reminders_sent.clone(),
+            title: None,
+            label: task.label.clone(),
+            fields: task.fields.clone(),
+            extra: task.extra_fields.clone(),
+        }
+    }
+}
+
+/// Build the in-memory list for a list's stored metadata, so every path that hands out a
+/// `TaskList` (create, read, adopt) reports the same fields.
+fn list_from_metadata(metadata: &ListMetadata, title: String, tasks: Vec<Task>, parent_id: Option<Uuid>, depth: usize) -> TaskList {
+    let mut list = TaskList {
+        id: metadata.id,
+        title,
+        tasks,
+        created_at: metadata.created_at,
+        updated_at: metadata.updated_at,
+        group_by_due_date: metadata.group_by_due_date,
+        parent_id,
+        depth,
+        next_due: None,
+    };
+    list.refresh_next_due();
+    list
+}
+
+/// Split a task file into its parsed frontmatter and trimmed markdown body. The
+/// frontmatter is YAML between `---` fences or TOML between `+++` fences. A leading
+/// UTF-8 BOM is skipped and CRLF (or lone CR) line endings are read as LF, as files
+/// saved by Windows editors often have them.
+fn parse_frontmatter(content: &str) -> Result<(TaskFrontmatter, String)> {
+    #[cfg(test)]
+    FRONTMATTER_PARSES.with(|count| count.set(count.get() + 1));
+    let content = normalize_line_endings(content);
+    let lines: Vec<&str> = content.lines().collect();
+    let (frontmatter, end) = first_frontmatter_block(&lines, parse_frontmatter_block)?;
+    let description = lines[end + 1..].join("\n");
+    Ok((frontmatter, description.trim().to_string()))
+}
+
+/// `content` without a leading BOM and with every line ending as LF.
+fn normalize_line_endings(content: &str) -> String {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    content.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// The frontmatter at the top of `lines`, read with `parse`, and the index of its closing
+/// fence line.
+fn first_frontmatter_block<T>(
+    lines: &[&str],
+    parse: impl Fn(&str, FrontmatterFormat) -> Result<T>,
+) -> Result<(T, usize)> {
+    let format = match lines.first().map(|line| line.trim_end()) {
+        Some("---") => FrontmatterFormat::Yaml,
+        Some("+++") => FrontmatterFormat::Toml,
+        _ => return Err(Error::InvalidData("Missing frontmatter delimiter".to_string())),
+    };
+    let fence = lines[0].trim_end();
+
+    // The closing fence is a line of its own. A fence line can also appear inside the
+    // frontmatter (in a TOML multi-line string) or the body (a markdown rule), so take the
+    // first one that ends a frontmatter block that parses
+    let mut first_error = None;
+    for end in (1..lines.len()).filter(|&i| lines[i].trim_end() == fence) {
+        match parse(&lines[1..end].join("\n"), format) {
+            Ok(frontmatter) => return Ok((frontmatter, end)),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    Err(first_error.unwrap_or_else(|| {
+        Error::InvalidData("Missing closing frontmatter delimiter".to_string())
+    }))
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Frontmatter blocks fully parsed on this thread, so tests can check that lookups by
+    /// ID leave the other task files alone.
+    pub(crate) static FRONTMATTER_PARSES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// The one frontmatter key needed to match a task file by ID.
+#[derive(Deserialize)]
+struct FrontmatterId {
+    id: Uuid,
+}
+
+/// The task ID of a task file, read from its frontmatter alone, for loops that only compare
+/// IDs. Usually just the `id` line is looked at; otherwise only the frontmatter block is
+/// parsed, never the description. Gives the same ID as [`parse_task_file`].
+pub fn parse_frontmatter_only(content: &str) -> Result<Uuid> {
+    if let Some(id) = frontmatter_id(content) {
+        return Ok(id);
+    }
+    let content = normalize_line_endings(content);
+    let lines: Vec<&str> = content.lines().collect();
+    let (frontmatter, _) = first_frontmatter_block(&lines, |block, format| Ok(match format {
+        FrontmatterFormat::Yaml => serde_yaml::from_str::<FrontmatterId>(block)?,
+        FrontmatterFormat::Toml => toml::from_str::<FrontmatterId>(block)?,
+    }))?;
+    Ok(frontmatter.id)
+}
+
+/// The task ID from the top-level `id` line of a task file's frontmatter, without parsing
+/// the rest of it. `None` when no such line is found before the closing fence (or a TOML
+/// table header), in which case the file has to be parsed in full.
+fn frontmatter_id(content: &str) -> Option<Uuid> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut lines = content.lines();
+    let fence = lines.next()?.trim_end();
+    let separator = match fence {
+        "---" => ':',
+        "+++" => '=',
+        _ => return None,
+    };
+    for line in lines {
+        let line = line.trim_end();
+        if line == fence || line.starts_with('[') {
+            return None;
+        }
+        let Some(value) = line.strip_prefix("id").and_then(|rest| rest.trim_start().strip_prefix(separator)) else {
+            continue;
+        };
+        return Uuid::parse_str(value.trim().trim_matches(['"', '\''])).ok();
+    }
+    None
+}
+
+fn parse_frontmatter_block(block: &str, format: FrontmatterFormat) -> Result<TaskFrontmatter> {
+    Ok(match format {
+        FrontmatterFormat::Yaml => serde_yaml::from_str(block)?,
+        FrontmatterFormat::Toml => {
+            let table: toml::Table = toml::from_str(block)?;
+            serde_yaml::from_value(toml_to_yaml(toml::Value::Table(table)))?
+        }
+    })
+}
+
+/// Convert parsed TOML into the YAML value model `TaskFrontmatter` deserializes from.
+/// TOML datetimes become RFC 3339 strings, which is how they appear in YAML frontmatter.
+fn toml_to_yaml(value: toml::Value) -> serde_yaml::Value {
+    match value {
+        toml::Value::String(s) => serde_yaml::Value::String(s),
+        toml::Value::Integer(i) => serde_yaml::Value::Number(i.into()),
+        toml::Value::Float(f) => serde_yaml::Value::Number(f.into()),
+        toml::Value::Boolean(b) => serde_yaml::Value::Bool(b),
+        toml::Value::Datetime(dt) => serde_yaml::Value::String(dt.to_string()),
+        toml::Value::Array(items) => {
+            serde_yaml::Value::Sequence(items.into_iter().map(toml_to_yaml).collect())
+        }
+        toml::Value::Table(table) => serde_yaml::Value::Mapping(
+            table.into_iter()
+                .map(|(k, v)| (serde_yaml::Value::String(k), toml_to_yaml(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// Frontmatter keys holding timestamps, written as native TOML datetimes. Other strings,
+/// such as the values of unknown keys, stay strings even when they look like a date.
+const TOML_DATETIME_KEYS: [&str; 6] = ["due", "created", "updated", "completed", "reminders", "reminders_sent"];
+
+/// Convert serialized frontmatter to TOML. TOML has no null, so nulls are dropped.
+fn yaml_to_toml(value: serde_yaml::Value) -> Option<toml::Value> {
+    match value {
+        serde_yaml::Value::Null => None,
+        serde_yaml::Value::Bool(b) => Some(toml::Value::Boolean(b)),
+        serde_yaml::Value::Number(n) => match n.as_i64() {
+            Some(i) => Some(toml::Value::Integer(i)),
+            None => n.as_f64().map(toml::Value::Float),
+        },
+        serde_yaml::Value::String(s) => Some(toml::Value::String(s)),
+        serde_yaml::Value::Sequence(items) => {
+            Some(toml::Value::Array(items.into_iter().filter_map(yaml_to_toml).collect()))
+        }
+        serde_yaml::Value::Mapping(mapping) => Some(toml::Value::Table(
+            mapping.into_iter()
+                .filter_map(|(k, v)| {
+                    let key = match k {
+                        serde_yaml::Value::String(s) => s,
+                        other => serde_yaml::to_string(&other).ok()?.trim_end().to_string(),
+                    };
+                    Some((key, yaml_to_toml(v)?))
+                })
+                .collect(),
+        )),
+        serde_yaml::Value::Tagged(tagged) => yaml_to_toml(tagged.value),
+    }
+}
+
+/// `value` with its RFC 3339 strings (alone or in an array) as native TOML datetimes.
+fn toml_datetimes(value: &mut toml::Value) {
+    match value {
+        toml::Value::String(s) if DateTime::parse_from_rfc3339(s).is_ok() => {
+            if let Ok(dt) = s.parse::<toml::value::Datetime>() {
+                *value = toml::Value::Datetime(dt);
+            }
+        }
+        toml::Value::Array(items) => items.iter_mut().for_each(toml_datetimes),
+        _ => {}
+    }
+}
+
+/// Parse the contents of a task markdown file. The title is usually not stored in the
+/// frontmatter, so it must be supplied (normally the file stem); a `title` key wins.
+pub fn parse_task_file(content: &str, title: &str) -> Result<Task> {
+    let (frontmatter, description) = parse_frontmatter(content)?;
+    Ok(Task {
+        id: frontmatter.id,
+        title: frontmatter.title.unwrap_or_else(|| title.to_string()),
+        description,
+        status: frontmatter.status,
+        due_date: frontmatter.due,
+        has_time: frontmatter.has_time,
+        created_at: frontmatter.created,
+        updated_at: frontmatter.updated,
+        completed_at: frontmatter.completed,
+        parent_id: frontmatter.parent,
+        depends_on: frontmatter.depends_on,
+        priority: frontmatter.priority,
+        tags: frontmatter.tags,
+        assignee: frontmatter.assignee,
+        label: frontmatter.label,
+        fields: frontmatter.fields,
+        reminders: frontmatter.reminders,
+        reminders_sent: frontmatter.reminders_sent,
+        extra_fields: frontmatter.extra,
+    })
+}
+
+/// Serialize a task to markdown with YAML frontmatter, as written to disk.
+pub fn serialize_task(task: &Task) -> Result<String> {
+    serialize_task_as(task, FrontmatterFormat::Yaml)
+}
+
+/// Serialize a task to markdown with frontmatter in the given syntax.
+pub fn serialize_task_as(task: &Task, format: FrontmatterFormat) -> Result<String> {
+    serialize_frontmatter(&TaskFrontmatter::from(task), &task.description, format)
+}
+
+fn serialize_frontmatter(frontmatter: &TaskFrontmatter, description: &str, format: FrontmatterFormat) -> Result<String> {
+    let (fence, body) = match format {
+        FrontmatterFormat::Yaml => ("---", serde_yaml::to_string(frontmatter)?),
+        FrontmatterFormat::Toml => {
+            let mut value = yaml_to_toml(serde_yaml::to_value(frontmatter)?)
+                .ok_or_else(|| Error::Serialization("Empty task frontmatter".to_string()))?;
+            if let toml::Value::Table(table) = &mut value {
+                for key in TOML_DATETIME_KEYS {
+                    if let Some(field) = table.get_mut(key) {
+                        toml_datetimes(field);
+                    }
+                }
+            }
+            ("+++", toml::to_string(&value)?)
         }
+    };
+
+    let mut content = String::new();
+    content.push_str(fence);
+    content.push('\n');
+    content.push_str(&body);
+    content.push_str(fence);
+    content.push_str("\n\n");
+    content.push_str(description);
+
+    Ok(content)
+}
+
+/// `task` with `existing`'s `updated_at` (and `completed_at`, if it is still completed)
+/// when the two differ in nothing else, so a no-op save leaves the stored task as is.
+/// `None` if something besides those timestamps changed.
+fn with_stored_timestamps(task: &Task, existing: &Task) -> Result<Option<Task>> {
+    // The title lives in the filename, so it isn't covered by the comparison below
+    if task.title != existing.title {
+        return Ok(None);
+    }
+    let mut unchanged = task.clone();
+    unchanged.updated_at = existing.updated_at;
+    if unchanged.status == existing.status {
+        unchanged.completed_at = existing.completed_at;
     }
+    if serialize_task(&unchanged)? == serialize_task(existing)? {
+        Ok(Some(unchanged))
+    } else {
+        Ok(None)
+    }
+}
+
+/// A task file whose name doesn't follow the workspace's `filename_scheme`. Paths are
+/// relative to the workspace root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskFileRename {
+    pub task_id: Uuid,
+    pub from: PathBuf,
+    pub to: PathBuf,
+    /// Another file already has the expected name, so this one was left alone.
+    pub blocked: bool,
 }
 
 pub trait Storage {
     fn read_task(&self, list_id: Uuid, task_id: Uuid) -> Result<Task>;
+    /// ID of the list holding `task_id`, found from the task files' `id` lines; only files
+    /// without a readable one are parsed in full.
+    fn find_task_list(&self, task_id: Uuid) -> Result<Uuid>;
+    /// Raw stored representation of a task (markdown with frontmatter).
+    fn read_task_source(&self, list_id: Uuid, task_id: Uuid) -> Result<String>;
     fn write_task(&mut self, list_id: Uuid, task: &Task) -> Result<()>;
     fn delete_task(&mut self, list_id: Uuid, task_id: Uuid) -> Result<()>;
+    /// Move a task's stored file to another list as-is, appending it to that list's
+    /// `task_order`. Unlike a write and a delete, nothing is reparsed, so fields onyx
+    /// doesn't know and the `updated` timestamp stay exactly as they were.
+    fn move_task(&mut self, from_list_id: Uuid, to_list_id: Uuid, task_id: Uuid) -> Result<()>;
     fn list_tasks(&self, list_id: Uuid) -> Result<Vec<Task>>;
+    /// The tasks of a list in the same order as `list_tasks`, each read from disk only
+    /// when the iterator reaches it.
+    fn iter_list_tasks(&self, list_id: Uuid) -> Result<Box<dyn Iterator<Item = Result<Task>> + '_>>;
 
     fn create_list(&mut self, name: String) -> Result<TaskList>;
+    /// Create a list nested inside `parent_id`'s directory.
+    fn create_sublist(&mut self, parent_id: Uuid, name: String) -> Result<TaskList>;
     fn get_lists(&self) -> Result<Vec<TaskList>>;
+    /// The lists `get_lists` returns, in the same order, without reading their tasks
+    /// (`tasks` is left empty).
+    fn get_list_headers(&self) -> Result<Vec<TaskList>>;
+    /// Like `get_lists`, but hand-made folders are left alone instead of being adopted, so
+    /// nothing is written.
+    fn get_registered_lists(&self) -> Result<Vec<TaskList>>;
     fn delete_list(&mut self, list_id: Uuid) -> Result<()>;
 
     fn read_root_metadata(&self) -> Result<RootMetadata>;
@@ -95,11 +495,60 @@ pub trait Storage {
 
     fn read_list_metadata(&self, list_id: Uuid) -> Result<ListMetadata>;
     fn write_list_metadata(&mut self, metadata: &ListMetadata) -> Result<()>;
+
+    fn read_workspace_settings(&self) -> Result<WorkspaceSettings>;
+    fn write_workspace_settings(&mut self, settings: &WorkspaceSettings) -> Result<()>;
+    /// Fall back to `defaults` for what the workspace settings leave unset when writing
+    /// task files.
+    fn set_settings_defaults(&mut self, defaults: WorkspaceSettings);
+
+    /// Names of non-hidden directories in the workspace root that have no `.listdata.json`.
+    fn unregistered_list_dirs(&self) -> Result<Vec<String>>;
+    /// The `unregistered_list_dirs` that `get_lists` would adopt, found without writing
+    /// anything.
+    fn adoptable_list_dirs(&self) -> Result<Vec<String>>;
+    /// Register a folder without `.listdata.json` as a list, even if it holds no tasks yet.
+    fn adopt_list(&mut self, name: &str) -> Result<TaskList>;
+    /// Task files named by another filename scheme than the current one. Unless `dry_run`,
+    /// each is rewritten under its expected name, except the `blocked` ones.
+    fn rename_task_files(&mut self, dry_run: bool) -> Result<Vec<TaskFileRename>>;
+
+    /// The tasks whose title or description may contain `text`, each with its list ID, in
+    /// list and task order, read with the help of the search index (see
+    /// [`SearchIndex::candidates`]). Task files modified since they were indexed are
+    /// indexed again first. `None` when there is no index or it can't narrow `text` down;
+    /// callers then scan every task.
+    fn search_candidates(&self, text: &str) -> Result<Option<Vec<(Uuid, Task)>>>;
+    /// Build the search index afresh from every task file, returning how many were indexed.
+    fn rebuild_search_index(&mut self) -> Result<usize>;
 }
 
 #[derive(Debug)]
 pub struct FileSystemStorage {
     root_path: PathBuf,
+    /// Global defaults (`AppConfig::defaults`) for what the workspace settings leave unset.
+    settings_defaults: WorkspaceSettings,
+    /// Syntax for task files written from now on, from the workspace settings.
+    frontmatter_format: FrontmatterFormat,
+    /// Naming for task files written from now on, from the workspace settings.
+    filename_scheme: FilenameScheme,
+}
+
+/// A task file found on disk, as compared against the search index.
+struct TaskFile {
+    list_id: Uuid,
+    path: PathBuf,
+    modified: DateTime<Utc>,
+    size: u64,
+}
+
+/// A list directory found on disk. The parent comes from the directory nesting, which
+/// wins over `parent_list` in the metadata if the two disagree.
+struct ListDir {
+    path: PathBuf,
+    metadata: ListMetadata,
+    parent: Option<Uuid>,
+    depth: usize,
 }
 
 impl FileSystemStorage {
@@ -107,65 +556,142 @@ impl FileSystemStorage {
         if !root_path.exists() {
             return Err(Error::NotFound(format!("Path does not exist: {:?}", root_path)));
         }
-        Ok(Self { root_path })
+        let storage = Self::with_file_settings(root_path);
+        storage.migrate()?;
+        Ok(storage)
     }
 
     pub fn init(root_path: PathBuf) -> Result<Self> {
         fs::create_dir_all(&root_path)?;
 
-        let storage = Self { root_path };
+        let storage = Self::with_file_settings(root_path);
 
         // Create default metadata if it doesn't exist
         if !storage.metadata_path().exists() {
-            storage.write_root_metadata_internal(&RootMetadata::default())?;
+            storage.save_root_metadata(&RootMetadata::default())?;
         }
+        storage.migrate()?;
 
         Ok(storage)
     }
 
+    /// Storage that writes task files the way the workspace settings at `root_path` ask.
+    /// Unreadable settings fall back to the defaults here; they are reported when the
+    /// settings are read for anything else.
+    fn with_file_settings(root_path: PathBuf) -> Self {
+        let mut storage = Self {
+            root_path,
+            settings_defaults: WorkspaceSettings::default(),
+            frontmatter_format: FrontmatterFormat::default(),
+            filename_scheme: FilenameScheme::default(),
+        };
+        storage.reload_file_settings();
+        storage
+    }
+
+    /// Re-read the workspace settings that decide how task files are written.
+    fn reload_file_settings(&mut self) {
+        let settings = WorkspaceSettings::load(&self.root_path).unwrap_or_default();
+        self.apply_file_settings(&settings);
+    }
+
+    /// Write task files as `settings`, merged over the global defaults, ask.
+    fn apply_file_settings(&mut self, settings: &WorkspaceSettings) {
+        let settings = settings.merged_over(&self.settings_defaults);
+        self.frontmatter_format = settings.frontmatter_format.unwrap_or_default();
+        self.filename_scheme = settings.filename_scheme.unwrap_or_default();
+    }
+
+    /// Bring workspaces written by older versions up to `RootMetadata::CURRENT_VERSION`.
+    /// Version 1 had no nested lists, so every existing list is marked top-level.
+    fn migrate(&self) -> Result<()> {
+        if !self.metadata_path().exists() {
+            return Ok(());
+        }
+        let mut root = self.read_root_metadata_internal()?;
+        if root.version >= RootMetadata::CURRENT_VERSION {
+            return Ok(());
+        }
+        if root.version < 2 {
+            for entry in fs::read_dir(&self.root_path)? {
+                let listdata_path = entry?.path().join(".listdata.json");
+                if !listdata_path.exists() {
+                    continue;
+                }
+                let mut metadata: ListMetadata = serde_json::from_str(&fs::read_to_string(&listdata_path)?)?;
+                if metadata.parent_list.take().is_some() {
+                    fs::write(&listdata_path, serde_json::to_string_pretty(&metadata)?)?;
+                }
+            }
+        }
+        // Version 3's timestamps were already filled in from the file's mtime when reading;
+        // save them without bumping `updated_at`, which would erase that
+        root.version = RootMetadata::CURRENT_VERSION;
+        self.save_root_metadata(&root)
+    }
+
     fn metadata_path(&self) -> PathBuf {
         self.root_path.join(".metadata.json")
     }
 
-    fn list_dir_path(&self, list_id: Uuid) -> Result<PathBuf> {
-        // Find the directory with this list ID
-        let entries = fs::read_dir(&self.root_path)?;
+    /// Every list directory in the workspace, parents before their sublists.
+    fn list_dirs(&self) -> Result<Vec<ListDir>> {
+        let mut dirs = Vec::new();
+        self.collect_list_dirs(&self.root_path, None, 0, &mut dirs)?;
+        Ok(dirs)
+    }
 
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_dir() {
-                let listdata_path = path.join(".listdata.json");
-                if listdata_path.exists() {
-                    let content = fs::read_to_string(&listdata_path)?;
-                    let list_metadata: ListMetadata = serde_json::from_str(&content)?;
-                    if list_metadata.id == list_id {
-                        return Ok(path);
-                    }
-                }
+    fn collect_list_dirs(&self, dir: &Path, parent: Option<Uuid>, depth: usize, dirs: &mut Vec<ListDir>) -> Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let listdata_path = path.join(".listdata.json");
+            if !path.is_dir() || !listdata_path.exists() {
+                continue;
+            }
+            let content = fs::read_to_string(&listdata_path)?;
+            let metadata: ListMetadata = serde_json::from_str(&content)?;
+            let id = metadata.id;
+            dirs.push(ListDir { path: path.clone(), metadata, parent, depth });
+            if depth < MAX_LIST_DEPTH {
+                self.collect_list_dirs(&path, Some(id), depth + 1, dirs)?;
             }
         }
+        Ok(())
+    }
 
-        Err(Error::ListNotFound(list_id.to_string()))
+    fn find_list_dir(&self, list_id: Uuid) -> Result<ListDir> {
+        self.list_dirs()?
+            .into_iter()
+            .find(|dir| dir.metadata.id == list_id)
+            .ok_or_else(|| Error::ListNotFound(list_id.to_string()))
+    }
+
+    fn list_dir_path(&self, list_id: Uuid) -> Result<PathBuf> {
+        Ok(self.find_list_dir(list_id)?.path)
     }
 
     fn list_dir_path_by_name(&self, name: &str) -> Result<PathBuf> {
+        self.child_dir_path(&self.root_path, name)
+    }
+
+    /// Path for a list directory called `name` inside `base` (the root or a parent list).
+    fn child_dir_path(&self, base: &Path, name: &str) -> Result<PathBuf> {
         // Reject names containing path separators or traversal components
         if name.contains('/') || name.contains('\\') || name == ".." || name.starts_with("../") || name.starts_with("..\\") {
-            return Err(Error::InvalidData("Invalid list name: path traversal not allowed".to_string()));
+            return Err(Error::InvalidPath("Invalid list name: path traversal not allowed".to_string()));
         }
-        let path = self.root_path.join(name);
+        let path = base.join(name);
         // Verify resolved path stays within root
         let canonical_root = self.root_path.canonicalize()
             .map_err(Error::Io)?;
         let canonical_path = if path.exists() {
             path.canonicalize().map_err(Error::Io)?
         } else {
-            // Parent must exist and be canonicalizable (it's root_path)
-            canonical_root.join(path.file_name().unwrap_or_default())
+            // Parent must exist and be canonicalizable (root_path or a list directory)
+            base.canonicalize().map_err(Error::Io)?.join(path.file_name().unwrap_or_default())
         };
         if !canonical_path.starts_with(&canonical_root) {
-            return Err(Error::InvalidData("Invalid list name: path escapes workspace".to_string()));
+            return Err(Error::InvalidPath("Invalid list name: path escapes workspace".to_string()));
         }
         Ok(path)
     }
@@ -182,53 +708,79 @@ impl FileSystemStorage {
             .to_string()
     }
 
-    fn task_file_path(&self, list_dir: &Path, task: &Task) -> PathBuf {
-        let safe_title = Self::sanitize_filename(&task.title);
-        let filename = if safe_title.is_empty() {
-            task.id.to_string()
-        } else {
-            safe_title
-        };
-        list_dir.join(format!("{}.md", filename))
+    /// Lowercase ASCII letters and digits of `title`, other runs of characters collapsed
+    /// into single dashes, cut to a length that keeps paths short.
+    fn slugify(title: &str) -> String {
+        const MAX_SLUG_LEN: usize = 40;
+        let mut slug = String::new();
+        for c in title.chars() {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c.to_ascii_lowercase());
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        slug.truncate(MAX_SLUG_LEN);
+        slug.trim_end_matches('-').to_string()
     }
 
-    fn parse_markdown_with_frontmatter(&self, content: &str) -> Result<(TaskFrontmatter, String)> {
-        let lines: Vec<&str> = content.lines().collect();
-
-        if lines.is_empty() || lines[0] != "---" {
-            return Err(Error::InvalidData("Missing frontmatter delimiter".to_string()));
+    /// File name without `.md` for `task` under the workspace's `filename_scheme`.
+    fn task_file_stem(&self, task: &Task) -> String {
+        let id = task.id.to_string();
+        match self.filename_scheme {
+            FilenameScheme::Title => {
+                let safe_title = Self::sanitize_filename(&task.title);
+                if safe_title.is_empty() { id } else { safe_title }
+            }
+            FilenameScheme::Uuid => id,
+            FilenameScheme::SlugUuid => match Self::slugify(&task.title) {
+                slug if slug.is_empty() => id,
+                slug => format!("{}-{}", slug, id),
+            },
         }
+    }
 
-        // Find closing ---
-        let end_idx = lines[1..]
-            .iter()
-            .position(|&line| line == "---")
-            .ok_or_else(|| Error::InvalidData("Missing closing frontmatter delimiter".to_string()))?;
-
-        let frontmatter_lines = &lines[1..=end_idx];
-        let frontmatter_str = frontmatter_lines.join("\n");
-        let frontmatter: TaskFrontmatter = serde_yaml::from_str(&frontmatter_str)?;
-
-        let description = if end_idx + 2 < lines.len() {
-            lines[end_idx + 2..].join("\n")
-        } else {
-            String::new()
-        };
+    fn task_file_path(&self, list_dir: &Path, task: &Task) -> PathBuf {
+        list_dir.join(format!("{}.md", self.task_file_stem(task)))
+    }
 
-        Ok((frontmatter, description.trim().to_string()))
+    fn parse_markdown_with_frontmatter(&self, content: &str) -> Result<(TaskFrontmatter, String)> {
+        parse_frontmatter(content)
     }
 
     fn write_markdown_with_frontmatter(&self, task: &Task) -> Result<String> {
-        let frontmatter = TaskFrontmatter::from(task);
-        let yaml = serde_yaml::to_string(&frontmatter)?;
+        let mut frontmatter = TaskFrontmatter::from(task);
+        // Store the title when reading it back from the file name wouldn't reproduce it
+        if self.task_file_stem(task) != task.title {
+            frontmatter.title = Some(task.title.clone());
+        }
+        serialize_frontmatter(&frontmatter, &task.description, self.frontmatter_format)
+    }
 
-        let mut content = String::new();
-        content.push_str("---\n");
-        content.push_str(&yaml);
-        content.push_str("---\n\n");
-        content.push_str(&task.description);
+    fn read_task_from_path(&self, path: &Path) -> Result<Task> {
+        let content = fs::read_to_string(path)?;
+        let title = path.file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| Error::InvalidPath(format!("Invalid filename: {}", path.display())))?;
+        parse_task_file(&content, title)
+    }
+
+    /// The file in `list_dir` holding `task_id`, if any.
+    fn task_path_in(&self, list_dir: &Path, task_id: Uuid) -> Result<Option<PathBuf>> {
+        for entry in fs::read_dir(list_dir)? {
+            let path = entry?.path();
+            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("md")
+                && parse_frontmatter_only(&fs::read_to_string(&path)?)? == task_id
+            {
+                return Ok(Some(path));
+            }
+        }
+        Ok(None)
+    }
 
-        Ok(content)
+    fn find_task_path(&self, list_id: Uuid, task_id: Uuid) -> Result<PathBuf> {
+        self.task_path_in(&self.list_dir_path(list_id)?, task_id)?
+            .ok_or_else(|| Error::TaskNotFound(task_id.to_string()))
     }
 
     fn read_root_metadata_internal(&self) -> Result<RootMetadata> {
@@ -237,96 +789,295 @@ impl FileSystemStorage {
             return Ok(RootMetadata::default());
         }
         let content = fs::read_to_string(&path)?;
-        let metadata = serde_json::from_str(&content)?;
-        Ok(metadata)
+        let mut value: serde_json::Value = serde_json::from_str(&content)?;
+        // Metadata from before version 3 has no timestamps; the file's mtime is the best guess
+        if let Some(fields) = value.as_object_mut() {
+            if !fields.contains_key("created_at") || !fields.contains_key("updated_at") {
+                let modified = fs::metadata(&path)
+                    .and_then(|m| m.modified())
+                    .map(DateTime::<Utc>::from)
+                    .unwrap_or_else(|_| Utc::now());
+                for key in ["created_at", "updated_at"] {
+                    fields.entry(key).or_insert_with(|| serde_json::json!(modified));
+                }
+            }
+        }
+        Ok(serde_json::from_value(value)?)
     }
 
+    /// Write the root metadata with `updated_at` set to now.
     fn write_root_metadata_internal(&self, metadata: &RootMetadata) -> Result<()> {
-        let path = self.metadata_path();
-        let content = serde_json::to_string_pretty(&metadata)?;
-        fs::write(&path, content)?;
+        self.save_root_metadata(&RootMetadata { updated_at: Utc::now(), ..metadata.clone() })
+    }
+
+    fn save_root_metadata(&self, metadata: &RootMetadata) -> Result<()> {
+        let content = serde_json::to_string_pretty(metadata)?;
+        fs::write(self.metadata_path(), content)?;
         Ok(())
     }
-}
 
-impl Storage for FileSystemStorage {
-    fn read_task(&self, list_id: Uuid, task_id: Uuid) -> Result<Task> {
-        let list_dir = self.list_dir_path(list_id)?;
+    /// Whether a list folder at `dir` would clash with an existing entry other than `own`
+    /// (the folder of the list being renamed). Besides the exact name, that covers a name
+    /// the file system itself maps onto another folder (different case on macOS or
+    /// Windows), and names that differ only in trailing dots or spaces, which Windows
+    /// drops, so a workspace synced there would have the two lists share one folder.
+    fn folder_taken(dir: &Path, own: Option<&Path>) -> Result<bool> {
+        let is_own = |path: &Path| -> Result<bool> {
+            Ok(match own {
+                Some(own) => path == own || path.canonicalize()? == own.canonicalize()?,
+                None => false,
+            })
+        };
+        if dir.exists() && !is_own(dir)? {
+            return Ok(true);
+        }
+        let (Some(base), Some(name)) = (dir.parent(), dir.file_name().and_then(|n| n.to_str())) else {
+            return Ok(false);
+        };
+        let windows_name = |name: &str| name.trim_end_matches(['.', ' ']).to_string();
+        for entry in fs::read_dir(base)? {
+            let path = entry?.path();
+            let Some(existing) = path.file_name().and_then(|n| n.to_str()) else { continue };
+            if existing != name && windows_name(existing) == windows_name(name) && !is_own(&path)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
 
-        // Read all task files in the list directory
-        let entries = fs::read_dir(&list_dir)?;
+    /// Create a list directory at `list_dir` with fresh metadata and append it to `list_order`.
+    fn create_list_at(&mut self, list_dir: PathBuf, name: String, parent: Option<Uuid>, depth: usize) -> Result<TaskList> {
+        if Self::folder_taken(&list_dir, None)? {
+            return Err(Error::ListAlreadyExists(name));
+        }
 
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
+        fs::create_dir_all(&list_dir)?;
+
+        let list_id = Uuid::new_v4();
+        let mut list_metadata = ListMetadata::new(list_id);
+        list_metadata.parent_list = parent;
 
+        let metadata_path = list_dir.join(".listdata.json");
+        let content = serde_json::to_string_pretty(&list_metadata)?;
+        fs::write(&metadata_path, content)?;
+
+        // Add to root metadata
+        let mut root_metadata = self.read_root_metadata_internal()?;
+        root_metadata.list_order.push(list_id);
+        if root_metadata.last_opened_list.is_none() {
+            root_metadata.last_opened_list = Some(list_id);
+        }
+        self.write_root_metadata_internal(&root_metadata)?;
+
+        Ok(list_from_metadata(&list_metadata, name, Vec::new(), parent, depth))
+    }
+
+    /// Give a hand-made folder (no `.listdata.json`) list metadata, with `task_order` taken
+    /// from its task files, oldest first. Every `.md` file must be a parseable task. Unless
+    /// `force` is set, folders with no task files are left alone so unrelated directories
+    /// aren't adopted. Returns the new metadata, or `None` if the folder wasn't adopted.
+    fn adopt_dir(&self, dir: &Path, force: bool) -> Result<Option<ListMetadata>> {
+        let Some(mut tasks) = self.adoptable_tasks(dir, force)? else {
+            return Ok(None);
+        };
+        tasks.sort_by_key(|t| task_sort_key(&HashMap::new(), t.id, t.created_at));
+
+        let mut metadata = ListMetadata::new(Uuid::new_v4());
+        metadata.task_order = tasks.iter().map(|t| t.id).collect();
+        fs::write(dir.join(".listdata.json"), serde_json::to_string_pretty(&metadata)?)?;
+
+        let mut root_metadata = self.read_root_metadata_internal()?;
+        root_metadata.list_order.push(metadata.id);
+        self.write_root_metadata_internal(&root_metadata)?;
+
+        Ok(Some(metadata))
+    }
+
+    /// The tasks `adopt_dir` would adopt `dir` with, or `None` if it would leave the folder
+    /// alone. Only reads.
+    fn adoptable_tasks(&self, dir: &Path, force: bool) -> Result<Option<Vec<Task>>> {
+        let mut tasks = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
             if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("md") {
-                let content = fs::read_to_string(&path)?;
-                let (frontmatter, description) = self.parse_markdown_with_frontmatter(&content)?;
-
-                if frontmatter.id == task_id {
-                    let title = path.file_stem()
-                        .and_then(|s| s.to_str())
-                        .ok_or_else(|| Error::InvalidData("Invalid filename".to_string()))?
-                        .to_string();
-
-                    return Ok(Task {
-                        id: frontmatter.id,
-                        title,
-                        description,
-                        status: frontmatter.status,
-                        due_date: frontmatter.due,
-                        has_time: frontmatter.has_time,
-                        created_at: frontmatter.created,
-                        updated_at: frontmatter.updated,
-                        parent_id: frontmatter.parent,
-                    });
+                match self.read_task_from_path(&path) {
+                    Ok(task) => tasks.push(task),
+                    Err(e) if force => {
+                        return Err(Error::InvalidData(format!(
+                            "Cannot adopt {}: {} is not a task file ({})", dir.display(), path.display(), e
+                        )));
+                    }
+                    Err(_) => return Ok(None),
+                }
+            }
+        }
+        if tasks.is_empty() && !force {
+            return Ok(None);
+        }
+        Ok(Some(tasks))
+    }
+
+    /// The lists with `.listdata.json`, ordered like `get_lists` and without their tasks.
+    fn registered_list_headers(&self) -> Result<Vec<TaskList>> {
+        let root_metadata = self.read_root_metadata_internal()?;
+
+        let mut lists = Vec::new();
+        for dir in self.list_dirs()? {
+            let title = dir.path.file_name()
+                .and_then(|s| s.to_str())
+                .ok_or_else(|| Error::InvalidPath(format!("Invalid directory name: {}", dir.path.display())))?
+                .to_string();
+
+            lists.push(list_from_metadata(&dir.metadata, title, Vec::new(), dir.parent, dir.depth));
+        }
+
+        // Sort by list_order, then place each sublist right after its parent
+        let order_map: HashMap<Uuid, usize> = root_metadata.list_order
+            .iter()
+            .enumerate()
+            .map(|(i, &id)| (id, i))
+            .collect();
+
+        // Lists missing from list_order go last, oldest first, then by title, so they don't
+        // move around between reads
+        lists.sort_by(|a, b| {
+            let position = |list: &TaskList| order_map.get(&list.id).copied().unwrap_or(usize::MAX);
+            (position(a), a.created_at, &a.title).cmp(&(position(b), b.created_at, &b.title))
+        });
+
+        Ok(flatten_list_tree(lists))
+    }
+
+    /// Fill in each list's tasks, as `get_lists` returns them.
+    fn with_tasks(&self, mut lists: Vec<TaskList>) -> Result<Vec<TaskList>> {
+        for list in &mut lists {
+            list.tasks = self.list_tasks(list.id)?;
+            list.refresh_next_due();
+        }
+        Ok(lists)
+    }
+
+    /// `path` relative to the workspace root with `/` separators, as the search index keys
+    /// files.
+    fn index_key(&self, path: &Path) -> String {
+        let relative = path.strip_prefix(&self.root_path).unwrap_or(path);
+        relative.components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Every task file on disk, keyed like the search index, with its list, modification
+    /// time and size.
+    fn task_files(&self) -> Result<BTreeMap<String, TaskFile>> {
+        let mut files = BTreeMap::new();
+        for dir in self.list_dirs()? {
+            for entry in fs::read_dir(&dir.path)? {
+                let path = entry?.path();
+                if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("md") {
+                    let metadata = fs::metadata(&path)?;
+                    let (modified, size) = (metadata.modified()?.into(), metadata.len());
+                    files.insert(self.index_key(&path), TaskFile { list_id: dir.metadata.id, path, modified, size });
                 }
             }
         }
+        Ok(files)
+    }
 
+    /// Apply `change` to the saved search index, if there is one. Errors are ignored: the
+    /// index is only a cache, and the next search re-indexes whatever it got wrong.
+    fn update_search_index(&self, change: impl FnOnce(&mut SearchIndex)) {
+        if let Some(mut index) = SearchIndex::load(&self.root_path) {
+            change(&mut index);
+            let _ = index.save(&self.root_path);
+        }
+    }
+
+    /// Append `task_id` to the list's `task_order` if it isn't there yet.
+    fn ensure_in_task_order(&mut self, list_id: Uuid, task_id: Uuid) -> Result<()> {
+        let mut list_metadata = self.read_list_metadata(list_id)?;
+        if !list_metadata.task_order.contains(&task_id) {
+            list_metadata.task_order.push(task_id);
+            list_metadata.updated_at = Utc::now();
+            self.write_list_metadata(&list_metadata)?;
+        }
+        Ok(())
+    }
+}
+
+impl Storage for FileSystemStorage {
+    fn read_task(&self, list_id: Uuid, task_id: Uuid) -> Result<Task> {
+        let path = self.find_task_path(list_id, task_id)?;
+        self.read_task_from_path(&path)
+    }
+
+    fn find_task_list(&self, task_id: Uuid) -> Result<Uuid> {
+        // Read the headers first: that adopts hand-made folders, as `get_lists` would
+        let lists = self.get_list_headers()?;
+        let dirs: HashMap<Uuid, PathBuf> = self.list_dirs()?
+            .into_iter()
+            .map(|dir| (dir.metadata.id, dir.path))
+            .collect();
+        for list in lists {
+            if let Some(dir) = dirs.get(&list.id) {
+                if self.task_path_in(dir, task_id)?.is_some() {
+                    return Ok(list.id);
+                }
+            }
+        }
         Err(Error::TaskNotFound(task_id.to_string()))
     }
 
+    fn read_task_source(&self, list_id: Uuid, task_id: Uuid) -> Result<String> {
+        let path = self.find_task_path(list_id, task_id)?;
+        Ok(fs::read_to_string(path)?)
+    }
+
     fn write_task(&mut self, list_id: Uuid, task: &Task) -> Result<()> {
         let list_dir = self.list_dir_path(list_id)?;
         let task_path = self.task_file_path(&list_dir, task);
 
-        // Remove old file if task was renamed (different filename, same ID)
+        // Find the stored copy of this task, if any
+        let mut stored: Option<(PathBuf, String)> = None;
         for entry in fs::read_dir(&list_dir)? {
             let entry = entry?;
             let path = entry.path();
-            if path == task_path { continue; }
             if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("md") {
                 if let Ok(content) = fs::read_to_string(&path) {
-                    if let Ok((fm, _)) = self.parse_markdown_with_frontmatter(&content) {
-                        if fm.id == task.id {
-                            fs::remove_file(&path)?;
-                            break;
-                        }
+                    if parse_frontmatter_only(&content).is_ok_and(|id| id == task.id) {
+                        stored = Some((path, content));
+                        break;
                     }
                 }
             }
         }
 
-        let content = self.write_markdown_with_frontmatter(task)?;
-        fs::write(&task_path, content)?;
-
-        // Update list metadata to include this task in task_order if not already present
-        let mut list_metadata = self.read_list_metadata(list_id)?;
-        if !list_metadata.task_order.contains(&task.id) {
-            list_metadata.task_order.push(task.id);
-            list_metadata.updated_at = Utc::now();
-            self.write_list_metadata(&list_metadata)?;
+        let mut task = Cow::Borrowed(task);
+        if let Some((path, content)) = &stored {
+            if *path != task_path {
+                // Renamed (different filename, same ID): drop the old file
+                fs::remove_file(path)?;
+            } else if let Ok(existing) = self.read_task_from_path(path) {
+                // Keep the stored timestamps when nothing else changed, so rewriting an
+                // unchanged task doesn't churn the file
+                if let Some(unchanged) = with_stored_timestamps(&task, &existing)? {
+                    task = Cow::Owned(unchanged);
+                }
+                if self.write_markdown_with_frontmatter(&task)? == *content {
+                    return self.ensure_in_task_order(list_id, task.id);
+                }
+            }
         }
 
-        Ok(())
+        let content = self.write_markdown_with_frontmatter(&task)?;
+        fs::write(&task_path, content)?;
+
+        self.ensure_in_task_order(list_id, task.id)
     }
 
     fn delete_task(&mut self, list_id: Uuid, task_id: Uuid) -> Result<()> {
-        let task = self.read_task(list_id, task_id)?;
-        let list_dir = self.list_dir_path(list_id)?;
-        let task_path = self.task_file_path(&list_dir, &task);
+        // Look the file up by ID: it may still be named by an earlier filename scheme
+        let task_path = self.find_task_path(list_id, task_id)?;
 
         fs::remove_file(&task_path)?;
 
@@ -339,6 +1090,31 @@ impl Storage for FileSystemStorage {
         Ok(())
     }
 
+    fn move_task(&mut self, from_list_id: Uuid, to_list_id: Uuid, task_id: Uuid) -> Result<()> {
+        let from_path = self.find_task_path(from_list_id, task_id)?;
+        if from_list_id == to_list_id {
+            return Ok(());
+        }
+        let file_name = from_path.file_name()
+            .ok_or_else(|| Error::InvalidPath(format!("Invalid filename: {}", from_path.display())))?;
+        let to_path = self.list_dir_path(to_list_id)?.join(file_name);
+        if to_path.exists() {
+            return Err(Error::InvalidData(format!(
+                "{} already exists in the destination list", to_path.display()
+            )));
+        }
+
+        fs::rename(&from_path, &to_path)?;
+        if let Err(e) = self.ensure_in_task_order(to_list_id, task_id) {
+            let _ = fs::rename(&to_path, &from_path);
+            return Err(e);
+        }
+        let mut from_metadata = self.read_list_metadata(from_list_id)?;
+        from_metadata.task_order.retain(|&id| id != task_id);
+        from_metadata.updated_at = Utc::now();
+        self.write_list_metadata(&from_metadata)
+    }
+
     fn list_tasks(&self, list_id: Uuid) -> Result<Vec<Task>> {
         let list_dir = self.list_dir_path(list_id)?;
         let list_metadata = self.read_list_metadata(list_id)?;
@@ -351,27 +1127,7 @@ impl Storage for FileSystemStorage {
             let path = entry.path();
 
             if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("md") {
-                let content = fs::read_to_string(&path)?;
-                let (frontmatter, description) = self.parse_markdown_with_frontmatter(&content)?;
-
-                let title = path.file_stem()
-                    .and_then(|s| s.to_str())
-                    .ok_or_else(|| Error::InvalidData("Invalid filename".to_string()))?
-                    .to_string();
-
-                let task = Task {
-                    id: frontmatter.id,
-                    title,
-                    description,
-                    status: frontmatter.status,
-                    due_date: frontmatter.due,
-                    has_time: frontmatter.has_time,
-                    created_at: frontmatter.created,
-                    updated_at: frontmatter.updated,
-                    parent_id: frontmatter.parent,
-                };
-
-                tasks.push(task);
+                tasks.push(self.read_task_from_path(&path)?);
             }
         }
 
@@ -382,107 +1138,97 @@ impl Storage for FileSystemStorage {
             .map(|(i, &id)| (id, i))
             .collect();
 
-        tasks.sort_by_key(|task| order_map.get(&task.id).copied().unwrap_or(usize::MAX));
+        tasks.sort_by_key(|task| task_sort_key(&order_map, task.id, task.created_at));
 
         Ok(tasks)
     }
 
     fn create_list(&mut self, name: String) -> Result<TaskList> {
         let list_dir = self.list_dir_path_by_name(&name)?;
+        self.create_list_at(list_dir, name, None, 0)
+    }
 
-        if list_dir.exists() {
-            return Err(Error::InvalidData(format!("List '{}' already exists", name)));
+    fn create_sublist(&mut self, parent_id: Uuid, name: String) -> Result<TaskList> {
+        let parent = self.find_list_dir(parent_id)?;
+        if parent.depth >= MAX_LIST_DEPTH {
+            return Err(Error::InvalidData(format!(
+                "Lists can only be nested {} levels deep", MAX_LIST_DEPTH
+            )));
         }
+        let list_dir = self.child_dir_path(&parent.path, &name)?;
+        self.create_list_at(list_dir, name, Some(parent_id), parent.depth + 1)
+    }
 
-        fs::create_dir_all(&list_dir)?;
-
-        let list_id = Uuid::new_v4();
-        let list_metadata = ListMetadata::new(list_id);
-
-        let metadata_path = list_dir.join(".listdata.json");
-        let content = serde_json::to_string_pretty(&list_metadata)?;
-        fs::write(&metadata_path, content)?;
+    fn iter_list_tasks(&self, list_id: Uuid) -> Result<Box<dyn Iterator<Item = Result<Task>> + '_>> {
+        let list_dir = self.list_dir_path(list_id)?;
+        let order_map: HashMap<Uuid, usize> = self.read_list_metadata(list_id)?.task_order
+            .into_iter()
+            .enumerate()
+            .map(|(i, id)| (id, i))
+            .collect();
 
-        // Add to root metadata
-        let mut root_metadata = self.read_root_metadata_internal()?;
-        root_metadata.list_order.push(list_id);
-        if root_metadata.last_opened_list.is_none() {
-            root_metadata.last_opened_list = Some(list_id);
+        // Only the paths are kept up front; each task is parsed when it is yielded. The ID
+        // comes from the cheap frontmatter scan, and the full frontmatter is parsed only for
+        // tasks missing from `task_order`, whose place depends on their creation time.
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(&list_dir)? {
+            let path = entry?.path();
+            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("md") {
+                let content = fs::read_to_string(&path)?;
+                let id = parse_frontmatter_only(&content)?;
+                let created = if order_map.contains_key(&id) {
+                    DateTime::<Utc>::MIN_UTC
+                } else {
+                    self.parse_markdown_with_frontmatter(&content)?.0.created
+                };
+                paths.push((task_sort_key(&order_map, id, created), path));
+            }
         }
-        self.write_root_metadata_internal(&root_metadata)?;
+        paths.sort_by_key(|(key, _)| *key);
 
-        let task_list = TaskList {
-            id: list_id,
-            title: name,
-            tasks: Vec::new(),
-            created_at: list_metadata.created_at,
-            updated_at: list_metadata.updated_at,
-            group_by_due_date: list_metadata.group_by_due_date,
-        };
-
-        Ok(task_list)
+        Ok(Box::new(paths.into_iter().map(move |(_, path)| self.read_task_from_path(&path))))
     }
 
     fn get_lists(&self) -> Result<Vec<TaskList>> {
-        let root_metadata = self.read_root_metadata_internal()?;
-        let mut lists = Vec::new();
-
-        let entries = fs::read_dir(&self.root_path)?;
-
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.is_dir() {
-                let listdata_path = path.join(".listdata.json");
-                if listdata_path.exists() {
-                    let content = fs::read_to_string(&listdata_path)?;
-                    let list_metadata: ListMetadata = serde_json::from_str(&content)?;
-
-                    let title = path.file_name()
-                        .and_then(|s| s.to_str())
-                        .ok_or_else(|| Error::InvalidData("Invalid directory name".to_string()))?
-                        .to_string();
-
-                    let tasks = self.list_tasks(list_metadata.id)?;
-
-                    let task_list = TaskList {
-                        id: list_metadata.id,
-                        title,
-                        tasks,
-                        created_at: list_metadata.created_at,
-                        updated_at: list_metadata.updated_at,
-                        group_by_due_date: list_metadata.group_by_due_date,
-                    };
+        let lists = self.get_list_headers()?;
+        self.with_tasks(lists)
+    }
 
-                    lists.push(task_list);
-                }
-            }
+    fn get_list_headers(&self) -> Result<Vec<TaskList>> {
+        // Adopt hand-made top-level folders before reading lists
+        for name in self.unregistered_list_dirs()? {
+            self.adopt_dir(&self.root_path.join(name), false)?;
         }
+        self.registered_list_headers()
+    }
 
-        // Sort by list_order
-        let order_map: HashMap<Uuid, usize> = root_metadata.list_order
-            .iter()
-            .enumerate()
-            .map(|(i, &id)| (id, i))
-            .collect();
-
-        lists.sort_by_key(|list| order_map.get(&list.id).copied().unwrap_or(usize::MAX));
-
-        Ok(lists)
+    fn get_registered_lists(&self) -> Result<Vec<TaskList>> {
+        let lists = self.registered_list_headers()?;
+        self.with_tasks(lists)
     }
 
     fn delete_list(&mut self, list_id: Uuid) -> Result<()> {
         let list_dir = self.list_dir_path(list_id)?;
+        // Sublists live inside the directory and go with it
+        let removed: Vec<Uuid> = self.list_dirs()?
+            .into_iter()
+            .filter(|dir| dir.path.starts_with(&list_dir))
+            .map(|dir| dir.metadata.id)
+            .collect();
 
         fs::remove_dir_all(&list_dir)?;
+        let folder = self.index_key(&list_dir);
+        self.update_search_index(|index| index.remove_folder(&folder));
 
         // Remove from root metadata
         let mut root_metadata = self.read_root_metadata_internal()?;
-        root_metadata.list_order.retain(|&id| id != list_id);
-        if root_metadata.last_opened_list == Some(list_id) {
+        root_metadata.list_order.retain(|id| !removed.contains(id));
+        if root_metadata.last_opened_list.is_some_and(|id| removed.contains(&id)) {
             root_metadata.last_opened_list = root_metadata.list_order.first().copied();
         }
+        if root_metadata.inbox_list.is_some_and(|id| removed.contains(&id)) {
+            root_metadata.inbox_list = None;
+        }
         self.write_root_metadata_internal(&root_metadata)?;
 
         Ok(())
@@ -490,13 +1236,18 @@ impl Storage for FileSystemStorage {
 
     fn rename_list(&mut self, list_id: Uuid, new_name: String) -> Result<()> {
         let old_dir = self.list_dir_path(list_id)?;
-        let new_dir = self.list_dir_path_by_name(&new_name)?;
+        // Renaming keeps a sublist under the same parent
+        let base = old_dir.parent().unwrap_or(&self.root_path).to_path_buf();
+        let new_dir = self.child_dir_path(&base, &new_name)?;
 
-        if new_dir.exists() {
-            return Err(Error::InvalidData(format!("A list named '{}' already exists", new_name)));
+        if Self::folder_taken(&new_dir, Some(&old_dir))? {
+            return Err(Error::ListAlreadyExists(new_name));
         }
 
         fs::rename(&old_dir, &new_dir)?;
+        // The task files keep their modification times, so only their index keys change
+        let (from, to) = (self.index_key(&old_dir), self.index_key(&new_dir));
+        self.update_search_index(|index| index.rename_folder(&from, &to));
 
         // Update metadata timestamp
         let metadata_path = new_dir.join(".listdata.json");
@@ -513,37 +1264,218 @@ impl Storage for FileSystemStorage {
         self.read_root_metadata_internal()
     }
 
-    fn write_root_metadata(&mut self, metadata: &RootMetadata) -> Result<()> {
-        self.write_root_metadata_internal(metadata)
+    fn write_root_metadata(&mut self, metadata: &RootMetadata) -> Result<()> {
+        self.write_root_metadata_internal(metadata)
+    }
+
+    fn read_list_metadata(&self, list_id: Uuid) -> Result<ListMetadata> {
+        let list_dir = self.list_dir_path(list_id)?;
+        let metadata_path = list_dir.join(".listdata.json");
+
+        if !metadata_path.exists() {
+            return Err(Error::NotFound(format!("List metadata not found: {}", list_id)));
+        }
+
+        let content = fs::read_to_string(&metadata_path)?;
+        let metadata = serde_json::from_str(&content)?;
+        Ok(metadata)
+    }
+
+    fn write_list_metadata(&mut self, metadata: &ListMetadata) -> Result<()> {
+        let list_dir = self.list_dir_path(metadata.id)?;
+        let metadata_path = list_dir.join(".listdata.json");
+
+        let content = serde_json::to_string_pretty(&metadata)?;
+        fs::write(&metadata_path, content)?;
+        Ok(())
+    }
+
+    fn read_workspace_settings(&self) -> Result<WorkspaceSettings> {
+        WorkspaceSettings::load(&self.root_path)
+    }
+
+    fn write_workspace_settings(&mut self, settings: &WorkspaceSettings) -> Result<()> {
+        settings.save(&self.root_path)?;
+        self.apply_file_settings(settings);
+        Ok(())
+    }
+
+    fn set_settings_defaults(&mut self, defaults: WorkspaceSettings) {
+        self.settings_defaults = defaults;
+        self.reload_file_settings();
+    }
+
+    fn unregistered_list_dirs(&self) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&self.root_path)? {
+            let path = entry?.path();
+            if !path.is_dir() || path.join(".listdata.json").exists() {
+                continue;
+            }
+            if let Some(name) = path.file_name().and_then(|s| s.to_str()) {
+                if !name.starts_with('.') {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    fn adoptable_list_dirs(&self) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for name in self.unregistered_list_dirs()? {
+            if self.adoptable_tasks(&self.root_path.join(&name), false)?.is_some() {
+                names.push(name);
+            }
+        }
+        Ok(names)
+    }
+
+    fn adopt_list(&mut self, name: &str) -> Result<TaskList> {
+        let dir = self.list_dir_path_by_name(name)?;
+        if !dir.is_dir() {
+            return Err(Error::ListNotFound(name.to_string()));
+        }
+        if dir.join(".listdata.json").exists() {
+            return Err(Error::InvalidData(format!("'{}' is already a list", name)));
+        }
+        let metadata = self.adopt_dir(&dir, true)?
+            .ok_or_else(|| Error::InvalidData(format!("Could not adopt '{}'", name)))?;
+        let tasks = self.list_tasks(metadata.id)?;
+        Ok(list_from_metadata(&metadata, name.to_string(), tasks, None, 0))
+    }
+
+    fn rename_task_files(&mut self, dry_run: bool) -> Result<Vec<TaskFileRename>> {
+        let mut renames = Vec::new();
+        for dir in self.list_dirs()? {
+            let mut paths: Vec<PathBuf> = fs::read_dir(&dir.path)?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<std::io::Result<_>>()?;
+            paths.sort();
+            for path in paths {
+                if !path.is_file() || path.extension().and_then(|s| s.to_str()) != Some("md") {
+                    continue;
+                }
+                let task = self.read_task_from_path(&path)?;
+                let expected = self.task_file_path(&dir.path, &task);
+                if expected == path {
+                    continue;
+                }
+                let blocked = expected.exists();
+                if !dry_run && !blocked {
+                    // Rewrite rather than move: the new name may need the title in the frontmatter
+                    fs::write(&expected, self.write_markdown_with_frontmatter(&task)?)?;
+                    fs::remove_file(&path)?;
+                }
+                let relative = |p: &Path| p.strip_prefix(&self.root_path).unwrap_or(p).to_path_buf();
+                renames.push(TaskFileRename {
+                    task_id: task.id,
+                    from: relative(&path),
+                    to: relative(&expected),
+                    blocked,
+                });
+            }
+        }
+        Ok(renames)
     }
 
-    fn read_list_metadata(&self, list_id: Uuid) -> Result<ListMetadata> {
-        let list_dir = self.list_dir_path(list_id)?;
-        let metadata_path = list_dir.join(".listdata.json");
+    fn search_candidates(&self, text: &str) -> Result<Option<Vec<(Uuid, Task)>>> {
+        let Some(mut index) = SearchIndex::load(&self.root_path) else {
+            return Ok(None);
+        };
+        // Read the headers first: that adopts hand-made folders, whose files the index lacks
+        let lists = self.get_list_headers()?;
+        let files = self.task_files()?;
+
+        // Re-index the files added or modified since they were indexed (task writes, sync,
+        // an editor) and forget the ones that are gone. Tasks read here aren't read again.
+        let fresh = |key: &str, file: &IndexedFile| {
+            files.get(key).is_some_and(|on_disk| {
+                file.list_id == on_disk.list_id && file.modified == on_disk.modified && file.size == on_disk.size
+            })
+        };
+        let mut reread = HashMap::new();
+        for (key, file) in &files {
+            if !index.files.get(key).is_some_and(|indexed| fresh(key, indexed)) {
+                reread.insert(key.clone(), self.read_task_from_path(&file.path)?);
+            }
+        }
+        if !reread.is_empty() || index.files.len() != files.len() {
+            index.refresh(fresh, reread.iter().map(|(key, task)| {
+                (key.clone(), files[key].list_id, task, files[key].modified, files[key].size)
+            }));
+            let _ = index.save(&self.root_path);
+        }
 
-        if !metadata_path.exists() {
-            return Err(Error::NotFound(format!("List metadata not found: {}", list_id)));
+        let Some(ids) = index.candidates(text) else {
+            return Ok(None);
+        };
+
+        let mut found = Vec::new();
+        for list in lists {
+            let order: HashMap<Uuid, usize> = self.read_list_metadata(list.id)?.task_order
+                .iter()
+                .enumerate()
+                .map(|(i, &id)| (id, i))
+                .collect();
+            let mut tasks = Vec::new();
+            for (key, file) in &index.files {
+                if file.list_id == list.id && ids.contains(&file.id) {
+                    match reread.remove(key) {
+                        Some(task) => tasks.push(task),
+                        None => tasks.push(self.read_task_from_path(&files[key].path)?),
+                    }
+                }
+            }
+            tasks.sort_by_key(|task| task_sort_key(&order, task.id, task.created_at));
+            found.extend(tasks.into_iter().map(|task| (list.id, task)));
         }
+        Ok(Some(found))
+    }
 
-        let content = fs::read_to_string(&metadata_path)?;
-        let metadata = serde_json::from_str(&content)?;
-        Ok(metadata)
+    fn rebuild_search_index(&mut self) -> Result<usize> {
+        self.get_list_headers()?;
+        let mut index = SearchIndex::default();
+        for (key, file) in self.task_files()? {
+            index.insert(key, file.list_id, &self.read_task_from_path(&file.path)?, file.modified, file.size);
+        }
+        index.save(&self.root_path)?;
+        Ok(index.files.len())
     }
+}
 
-    fn write_list_metadata(&mut self, metadata: &ListMetadata) -> Result<()> {
-        let list_dir = self.list_dir_path(metadata.id)?;
-        let metadata_path = list_dir.join(".listdata.json");
+/// Sort key placing a task by its `task_order` position. Tasks missing from `task_order`
+/// (added outside onyx) go after the rest, oldest first with ties broken by ID, so their
+/// order never depends on how the directory happens to be listed.
+fn task_sort_key(order: &HashMap<Uuid, usize>, id: Uuid, created: DateTime<Utc>) -> (usize, DateTime<Utc>, Uuid) {
+    (order.get(&id).copied().unwrap_or(usize::MAX), created, id)
+}
 
-        let content = serde_json::to_string_pretty(&metadata)?;
-        fs::write(&metadata_path, content)?;
-        Ok(())
+/// Reorder lists (already sorted by `list_order`) depth-first, so every sublist follows
+/// its parent and siblings keep their relative order.
+fn flatten_list_tree(lists: Vec<TaskList>) -> Vec<TaskList> {
+    fn visit(parent: Option<Uuid>, pending: &mut Vec<Option<TaskList>>, out: &mut Vec<TaskList>) {
+        for i in 0..pending.len() {
+            if pending[i].as_ref().is_some_and(|list| list.parent_id == parent) {
+                let list = pending[i].take().expect("checked above");
+                let id = list.id;
+                out.push(list);
+                visit(Some(id), pending, out);
+            }
+        }
     }
+    let mut pending: Vec<Option<TaskList>> = lists.into_iter().map(Some).collect();
+    let mut out = Vec::with_capacity(pending.len());
+    visit(None, &mut pending, &mut out);
+    out
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::models::Task;
+    use chrono::TimeZone;
     use tempfile::TempDir;
 
     fn init_storage(temp_dir: &TempDir) -> FileSystemStorage {
@@ -596,6 +1528,94 @@ mod tests {
         assert!(matches!(result.unwrap_err(), Error::InvalidData(_)));
     }
 
+    #[test]
+    fn test_parse_task_file_with_bom_and_crlf() {
+        let content = "\u{feff}---\r\nid: 550e8400-e29b-41d4-a716-446655440000\r\nstatus: completed\r\ncreated: 2026-01-01T00:00:00Z\r\nupdated: 2026-01-01T00:00:00Z\r\ntags:\r\n- home\r\n---\r\n\r\nFirst line\r\nSecond line\r\n";
+        let task = parse_task_file(content, "Windows").unwrap();
+        assert_eq!(task.id.to_string(), "550e8400-e29b-41d4-a716-446655440000");
+        assert_eq!(task.status, TaskStatus::Completed);
+        assert_eq!(task.tags, vec!["home"]);
+        assert_eq!(task.description, "First line\nSecond line");
+
+        // Same with TOML frontmatter, and with old Mac-style lone CRs
+        let toml = "\u{feff}+++\r\nid = \"550e8400-e29b-41d4-a716-446655440000\"\r\nstatus = \"backlog\"\r\ncreated = 2026-01-01T00:00:00Z\r\nupdated = 2026-01-01T00:00:00Z\r\n+++\r\n\r\nNotes";
+        assert_eq!(parse_task_file(toml, "Windows").unwrap().description, "Notes");
+        let cr = content.replace("\r\n", "\r");
+        assert_eq!(parse_task_file(&cr, "Mac").unwrap().description, "First line\nSecond line");
+    }
+
+    #[test]
+    fn test_parse_frontmatter_only_agrees_with_full_parse() {
+        let task = Task::new("Report".to_string()).with_description("---\nid: not-this-one\n".to_string());
+        let id = "550e8400-e29b-41d4-a716-446655440000";
+        let rest = "status: backlog\ncreated: 2026-01-01T00:00:00Z\nupdated: 2026-01-01T00:00:00Z\n";
+        let files = [
+            serialize_task(&task).unwrap(),
+            serialize_task_as(&task, FrontmatterFormat::Toml).unwrap(),
+            format!("---\n{}id: \"{}\"\n---\n", rest, id),
+            // Quoted key and flow style: no plain `id` line, so the block itself is parsed
+            format!("---\n\"id\": {}\n{}---\n", id, rest),
+            format!("---\n{{id: {}, status: backlog, created: 2026-01-01T00:00:00Z, updated: 2026-01-01T00:00:00Z}}\n---\n", id),
+            format!("\u{feff}---\r{}id: {}\r---\rBody", rest.replace('\n', "\r"), id),
+        ];
+        for content in &files {
+            let full = parse_task_file(content, "t").unwrap().id;
+            assert_eq!(parse_frontmatter_only(content).unwrap(), full, "{}", content);
+        }
+        assert_eq!(parse_frontmatter_only(&files[0]).unwrap(), task.id);
+
+        assert!(parse_frontmatter_only("No frontmatter").is_err());
+    }
+
+    #[test]
+    fn test_task_with_bom_and_crlf_is_found_and_rewritten() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let list = storage.create_list("Tasks".to_string()).unwrap();
+        let id = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        let path = temp_dir.path().join("Tasks").join("Synced.md");
+        fs::write(&path, "\u{feff}---\r\nid: 550e8400-e29b-41d4-a716-446655440000\r\nstatus: backlog\r\ncreated: 2026-01-01T00:00:00Z\r\nupdated: 2026-01-01T00:00:00Z\r\n---\r\n").unwrap();
+
+        let mut task = storage.read_task(list.id, id).unwrap();
+        assert_eq!(task.title, "Synced");
+        task.complete();
+        storage.write_task(list.id, &task).unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("---\n"));
+        assert!(!written.contains('\r'));
+        assert_eq!(storage.read_task(list.id, id).unwrap().status, TaskStatus::Completed);
+        assert_eq!(storage.list_tasks(list.id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_frontmatter_with_rules_in_description() {
+        let frontmatter = "---\nid: 550e8400-e29b-41d4-a716-446655440000\nstatus: backlog\ncreated: 2026-01-01T00:00:00Z\nupdated: 2026-01-01T00:00:00Z\n---\n";
+        // Right after the closing fence, with and without a blank line, and between sections
+        for body in ["---\nAfter a rule", "\n---\nAfter a rule", "Intro\n\n---\n\nMore\n---\n+++\n---"] {
+            let task = parse_task_file(&format!("{}{}", frontmatter, body), "Rules").unwrap();
+            assert_eq!(task.description, body.trim());
+            assert_eq!(task.id.to_string(), "550e8400-e29b-41d4-a716-446655440000");
+        }
+        // Trailing spaces on a fence line don't hide it
+        let spaced = frontmatter.replace("---\n", "---  \n");
+        assert_eq!(parse_task_file(&format!("{}Body", spaced), "Rules").unwrap().description, "Body");
+    }
+
+    #[test]
+    fn test_fence_lines_inside_frontmatter_values_roundtrip() {
+        let mut task = Task::new("Fences".to_string());
+        task.description = "---\nBetween rules\n+++\n---".to_string();
+        task.extra_fields.insert("note".to_string(), serde_yaml::Value::from("above\n+++\n---\nbelow"));
+
+        for format in [FrontmatterFormat::Yaml, FrontmatterFormat::Toml] {
+            let content = serialize_task_as(&task, format).unwrap();
+            let parsed = parse_task_file(&content, "Fences").unwrap();
+            assert_eq!(parsed.description, task.description, "{:?}", format);
+            assert_eq!(parsed.extra_fields, task.extra_fields, "{:?}", format);
+        }
+    }
+
     #[test]
     fn test_parse_frontmatter_empty_content() {
         let temp_dir = TempDir::new().unwrap();
@@ -645,6 +1665,343 @@ mod tests {
         assert_eq!(desc, "Line 1\n\nLine 3");
     }
 
+    #[test]
+    fn test_markdown_roundtrip_priority_and_tags() {
+        let task = Task::builder("Tagged")
+            .priority(Priority::Medium)
+            .tags(["work", "q3"])
+            .assignee("Sam")
+            .label("📦")
+            .build();
+
+        let markdown = serialize_task(&task).unwrap();
+        assert!(markdown.contains("priority: medium"));
+        assert!(markdown.contains("assignee: Sam"));
+        assert!(markdown.contains("label: 📦"));
+        let parsed = parse_task_file(&markdown, "Tagged").unwrap();
+        assert_eq!(parsed.priority, Some(Priority::Medium));
+        assert_eq!(parsed.tags, vec!["work", "q3"]);
+        assert_eq!(parsed.assignee.as_deref(), Some("Sam"));
+        assert_eq!(parsed.label.as_deref(), Some("📦"));
+    }
+
+    #[test]
+    fn test_serialized_frontmatter_snapshot() {
+        use chrono::TimeZone;
+        let at = |h| Utc.with_ymd_and_hms(2025, 3, 4, h, 30, 0).unwrap();
+        let mut task = Task::builder("Snapshot")
+            .description("Body text")
+            .due_time(at(17))
+            .priority(Priority::High)
+            .tags(["work", "true", "2025", "a: b"])
+            .parent_id(Uuid::parse_str("00000000-0000-0000-0000-000000000002").unwrap())
+            .build();
+        task.id = Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap();
+        task.created_at = at(9);
+        task.updated_at = at(10) + chrono::Duration::milliseconds(250);
+
+        // Exact bytes: field order, quoting and timestamp format all show up in git diffs
+        // and sync, so any change here should be deliberate.
+        assert_eq!(serialize_task(&task).unwrap(), "\
+---
+id: 00000000-0000-0000-0000-000000000001
+status: backlog
+priority: high
+due: 2025-03-04T17:30:00Z
+has_time: true
+created: 2025-03-04T09:30:00Z
+updated: 2025-03-04T10:30:00.250Z
+parent: 00000000-0000-0000-0000-000000000002
+tags:
+- work
+- 'true'
+- '2025'
+- 'a: b'
+---
+
+Body text");
+
+        // Optional fields land in their fixed slots too
+        task.complete();
+        task.updated_at = at(11);
+        task.completed_at = Some(at(11));
+        task.assignee = Some("Sam".to_string());
+        task.label = Some("🔥".to_string());
+        task.fields.insert("issue".to_string(), "42".to_string());
+        task.depends_on.push(Uuid::nil());
+        task.tags.clear();
+        let yaml = serialize_task(&task).unwrap();
+        let keys: Vec<&str> = yaml.lines()
+            .filter_map(|line| line.split_once(':').map(|(key, _)| key))
+            .filter(|key| !key.starts_with(' ') && !key.starts_with('-'))
+            .collect();
+        assert_eq!(keys, vec!["id", "status", "priority", "due", "has_time", "created", "updated", "completed", "parent", "depends_on", "assignee", "label", "fields"]);
+    }
+
+    #[test]
+    fn test_unknown_frontmatter_fields_survive_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let list = storage.create_list("Tasks".to_string()).unwrap();
+        let path = temp_dir.path().join("Tasks").join("Foreign.md");
+        fs::write(&path, "\
+---
+id: 550e8400-e29b-41d4-a716-446655440000
+status: backlog
+created: 2025-01-01T00:00:00Z
+updated: 2025-01-01T00:00:00Z
+labels:
+- red
+- blue
+x-source: other-app
+---
+
+Notes").unwrap();
+        let mut meta = storage.read_list_metadata(list.id).unwrap();
+        meta.task_order.push(Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap());
+        storage.write_list_metadata(&meta).unwrap();
+
+        let mut task = storage.list_tasks(list.id).unwrap().remove(0);
+        assert_eq!(task.extra_fields.len(), 2);
+        assert_eq!(task.extra_fields["x-source"], serde_yaml::Value::from("other-app"));
+
+        task.description = "Edited".to_string();
+        storage.write_task(list.id, &task).unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.contains("labels:\n- red\n- blue\nx-source: other-app\n---"));
+        let reread = storage.read_task(list.id, task.id).unwrap();
+        assert_eq!(reread.description, "Edited");
+        assert_eq!(reread.extra_fields, task.extra_fields);
+    }
+
+    #[test]
+    fn test_custom_fields_set_read_and_clear() {
+        let mut task = Task::builder("Linked")
+            .field("issue", "https://github.com/SteelDynamite/bevy-tasks/issues/7")
+            .field("estimate", "3h")
+            .build();
+        task.extra_fields.insert("x-source".to_string(), serde_yaml::Value::from("other-app"));
+
+        for format in [FrontmatterFormat::Yaml, FrontmatterFormat::Toml] {
+            let markdown = serialize_task_as(&task, format).unwrap();
+            let parsed = parse_task_file(&markdown, "Linked").unwrap();
+            assert_eq!(parsed.fields, task.fields, "{:?}", format);
+            // Keys this version doesn't know stay apart from the custom fields
+            assert_eq!(parsed.extra_fields, task.extra_fields, "{:?}", format);
+        }
+        let yaml = serialize_task(&task).unwrap();
+        assert!(yaml.contains("fields:\n  estimate: 3h\n  issue: https://"), "{}", yaml);
+
+        // Clearing the last field drops the key altogether
+        task.fields.clear();
+        assert!(!serialize_task(&task).unwrap().contains("fields:"));
+
+        // Hand-written numbers and booleans read back as text; nested values are refused
+        let content = "---\nid: 550e8400-e29b-41d4-a716-446655440000\nstatus: backlog\ncreated: 2025-01-01T00:00:00Z\nupdated: 2025-01-01T00:00:00Z\nfields:\n  issue: 42\n  billable: true\n---\n";
+        let parsed = parse_task_file(content, "Hand").unwrap();
+        assert_eq!(parsed.fields["issue"], "42");
+        assert_eq!(parsed.fields["billable"], "true");
+        assert!(parse_task_file(&content.replace("issue: 42", "issue: [1, 2]"), "Hand").is_err());
+    }
+
+    #[test]
+    fn test_toml_frontmatter_roundtrip() {
+        use chrono::TimeZone;
+        let mut task = Task::builder("Hugo")
+            .description("Body text")
+            .due_time(Utc.with_ymd_and_hms(2025, 3, 4, 17, 30, 0).unwrap())
+            .priority(Priority::High)
+            .tags(["work", "2025"])
+            .build();
+        task.extra_fields.insert("draft".to_string(), serde_yaml::Value::Bool(true));
+
+        let markdown = serialize_task_as(&task, FrontmatterFormat::Toml).unwrap();
+        assert!(markdown.starts_with("+++\nid = \""));
+        assert!(markdown.contains("\ndue = 2025-03-04T17:30:00Z\n"));
+        assert!(markdown.contains("\ntags = [\"work\", \"2025\"]\n"));
+        assert!(markdown.contains("\n+++\n\nBody text"));
+
+        let parsed = parse_task_file(&markdown, "Hugo").unwrap();
+        assert_eq!(parsed.id, task.id);
+        assert_eq!(parsed.due_date, task.due_date);
+        assert_eq!(parsed.created_at, task.created_at);
+        assert_eq!(parsed.priority, Some(Priority::High));
+        assert_eq!(parsed.tags, task.tags);
+        assert_eq!(parsed.extra_fields, task.extra_fields);
+        assert_eq!(parsed.description, "Body text");
+        // Converting back to YAML gives the same file as a task that was always YAML
+        assert_eq!(serialize_task(&parsed).unwrap(), serialize_task(&task).unwrap());
+    }
+
+    #[test]
+    fn test_toml_keeps_date_like_strings_in_unknown_keys() {
+        let mut task = Task::new("Hugo".to_string());
+        task.created_at = "2025-01-02T03:04:05Z".parse().unwrap();
+        task.reminders.push("2025-03-01T09:00:00Z".parse().unwrap());
+        let date = serde_yaml::Value::String("2025-03-04T17:30:00Z".to_string());
+        task.extra_fields.insert("publish".to_string(), date.clone());
+
+        let markdown = serialize_task_as(&task, FrontmatterFormat::Toml).unwrap();
+        assert!(markdown.contains("\npublish = \"2025-03-04T17:30:00Z\"\n"), "{}", markdown);
+        // Known timestamps are still native datetimes, including in arrays
+        assert!(markdown.contains("\ncreated = 2025-01-02T03:04:05Z\n"), "{}", markdown);
+        assert!(markdown.contains("\nreminders = [2"), "{}", markdown);
+
+        // YAML -> TOML -> YAML keeps the string a string
+        let yaml = serialize_task(&parse_task_file(&markdown, "Hugo").unwrap()).unwrap();
+        let parsed = parse_task_file(&yaml, "Hugo").unwrap();
+        assert_eq!(parsed.extra_fields["publish"], date);
+        assert_eq!(yaml, serialize_task(&task).unwrap());
+    }
+
+    fn set_filename_scheme(storage: &mut FileSystemStorage, scheme: FilenameScheme) {
+        let settings = WorkspaceSettings { filename_scheme: Some(scheme), ..Default::default() };
+        storage.write_workspace_settings(&settings).unwrap();
+    }
+
+    #[test]
+    fn test_filename_schemes_roundtrip() {
+        let cases = [
+            (FilenameScheme::Title, "Buy milk_ 2L"),
+            (FilenameScheme::Uuid, "{id}"),
+            (FilenameScheme::SlugUuid, "buy-milk-2l-{id}"),
+        ];
+        for (scheme, stem) in cases {
+            let temp_dir = TempDir::new().unwrap();
+            let mut storage = init_storage(&temp_dir);
+            set_filename_scheme(&mut storage, scheme);
+            let list = storage.create_list("Tasks".to_string()).unwrap();
+
+            let task = Task::builder("Buy milk: 2L").description("Semi-skimmed").build();
+            storage.write_task(list.id, &task).unwrap();
+            let stem = stem.replace("{id}", &task.id.to_string());
+            assert!(temp_dir.path().join("Tasks").join(format!("{}.md", stem)).exists(), "{:?}", scheme);
+
+            let read = storage.read_task(list.id, task.id).unwrap();
+            assert_eq!(read.title, "Buy milk: 2L", "{:?}", scheme);
+            assert_eq!(read.description, "Semi-skimmed");
+            assert_eq!(storage.list_tasks(list.id).unwrap()[0].title, "Buy milk: 2L");
+
+            // Renaming the task keeps ID-based names stable and cleans up title-based ones
+            let mut renamed = read.clone();
+            renamed.title = "Buy oat milk".to_string();
+            storage.write_task(list.id, &renamed).unwrap();
+            let files = fs::read_dir(temp_dir.path().join("Tasks")).unwrap()
+                .filter(|e| e.as_ref().unwrap().path().extension().is_some_and(|x| x == "md"))
+                .count();
+            assert_eq!(files, 1);
+            assert_eq!(storage.read_task(list.id, task.id).unwrap().title, "Buy oat milk");
+            storage.delete_task(list.id, task.id).unwrap();
+            assert!(storage.list_tasks(list.id).unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_title_scheme_files_have_no_title_key() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let list = storage.create_list("Tasks".to_string()).unwrap();
+        storage.write_task(list.id, &Task::new("Plain".to_string())).unwrap();
+        let content = fs::read_to_string(temp_dir.path().join("Tasks").join("Plain.md")).unwrap();
+        assert!(!content.contains("title:"));
+    }
+
+    #[test]
+    fn test_rename_task_files_after_scheme_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let list = storage.create_list("Tasks".to_string()).unwrap();
+        let task = Task::new("Call Alex".to_string());
+        storage.write_task(list.id, &task).unwrap();
+
+        set_filename_scheme(&mut storage, FilenameScheme::Uuid);
+        let planned = storage.rename_task_files(true).unwrap();
+        assert_eq!(planned, vec![TaskFileRename {
+            task_id: task.id,
+            from: PathBuf::from("Tasks").join("Call Alex.md"),
+            to: PathBuf::from("Tasks").join(format!("{}.md", task.id)),
+            blocked: false,
+        }]);
+        assert!(temp_dir.path().join("Tasks").join("Call Alex.md").exists());
+
+        assert_eq!(storage.rename_task_files(false).unwrap(), planned);
+        assert!(!temp_dir.path().join("Tasks").join("Call Alex.md").exists());
+        assert_eq!(storage.read_task(list.id, task.id).unwrap().title, "Call Alex");
+        assert!(storage.rename_task_files(true).unwrap().is_empty());
+
+        // Switching back finds the file again, but won't overwrite a file in the way
+        set_filename_scheme(&mut storage, FilenameScheme::Title);
+        let other = Task::new("Call Alex".to_string());
+        let other_path = temp_dir.path().join("Tasks").join("Call Alex.md");
+        fs::write(&other_path, serialize_task(&other).unwrap()).unwrap();
+        let blocked: Vec<bool> = storage.rename_task_files(false).unwrap().iter().map(|r| r.blocked).collect();
+        assert_eq!(blocked, vec![true]);
+        assert_eq!(storage.read_task(list.id, task.id).unwrap().title, "Call Alex");
+    }
+
+    #[test]
+    fn test_frontmatter_format_setting_controls_writes() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let list = storage.create_list("Tasks".to_string()).unwrap();
+
+        let yaml_task = Task::new("Before".to_string());
+        storage.write_task(list.id, &yaml_task).unwrap();
+        let path = temp_dir.path().join("Tasks").join("Before.md");
+        assert!(fs::read_to_string(&path).unwrap().starts_with("---\n"));
+
+        let settings = WorkspaceSettings {
+            frontmatter_format: Some(FrontmatterFormat::Toml),
+            ..Default::default()
+        };
+        storage.write_workspace_settings(&settings).unwrap();
+        let toml_task = Task::new("After".to_string());
+        storage.write_task(list.id, &toml_task).unwrap();
+        let toml_path = temp_dir.path().join("Tasks").join("After.md");
+        assert!(fs::read_to_string(&toml_path).unwrap().starts_with("+++\n"));
+
+        // Both syntaxes read back, and a reopened storage picks the setting up again
+        let reopened = FileSystemStorage::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(reopened.list_tasks(list.id).unwrap().len(), 2);
+        assert_eq!(reopened.frontmatter_format, FrontmatterFormat::Toml);
+    }
+
+    #[test]
+    fn test_markdown_roundtrip_reminders() {
+        let at = Utc.with_ymd_and_hms(2026, 6, 1, 9, 0, 0).unwrap();
+        let mut task = Task::builder("Reminded").reminder(at).build();
+        task.mark_reminder_sent(at);
+
+        let markdown = serialize_task(&task).unwrap();
+        assert!(markdown.contains("reminders:"));
+        let parsed = parse_task_file(&markdown, "Reminded").unwrap();
+        assert_eq!(parsed.reminders, vec![at]);
+        assert_eq!(parsed.reminders_sent, vec![at]);
+    }
+
+    #[test]
+    fn test_markdown_roundtrip_completed_at() {
+        let mut task = Task::new("Finished".to_string());
+        task.complete();
+
+        let markdown = serialize_task(&task).unwrap();
+        assert!(markdown.contains("completed:"));
+        let parsed = parse_task_file(&markdown, "Finished").unwrap();
+        assert_eq!(parsed.completed_at, task.completed_at);
+    }
+
+    #[test]
+    fn test_parse_old_file_without_priority_or_tags() {
+        let content = "---\nid: 550e8400-e29b-41d4-a716-446655440000\nstatus: backlog\ncreated: 2026-01-01T00:00:00Z\nupdated: 2026-01-01T00:00:00Z\n---\n";
+        let task = parse_task_file(content, "Old").unwrap();
+        assert!(task.priority.is_none());
+        assert!(task.tags.is_empty());
+        assert!(task.completed_at.is_none());
+        assert!(task.assignee.is_none());
+        assert!(task.label.is_none());
+    }
+
     // --- FileSystemStorage init/new ---
 
     #[test]
@@ -686,10 +2043,41 @@ mod tests {
         fs::remove_file(temp_dir.path().join(".metadata.json")).unwrap();
 
         let meta = storage.read_root_metadata().unwrap();
-        assert_eq!(meta.version, 1);
+        assert_eq!(meta.version, RootMetadata::CURRENT_VERSION);
         assert!(meta.list_order.is_empty());
     }
 
+    #[test]
+    fn test_root_metadata_timestamps() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let meta = storage.read_root_metadata().unwrap();
+        assert_eq!(meta.created_at, meta.updated_at);
+
+        storage.create_list("Work".to_string()).unwrap();
+        let after = storage.read_root_metadata().unwrap();
+        assert_eq!(after.created_at, meta.created_at);
+        assert!(after.updated_at >= meta.updated_at);
+    }
+
+    #[test]
+    fn test_migrate_version_2_timestamps_from_mtime() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let path = root.join(".metadata.json");
+        fs::write(&path, "{\"version\": 2, \"list_order\": []}").unwrap();
+        let mtime: DateTime<Utc> = fs::metadata(&path).unwrap().modified().unwrap().into();
+
+        let storage = FileSystemStorage::new(root.to_path_buf()).unwrap();
+        let meta = storage.read_root_metadata().unwrap();
+        assert_eq!(meta.version, RootMetadata::CURRENT_VERSION);
+        assert_eq!(meta.created_at, mtime);
+        assert_eq!(meta.updated_at, mtime);
+        // Persisted, not just filled in on read
+        let raw = fs::read_to_string(&path).unwrap();
+        assert!(raw.contains("created_at") && raw.contains("updated_at"));
+    }
+
     // --- List operations ---
 
     #[test]
@@ -700,7 +2088,37 @@ mod tests {
         storage.create_list("Dupes".to_string()).unwrap();
         let result = storage.create_list("Dupes".to_string());
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), Error::InvalidData(_)));
+        assert!(matches!(result.unwrap_err(), Error::ListAlreadyExists(_)));
+
+        // Windows would store "Dupes." and "Dupes " in the same folder as "Dupes"
+        for name in ["Dupes.", "Dupes ", "Dupes. ."] {
+            assert!(matches!(storage.create_list(name.to_string()).unwrap_err(), Error::ListAlreadyExists(_)));
+        }
+        assert_eq!(storage.get_list_headers().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_rename_list_never_overwrites_another_list() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let a = storage.create_list("A".to_string()).unwrap();
+        let b = storage.create_list("B".to_string()).unwrap();
+        let task = Task::new("Keep me".to_string());
+        storage.write_task(b.id, &task).unwrap();
+
+        let err = storage.rename_list(a.id, "B".to_string()).unwrap_err();
+        assert!(matches!(err, Error::ListAlreadyExists(ref name) if name == "B"));
+        let err = storage.rename_list(a.id, "B.".to_string()).unwrap_err();
+        assert!(matches!(err, Error::ListAlreadyExists(_)));
+
+        let lists = storage.get_lists().unwrap();
+        assert_eq!(lists.iter().map(|l| l.title.as_str()).collect::<Vec<_>>(), vec!["A", "B"]);
+        assert_eq!(lists[1].tasks[0].id, task.id);
+
+        // A list may still take a name its own folder is already stored under on Windows
+        storage.rename_list(a.id, "A.".to_string()).unwrap();
+        storage.rename_list(a.id, "A".to_string()).unwrap();
+        storage.rename_list(a.id, "A".to_string()).unwrap();
     }
 
     #[test]
@@ -717,6 +2135,17 @@ mod tests {
         assert!(!meta_after.list_order.contains(&list.id));
     }
 
+    #[test]
+    fn test_create_list_path_traversal_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+
+        for name in ["../escape", "a/b", ".."] {
+            let result = storage.create_list(name.to_string());
+            assert!(matches!(result.unwrap_err(), Error::InvalidPath(_)));
+        }
+    }
+
     #[test]
     fn test_list_dir_path_nonexistent_list() {
         let temp_dir = TempDir::new().unwrap();
@@ -785,6 +2214,64 @@ mod tests {
         assert_eq!(meta.task_order.len(), 1); // Should not duplicate
     }
 
+    #[test]
+    fn test_recompleting_task_does_not_rewrite_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let list = storage.create_list("Tasks".to_string()).unwrap();
+
+        let mut task = Task::new("Ship it".to_string());
+        task.complete();
+        storage.write_task(list.id, &task).unwrap();
+        let path = temp_dir.path().join("Tasks").join("Ship it.md");
+        let before = fs::read_to_string(&path).unwrap();
+        let stamp = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        fs::File::options().write(true).open(&path).unwrap().set_modified(stamp).unwrap();
+
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let mut again = storage.read_task(list.id, task.id).unwrap();
+        again.complete();
+        storage.write_task(list.id, &again).unwrap();
+
+        assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), stamp);
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+        let stored = storage.read_task(list.id, task.id).unwrap();
+        assert_eq!(stored.updated_at, task.updated_at);
+        assert_eq!(stored.completed_at, task.completed_at);
+    }
+
+    #[test]
+    fn test_write_task_with_changes_still_bumps_updated() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let list = storage.create_list("Tasks".to_string()).unwrap();
+
+        let mut task = Task::new("Draft".to_string());
+        task.updated_at = Utc::now() - chrono::Duration::days(1);
+        storage.write_task(list.id, &task).unwrap();
+
+        let mut edited = task.clone();
+        edited.description = "More detail".to_string();
+        edited.tags.push("work".to_string());
+        edited.updated_at = Utc::now();
+        storage.write_task(list.id, &edited).unwrap();
+
+        let stored = storage.read_task(list.id, task.id).unwrap();
+        assert_eq!(stored.description, "More detail");
+        assert_eq!(stored.tags, vec!["work"]);
+        assert_eq!(stored.updated_at, edited.updated_at);
+
+        // Reopening is a status change, so it goes through as well
+        let mut reopened = stored.clone();
+        reopened.complete();
+        storage.write_task(list.id, &reopened).unwrap();
+        reopened.uncomplete();
+        storage.write_task(list.id, &reopened).unwrap();
+        let stored = storage.read_task(list.id, task.id).unwrap();
+        assert_eq!(stored.status, TaskStatus::Backlog);
+        assert_eq!(stored.updated_at, reopened.updated_at);
+    }
+
     #[test]
     fn test_delete_task_removes_from_order() {
         let temp_dir = TempDir::new().unwrap();
@@ -799,6 +2286,55 @@ mod tests {
         assert!(!meta.task_order.contains(&task.id));
     }
 
+    #[test]
+    fn test_move_task_keeps_file_bytes() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let from = storage.create_list("From".to_string()).unwrap();
+        let to = storage.create_list("To".to_string()).unwrap();
+        let content = "\
+---
+id: 550e8400-e29b-41d4-a716-446655440000
+status: backlog
+created: 2025-01-01T00:00:00Z
+updated: 2025-01-01T00:00:00Z
+x-source: other-app
+---
+
+Notes";
+        fs::write(temp_dir.path().join("From").join("Foreign.md"), content).unwrap();
+        let id = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        let mut meta = storage.read_list_metadata(from.id).unwrap();
+        meta.task_order.push(id);
+        storage.write_list_metadata(&meta).unwrap();
+        let other = Task::new("Other".to_string());
+        storage.write_task(to.id, &other).unwrap();
+
+        storage.move_task(from.id, to.id, id).unwrap();
+
+        assert_eq!(fs::read_to_string(temp_dir.path().join("To").join("Foreign.md")).unwrap(), content);
+        assert!(!temp_dir.path().join("From").join("Foreign.md").exists());
+        assert!(storage.read_list_metadata(from.id).unwrap().task_order.is_empty());
+        assert_eq!(storage.read_list_metadata(to.id).unwrap().task_order, vec![other.id, id]);
+    }
+
+    #[test]
+    fn test_move_task_refuses_to_overwrite() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let from = storage.create_list("From".to_string()).unwrap();
+        let to = storage.create_list("To".to_string()).unwrap();
+        let moving = Task::new("Same".to_string());
+        let staying = Task::new("Same".to_string());
+        storage.write_task(from.id, &moving).unwrap();
+        storage.write_task(to.id, &staying).unwrap();
+
+        assert!(storage.move_task(from.id, to.id, moving.id).unwrap_err().is_invalid_data());
+        assert_eq!(storage.read_task(from.id, moving.id).unwrap().id, moving.id);
+        assert_eq!(storage.read_task(to.id, staying.id).unwrap().id, staying.id);
+        assert_eq!(storage.read_list_metadata(to.id).unwrap().task_order, vec![staying.id]);
+    }
+
     #[test]
     fn test_list_tasks_respects_order() {
         let temp_dir = TempDir::new().unwrap();
@@ -823,6 +2359,39 @@ mod tests {
         assert_eq!(tasks[2].id, t2.id);
     }
 
+    #[test]
+    fn test_tasks_missing_from_order_append_stably() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let list = storage.create_list("Tasks".to_string()).unwrap();
+
+        let at = |day| Utc.with_ymd_and_hms(2026, 1, day, 0, 0, 0).unwrap();
+        let ordered = Task::new("Ordered".to_string());
+        let mut tasks: Vec<Task> = ["Newest", "Tie b", "Oldest", "Tie a"].iter()
+            .map(|title| Task::new(title.to_string()))
+            .collect();
+        for (task, day) in tasks.iter_mut().zip([9, 5, 1, 5]) {
+            task.created_at = at(day);
+            task.updated_at = at(day);
+        }
+        for task in tasks.iter().chain([&ordered]) {
+            storage.write_task(list.id, task).unwrap();
+        }
+        // As if the files had been dropped in by another app: only one is in task_order
+        let mut meta = storage.read_list_metadata(list.id).unwrap();
+        meta.task_order = vec![ordered.id];
+        storage.write_list_metadata(&meta).unwrap();
+
+        let (tie_first, tie_second) = if tasks[1].id < tasks[3].id { (1, 3) } else { (3, 1) };
+        let expected = vec![ordered.id, tasks[2].id, tasks[tie_first].id, tasks[tie_second].id, tasks[0].id];
+        for _ in 0..3 {
+            let listed: Vec<Uuid> = storage.list_tasks(list.id).unwrap().iter().map(|t| t.id).collect();
+            assert_eq!(listed, expected);
+            let iterated: Vec<Uuid> = storage.iter_list_tasks(list.id).unwrap().map(|t| t.unwrap().id).collect();
+            assert_eq!(iterated, expected);
+        }
+    }
+
     #[test]
     fn test_list_tasks_empty_list() {
         let temp_dir = TempDir::new().unwrap();
@@ -832,4 +2401,195 @@ mod tests {
         let tasks = storage.list_tasks(list.id).unwrap();
         assert!(tasks.is_empty());
     }
+
+    // --- Adopting hand-made folders ---
+
+    #[test]
+    fn test_get_lists_adopts_folder_with_task_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = init_storage(&temp_dir);
+        let dir = temp_dir.path().join("Manual");
+        fs::create_dir(&dir).unwrap();
+        let mut older = Task::new("First".to_string());
+        older.created_at -= chrono::Duration::hours(1);
+        let newer = Task::new("Second".to_string());
+        fs::write(dir.join("Second.md"), serialize_task(&newer).unwrap()).unwrap();
+        fs::write(dir.join("First.md"), serialize_task(&older).unwrap()).unwrap();
+
+        let lists = storage.get_lists().unwrap();
+        assert_eq!(lists.len(), 1);
+        assert_eq!(lists[0].title, "Manual");
+        assert_eq!(lists[0].tasks.iter().map(|t| t.id).collect::<Vec<_>>(), vec![older.id, newer.id]);
+        assert!(dir.join(".listdata.json").exists());
+        assert_eq!(storage.read_root_metadata().unwrap().list_order, vec![lists[0].id]);
+
+        // Adoption happens once; the id is stable on later reads.
+        assert_eq!(storage.get_lists().unwrap()[0].id, lists[0].id);
+    }
+
+    #[test]
+    fn test_get_lists_ignores_unrelated_folders() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = init_storage(&temp_dir);
+        let notes = temp_dir.path().join("Notes");
+        fs::create_dir(&notes).unwrap();
+        fs::write(notes.join("readme.md"), "# Just a note\n").unwrap();
+        fs::create_dir(temp_dir.path().join("Empty")).unwrap();
+        let hidden = temp_dir.path().join(".obsidian");
+        fs::create_dir(&hidden).unwrap();
+        fs::write(hidden.join("t.md"), serialize_task(&Task::new("t".into())).unwrap()).unwrap();
+
+        assert!(storage.get_lists().unwrap().is_empty());
+        assert!(!notes.join(".listdata.json").exists());
+        assert!(!hidden.join(".listdata.json").exists());
+        assert_eq!(storage.unregistered_list_dirs().unwrap(), vec!["Empty", "Notes"]);
+    }
+
+    #[test]
+    fn test_adopt_list_forced() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        fs::create_dir(temp_dir.path().join("Empty")).unwrap();
+        let notes = temp_dir.path().join("Notes");
+        fs::create_dir(&notes).unwrap();
+        fs::write(notes.join("readme.md"), "# Just a note\n").unwrap();
+
+        let list = storage.adopt_list("Empty").unwrap();
+        assert!(list.tasks.is_empty());
+        assert_eq!(storage.get_lists().unwrap().len(), 1);
+
+        assert!(storage.adopt_list("Empty").unwrap_err().is_invalid_data());
+        assert!(storage.adopt_list("Notes").unwrap_err().is_invalid_data());
+        assert!(storage.adopt_list("Missing").unwrap_err().is_not_found());
+    }
+
+    // --- Nested lists ---
+
+    #[test]
+    fn test_sublists_follow_parent_with_depth() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let work = storage.create_list("Work".to_string()).unwrap();
+        let home = storage.create_list("Home".to_string()).unwrap();
+        let q3 = storage.create_sublist(work.id, "Q3".to_string()).unwrap();
+        let launch = storage.create_sublist(q3.id, "Launch".to_string()).unwrap();
+        let hiring = storage.create_sublist(work.id, "Hiring".to_string()).unwrap();
+        assert!(temp_dir.path().join("Work").join("Q3").join("Launch").join(".listdata.json").exists());
+
+        let lists = storage.get_lists().unwrap();
+        let shape: Vec<(&str, usize, Option<Uuid>)> = lists.iter()
+            .map(|l| (l.title.as_str(), l.depth, l.parent_id))
+            .collect();
+        assert_eq!(shape, vec![
+            ("Work", 0, None),
+            ("Q3", 1, Some(work.id)),
+            ("Launch", 2, Some(q3.id)),
+            ("Hiring", 1, Some(work.id)),
+            ("Home", 0, None),
+        ]);
+        assert_eq!(storage.read_list_metadata(launch.id).unwrap().parent_list, Some(q3.id));
+
+        // Tasks in a sublist stay in that list only
+        storage.write_task(hiring.id, &Task::new("Post job".to_string())).unwrap();
+        assert_eq!(storage.list_tasks(hiring.id).unwrap().len(), 1);
+        assert!(storage.list_tasks(work.id).unwrap().is_empty());
+        assert!(storage.list_tasks(home.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sublist_depth_limit_and_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let a = storage.create_list("A".to_string()).unwrap();
+        let b = storage.create_sublist(a.id, "B".to_string()).unwrap();
+        let c = storage.create_sublist(b.id, "C".to_string()).unwrap();
+
+        assert!(storage.create_sublist(c.id, "D".to_string()).unwrap_err().is_invalid_data());
+        assert!(matches!(storage.create_sublist(a.id, "../B".to_string()).unwrap_err(), Error::InvalidPath(_)));
+        assert!(storage.create_sublist(a.id, "B".to_string()).unwrap_err().is_invalid_data());
+        // Same name under a different parent is fine
+        storage.create_sublist(b.id, "A".to_string()).unwrap();
+        assert!(storage.create_sublist(Uuid::new_v4(), "X".to_string()).unwrap_err().is_not_found());
+    }
+
+    #[test]
+    fn test_lists_missing_from_order_sort_stably() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let names = ["Ordered", "Newer", "Tie b", "Tie a"];
+        let lists: Vec<TaskList> = names.iter().map(|name| storage.create_list(name.to_string()).unwrap()).collect();
+
+        let at = |day| Utc.with_ymd_and_hms(2026, 1, day, 0, 0, 0).unwrap();
+        for (list, day) in lists.iter().zip([1, 9, 5, 5]) {
+            let mut meta = storage.read_list_metadata(list.id).unwrap();
+            meta.created_at = at(day);
+            storage.write_list_metadata(&meta).unwrap();
+        }
+        // Only one list is in list_order, as if the others had been made by hand
+        let mut root = storage.read_root_metadata().unwrap();
+        root.list_order = vec![lists[0].id];
+        storage.write_root_metadata(&root).unwrap();
+
+        for _ in 0..3 {
+            let titles: Vec<String> = storage.get_list_headers().unwrap().into_iter().map(|l| l.title).collect();
+            assert_eq!(titles, vec!["Ordered", "Tie a", "Tie b", "Newer"]);
+        }
+    }
+
+    #[test]
+    fn test_delete_list_removes_sublists_from_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let work = storage.create_list("Work".to_string()).unwrap();
+        let home = storage.create_list("Home".to_string()).unwrap();
+        let q3 = storage.create_sublist(work.id, "Q3".to_string()).unwrap();
+
+        let mut root = storage.read_root_metadata().unwrap();
+        root.last_opened_list = Some(q3.id);
+        root.inbox_list = Some(q3.id);
+        storage.write_root_metadata(&root).unwrap();
+
+        storage.delete_list(work.id).unwrap();
+        let root = storage.read_root_metadata().unwrap();
+        assert_eq!(root.list_order, vec![home.id]);
+        assert_eq!(root.last_opened_list, Some(home.id));
+        assert_eq!(root.inbox_list, None);
+    }
+
+    #[test]
+    fn test_rename_sublist_stays_under_parent() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = init_storage(&temp_dir);
+        let work = storage.create_list("Work".to_string()).unwrap();
+        let q3 = storage.create_sublist(work.id, "Q3".to_string()).unwrap();
+
+        storage.rename_list(q3.id, "Q4".to_string()).unwrap();
+        assert!(temp_dir.path().join("Work").join("Q4").is_dir());
+        let renamed = storage.get_lists().unwrap().into_iter().find(|l| l.id == q3.id).unwrap();
+        assert_eq!(renamed.title, "Q4");
+        assert_eq!(renamed.parent_id, Some(work.id));
+    }
+
+    #[test]
+    fn test_migrate_version_1_lists_to_top_level() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let list_id = Uuid::new_v4();
+        fs::write(root.join(".metadata.json"), format!(
+            "{{\"version\": 1, \"list_order\": [\"{}\"]}}", list_id
+        )).unwrap();
+        fs::create_dir(root.join("Old")).unwrap();
+        fs::write(root.join("Old").join(".listdata.json"), format!(
+            "{{\"id\": \"{}\", \"created_at\": \"2025-01-01T00:00:00Z\", \"updated_at\": \"2025-01-01T00:00:00Z\", \"group_by_due_date\": false, \"task_order\": []}}",
+            list_id
+        )).unwrap();
+
+        let storage = FileSystemStorage::new(root.to_path_buf()).unwrap();
+        assert_eq!(storage.read_root_metadata().unwrap().version, RootMetadata::CURRENT_VERSION);
+        let lists = storage.get_lists().unwrap();
+        assert_eq!(lists.len(), 1);
+        assert_eq!(lists[0].id, list_id);
+        assert_eq!(lists[0].parent_id, None);
+        assert_eq!(lists[0].depth, 0);
+    }
 }
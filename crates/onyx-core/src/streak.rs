@@ -0,0 +1,96 @@
+use chrono::{DateTime, Days, NaiveDate, TimeZone, Utc};
+
+/// Runs of consecutive calendar days with at least one completion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Streaks {
+    /// Run ending today, or yesterday if nothing has been completed yet today.
+    pub current: u32,
+    pub longest: u32,
+}
+
+/// Distinct calendar days in `tz` on which something was completed, oldest first.
+pub fn completion_days<Tz: TimeZone>(completions: &[DateTime<Utc>], tz: &Tz) -> Vec<NaiveDate> {
+    let mut days: Vec<NaiveDate> = completions.iter()
+        .map(|at| at.with_timezone(tz).date_naive())
+        .collect();
+    days.sort();
+    days.dedup();
+    days
+}
+
+/// Current and longest completion streaks at `now`, judged by calendar days in `tz`.
+/// Completions after `now` are ignored.
+pub fn compute_streaks<Tz: TimeZone>(completions: &[DateTime<Utc>], now: DateTime<Utc>, tz: &Tz) -> Streaks {
+    let today = now.with_timezone(tz).date_naive();
+    let days: Vec<NaiveDate> = completion_days(completions, tz)
+        .into_iter()
+        .filter(|day| *day <= today)
+        .collect();
+
+    let mut streaks = Streaks::default();
+    let mut run = 0;
+    let mut prev: Option<NaiveDate> = None;
+    for day in &days {
+        run = match prev {
+            Some(p) if p.checked_add_days(Days::new(1)) == Some(*day) => run + 1,
+            _ => 1,
+        };
+        streaks.longest = streaks.longest.max(run);
+        prev = Some(*day);
+    }
+
+    // The last run is still going if it reaches today or yesterday
+    let yesterday = today.checked_sub_days(Days::new(1));
+    if prev.is_some_and(|last| last == today || Some(last) == yesterday) {
+        streaks.current = run;
+    }
+    streaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_no_completions() {
+        assert_eq!(compute_streaks(&[], at(10, 12), &Utc), Streaks::default());
+    }
+
+    #[test]
+    fn test_current_and_longest_runs() {
+        // Runs: 1-4 (four days), then 7-9 (three days, ending today)
+        let completions = [at(1, 9), at(2, 9), at(2, 18), at(3, 9), at(4, 9), at(7, 9), at(8, 9), at(9, 9)];
+        let streaks = compute_streaks(&completions, at(9, 20), &Utc);
+        assert_eq!(streaks, Streaks { current: 3, longest: 4 });
+    }
+
+    #[test]
+    fn test_current_streak_survives_until_end_of_next_day() {
+        let completions = [at(5, 9), at(6, 9)];
+        assert_eq!(compute_streaks(&completions, at(7, 23), &Utc).current, 2);
+        assert_eq!(compute_streaks(&completions, at(8, 0), &Utc).current, 0);
+        assert_eq!(compute_streaks(&completions, at(8, 0), &Utc).longest, 2);
+    }
+
+    #[test]
+    fn test_days_grouped_in_local_timezone() {
+        // 23:00 UTC on June 1 and 01:00 UTC on June 2 are the same day at UTC-3
+        let tz = FixedOffset::west_opt(3 * 3600).unwrap();
+        let completions = [at(1, 23), at(2, 1)];
+        assert_eq!(completion_days(&completions, &tz).len(), 1);
+        assert_eq!(completion_days(&completions, &Utc).len(), 2);
+        assert_eq!(compute_streaks(&completions, at(2, 12), &tz), Streaks { current: 1, longest: 1 });
+        assert_eq!(compute_streaks(&completions, at(2, 12), &Utc), Streaks { current: 2, longest: 2 });
+    }
+
+    #[test]
+    fn test_future_completions_ignored() {
+        let completions = [at(9, 9), at(10, 9), at(12, 9)];
+        assert_eq!(compute_streaks(&completions, at(10, 12), &Utc), Streaks { current: 2, longest: 2 });
+    }
+}
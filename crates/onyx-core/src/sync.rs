@@ -1,9 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::future::Future;
 use std::path::Path;
 use chrono::{DateTime, Utc};
+use futures::stream::{self, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
+use crate::config::WorkspaceSettings;
 use crate::error::{Error, Result};
+use crate::storage::MAX_LIST_DEPTH;
 use crate::webdav::WebDavClient;
 
 // --- Sync State ---
@@ -49,17 +53,65 @@ impl SyncAction {
     }
 }
 
-/// Result summary of a sync operation.
-#[derive(Debug, Default)]
+/// Result summary of a sync operation. Each count has a matching list of affected paths.
+#[derive(Debug, Default, Serialize)]
 pub struct SyncResult {
     pub uploaded: u32,
     pub downloaded: u32,
+    /// Total size of the files uploaded, in bytes.
+    pub bytes_uploaded: u64,
+    /// Total size of the files downloaded, in bytes.
+    pub bytes_downloaded: u64,
     pub deleted_local: u32,
     pub deleted_remote: u32,
     pub conflicts: u32,
+    pub uploaded_files: Vec<String>,
+    pub downloaded_files: Vec<String>,
+    pub deleted_local_files: Vec<String>,
+    pub deleted_remote_files: Vec<String>,
+    pub conflict_files: Vec<String>,
     pub errors: Vec<String>,
 }
 
+impl SyncResult {
+    /// Count a successfully executed action that moved `bytes` (0 for deletions).
+    fn record_success(&mut self, action: &SyncAction, bytes: u64) {
+        let path = action.path().to_string();
+        match action {
+            SyncAction::Upload { .. } | SyncAction::ConflictLocalWins { .. } => {
+                self.uploaded += 1;
+                self.bytes_uploaded += bytes;
+                self.uploaded_files.push(path);
+            }
+            SyncAction::Download { .. } | SyncAction::ConflictRemoteWins { .. } => {
+                self.downloaded += 1;
+                self.bytes_downloaded += bytes;
+                self.downloaded_files.push(path);
+            }
+            SyncAction::DeleteLocal { .. } => {
+                self.deleted_local += 1;
+                self.deleted_local_files.push(path);
+            }
+            SyncAction::DeleteRemote { .. } => {
+                self.deleted_remote += 1;
+                self.deleted_remote_files.push(path);
+            }
+        }
+    }
+
+    /// Count a failed action. Failed transfers are left as conflicts for the next sync.
+    fn record_failure(&mut self, action: &SyncAction, msg: String) {
+        self.errors.push(msg);
+        if matches!(action,
+            SyncAction::Upload { .. } | SyncAction::Download { .. }
+            | SyncAction::ConflictLocalWins { .. } | SyncAction::ConflictRemoteWins { .. }
+        ) {
+            self.conflicts += 1;
+            self.conflict_files.push(action.path().to_string());
+        }
+    }
+}
+
 /// Sync direction mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SyncMode {
@@ -371,17 +423,14 @@ pub fn compute_checksum(data: &[u8]) -> String {
 fn is_syncable(path: &str) -> bool {
     let parts: Vec<&str> = path.split('/').collect();
     let filename = parts.last().copied().unwrap_or(path);
-    // .metadata.json only at workspace root (depth 1)
-    if filename == ".metadata.json" {
+    // .metadata.json and .workspace.json only at workspace root (depth 1)
+    if filename == ".metadata.json" || filename == WorkspaceSettings::FILE_NAME {
         return parts.len() == 1;
     }
-    // .listdata.json only inside a list directory (depth 2)
-    if filename == ".listdata.json" {
-        return parts.len() == 2;
-    }
-    // .md files inside a list directory (depth 2)
-    if filename.ends_with(".md") {
-        return parts.len() == 2;
+    // .listdata.json and .md files only inside a list directory, which may be a
+    // sublist up to MAX_LIST_DEPTH levels down
+    if filename == ".listdata.json" || filename.ends_with(".md") {
+        return (2..=MAX_LIST_DEPTH + 2).contains(&parts.len());
     }
     false
 }
@@ -389,17 +438,25 @@ fn is_syncable(path: &str) -> bool {
 /// Scan local workspace files and compute checksums.
 pub fn scan_local_files(workspace_path: &Path) -> Result<Vec<LocalFileInfo>> {
     let mut files = Vec::new();
-    scan_dir_recursive(workspace_path, workspace_path, &mut files)?;
+    scan_dir_recursive(workspace_path, workspace_path, None, &mut files)?;
+    Ok(files)
+}
+
+/// Like `scan_local_files`, but only files last modified at or after `cutoff`. Older
+/// files are skipped before being read, so they cost no checksum.
+pub fn scan_local_files_since(workspace_path: &Path, cutoff: DateTime<Utc>) -> Result<Vec<LocalFileInfo>> {
+    let mut files = Vec::new();
+    scan_dir_recursive(workspace_path, workspace_path, Some(cutoff), &mut files)?;
     Ok(files)
 }
 
-fn scan_dir_recursive(root: &Path, dir: &Path, files: &mut Vec<LocalFileInfo>) -> Result<()> {
+fn scan_dir_recursive(root: &Path, dir: &Path, cutoff: Option<DateTime<Utc>>, files: &mut Vec<LocalFileInfo>) -> Result<()> {
     let entries = std::fs::read_dir(dir)?;
     for entry in entries {
         let entry = entry?;
         let path = entry.path();
         let relative = path.strip_prefix(root)
-            .map_err(|e| Error::Sync(e.to_string()))?
+            .map_err(|e| Error::InvalidPath(e.to_string()))?
             .to_string_lossy()
             .replace('\\', "/");
 
@@ -409,15 +466,16 @@ fn scan_dir_recursive(root: &Path, dir: &Path, files: &mut Vec<LocalFileInfo>) -
         }
 
         if path.is_dir() {
-            scan_dir_recursive(root, &path, files)?;
+            scan_dir_recursive(root, &path, cutoff, files)?;
         } else if is_syncable(&relative) {
-            let data = std::fs::read(&path)?;
             let metadata = std::fs::metadata(&path)?;
-            let modified = metadata.modified().ok()
-                .map(|t| {
-                    let dt: DateTime<Utc> = t.into();
-                    dt.to_rfc3339()
-                });
+            let modified: Option<DateTime<Utc>> = metadata.modified().ok().map(Into::into);
+            // Without an mtime a file can't be ruled out, so it is kept
+            if cutoff.zip(modified).is_some_and(|(cutoff, modified)| modified < cutoff) {
+                continue;
+            }
+            let data = std::fs::read(&path)?;
+            let modified = modified.map(|dt| dt.to_rfc3339());
 
             files.push(LocalFileInfo {
                 path: relative,
@@ -460,6 +518,25 @@ fn scan_remote_files<'a>(client: &'a WebDavClient, base_path: &'a str) -> std::p
     })
 }
 
+/// Compiled `sync_ignore` glob patterns.
+struct IgnoreSet {
+    patterns: Vec<glob::Pattern>,
+}
+
+impl IgnoreSet {
+    fn new(patterns: &[String]) -> Result<Self> {
+        let patterns = patterns.iter()
+            .map(|p| glob::Pattern::new(p)
+                .map_err(|e| Error::InvalidData(format!("Invalid sync_ignore pattern '{}': {}", p, e))))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { patterns })
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        self.patterns.iter().any(|p| p.matches(path))
+    }
+}
+
 // --- Sync State I/O ---
 
 impl SyncState {
@@ -474,10 +551,14 @@ impl SyncState {
         }
     }
 
+    /// Write the state via a temporary file and rename, since it is saved after every upload
+    /// and a push killed mid-write must not leave it truncated.
     pub fn save(&self, workspace_path: &Path) -> Result<()> {
         let state_path = workspace_path.join(".syncstate.json");
+        let tmp_path = workspace_path.join(".syncstate.json.tmp");
         let content = serde_json::to_string_pretty(self)?;
-        std::fs::write(&state_path, content)?;
+        std::fs::write(&tmp_path, content)?;
+        std::fs::rename(&tmp_path, &state_path)?;
         Ok(())
     }
 
@@ -498,40 +579,91 @@ impl SyncState {
 
 // --- Sync Executor ---
 
+/// Number of uploads run at once when the workspace doesn't set `sync_concurrency`.
+pub const DEFAULT_UPLOAD_CONCURRENCY: usize = 4;
+
 /// Callback type for sync progress reporting.
 pub type ProgressCallback = Box<dyn Fn(&str) + Send + Sync>;
 
-/// Execute a full sync between a local workspace and a remote WebDAV server.
+/// Remote folder (below the configured WebDAV URL) that workspaces sync into.
+pub const REMOTE_FOLDER: &str = "Onyx";
+
+/// Execute a full sync between a local workspace and a remote WebDAV server. `defaults`
+/// are the global workspace settings (`AppConfig::defaults`) that the workspace's own
+/// [`WorkspaceSettings::FILE_NAME`] is merged over, e.g. for `sync_ignore` and
+/// `sync_concurrency`.
 pub async fn sync_workspace(
     workspace_path: &Path,
+    defaults: &WorkspaceSettings,
     webdav_url: &str,
     username: &str,
     password: &str,
     mode: SyncMode,
     on_progress: Option<ProgressCallback>,
 ) -> Result<SyncResult> {
+    let client = WebDavClient::new(webdav_url, username, password)?;
+    sync_workspace_with(workspace_path, defaults, &client, mode, on_progress).await
+}
+
+/// Like `sync_workspace`, but reuses an existing client pointed at the WebDAV root, e.g. one
+/// that already ran `test_connection`.
+pub async fn sync_workspace_with(
+    workspace_path: &Path,
+    defaults: &WorkspaceSettings,
+    client: &WebDavClient,
+    mode: SyncMode,
+    on_progress: Option<ProgressCallback>,
+) -> Result<SyncResult> {
+    with_timeout(sync_workspace_inner(workspace_path, defaults, client, mode, None, on_progress)).await
+}
+
+/// Push only the files modified at or after `since`, a fast path for "I just edited a few
+/// tasks": unchanged files are neither read nor compared. Local deletions aren't noticed
+/// this way and wait for the next full push or sync.
+pub async fn push_changed_since(
+    workspace_path: &Path,
+    defaults: &WorkspaceSettings,
+    client: &WebDavClient,
+    since: DateTime<Utc>,
+    on_progress: Option<ProgressCallback>,
+) -> Result<SyncResult> {
+    with_timeout(sync_workspace_inner(workspace_path, defaults, client, SyncMode::Push, Some(since), on_progress)).await
+}
+
+async fn with_timeout(sync: impl Future<Output = Result<SyncResult>>) -> Result<SyncResult> {
     // Wrap entire sync in a hard timeout — reqwest's built-in timeout
     // doesn't reliably fire on Windows native TLS when the server is unreachable.
-    match tokio::time::timeout(
-        crate::webdav::REQUEST_TIMEOUT * 2,
-        sync_workspace_inner(workspace_path, webdav_url, username, password, mode, on_progress),
-    ).await {
+    match tokio::time::timeout(crate::webdav::REQUEST_TIMEOUT * 2, sync).await {
         Ok(result) => result,
         Err(_) => Err(Error::WebDav("Sync timed out — server may be unreachable".into())),
     }
 }
 
+/// Narrow `remote_files` and the sync state to the paths in `local_files`, so the diff
+/// only covers those files. Used when `local_files` is a partial scan: left as is, every
+/// file missing from it would look deleted locally.
+fn only_scanned(local_files: &[LocalFileInfo], remote_files: &mut Vec<RemoteFileSnapshot>, sync_state: &SyncState) -> SyncState {
+    let scanned: HashSet<&str> = local_files.iter().map(|f| f.path.as_str()).collect();
+    remote_files.retain(|f| scanned.contains(f.path.as_str()));
+    SyncState {
+        last_sync: sync_state.last_sync,
+        files: sync_state.files.iter()
+            .filter(|(path, _)| scanned.contains(path.as_str()))
+            .map(|(path, entry)| (path.clone(), entry.clone()))
+            .collect(),
+    }
+}
+
 async fn sync_workspace_inner(
     workspace_path: &Path,
-    webdav_url: &str,
-    username: &str,
-    password: &str,
+    defaults: &WorkspaceSettings,
+    client: &WebDavClient,
     mode: SyncMode,
+    since: Option<DateTime<Utc>>,
     on_progress: Option<ProgressCallback>,
 ) -> Result<SyncResult> {
     // Sync into an "Onyx" subfolder so we don't scan the user's entire cloud storage
-    let sync_url = format!("{}/Onyx", webdav_url.trim_end_matches('/'));
-    let client = WebDavClient::new(&sync_url, username, password)?;
+    let client = client.subdir(REMOTE_FOLDER);
     let mut sync_state = SyncState::load(workspace_path);
     let queue = OfflineQueue::load(workspace_path);
     let mut result = SyncResult::default();
@@ -546,12 +678,25 @@ async fn sync_workspace_inner(
     client.create_dir("").await.ok();
     client.test_connection().await?;
 
+    // Paths matching the workspace's sync_ignore patterns (or the global ones, when it
+    // sets none) are left alone on both sides
+    let settings = WorkspaceSettings::load(workspace_path)?.merged_over(defaults);
+    let ignore = IgnoreSet::new(&settings.sync_ignore)?;
+    let concurrency = settings.sync_concurrency.unwrap_or(DEFAULT_UPLOAD_CONCURRENCY).max(1);
+
     // Scan local files
-    let local_files = scan_local_files(workspace_path)?;
+    let mut local_files = match since {
+        Some(cutoff) => scan_local_files_since(workspace_path, cutoff)?,
+        None => scan_local_files(workspace_path)?,
+    };
+    local_files.retain(|f| !ignore.matches(&f.path));
 
     // Scan remote files
-    let remote_files = match scan_remote_files(&client, "").await {
-        Ok(files) => files,
+    let mut remote_files = match scan_remote_files(&client, "").await {
+        Ok(mut files) => {
+            files.retain(|f| !ignore.matches(&f.path));
+            files
+        }
         Err(e) => {
             // Network error during scan: save what we can and return
             result.errors.push(format!("Failed to scan remote: {}", e));
@@ -560,7 +705,12 @@ async fn sync_workspace_inner(
     };
 
     // Compute actions from three-way diff
-    let fresh_actions = compute_sync_actions(&local_files, &remote_files, &sync_state);
+    let fresh_actions = if since.is_some() {
+        let recent_state = only_scanned(&local_files, &mut remote_files, &sync_state);
+        compute_sync_actions(&local_files, &remote_files, &recent_state)
+    } else {
+        compute_sync_actions(&local_files, &remote_files, &sync_state)
+    };
 
     // Merge with offline queue
     let all_actions = queue.merge_with_actions(fresh_actions);
@@ -574,27 +724,49 @@ async fn sync_workspace_inner(
 
     // Execute actions, collecting failures for the queue
     let mut failed_actions = Vec::new();
+    let (uploads, others): (Vec<SyncAction>, Vec<SyncAction>) = actions.into_iter()
+        .partition(|a| matches!(a, SyncAction::Upload { .. } | SyncAction::ConflictLocalWins { .. }));
+
+    // Create remote folders up front, one at a time, so concurrent uploads into the same
+    // list don't race each other's MKCOLs
+    let mut dir_errors: HashMap<String, String> = HashMap::new();
+    for parent in uploads.iter().filter_map(|a| path_parent(a.path())).collect::<BTreeSet<_>>() {
+        if let Err(e) = client.ensure_dir(parent).await {
+            dir_errors.insert(parent.to_string(), e.to_string());
+        }
+    }
 
-    for action in &actions {
-        match execute_action(&client, workspace_path, action, &mut sync_state, &report).await {
-            Ok(()) => {
-                match action {
-                    SyncAction::Upload { .. } | SyncAction::ConflictLocalWins { .. } => result.uploaded += 1,
-                    SyncAction::Download { .. } | SyncAction::ConflictRemoteWins { .. } => result.downloaded += 1,
-                    SyncAction::DeleteLocal { .. } => result.deleted_local += 1,
-                    SyncAction::DeleteRemote { .. } => result.deleted_remote += 1,
-                }
+    let mut outcomes = run_uploads(workspace_path, &mut sync_state, uploads, concurrency, |action| {
+        let dir_error = path_parent(action.path()).and_then(|p| dir_errors.get(p)).cloned();
+        let (client, report) = (&client, &report);
+        async move {
+            match dir_error {
+                Some(e) => Err(Error::WebDav(e)),
+                None => upload_file(client, workspace_path, &action, report).await,
             }
+        }
+    }).await?;
+    // Report in path order so the result doesn't depend on which upload finished first
+    outcomes.sort_by(|(a, _), (b, _)| a.path().cmp(b.path()));
+    for (action, outcome) in outcomes {
+        match outcome {
+            Ok(bytes) => result.record_success(&action, bytes),
             Err(e) => {
                 let msg = format!("Failed {}: {}", action.path(), e);
                 report(&format!("  ! {}", msg));
-                result.errors.push(msg);
-                if matches!(action,
-                    SyncAction::Upload { .. } | SyncAction::Download { .. }
-                    | SyncAction::ConflictLocalWins { .. } | SyncAction::ConflictRemoteWins { .. }
-                ) {
-                    result.conflicts += 1;
-                }
+                result.record_failure(&action, msg);
+                failed_actions.push(action);
+            }
+        }
+    }
+
+    for action in &others {
+        match execute_action(&client, workspace_path, action, &mut sync_state, &report).await {
+            Ok(bytes) => result.record_success(action, bytes),
+            Err(e) => {
+                let msg = format!("Failed {}: {}", action.path(), e);
+                report(&format!("  ! {}", msg));
+                result.record_failure(action, msg);
                 failed_actions.push(action.clone());
             }
         }
@@ -606,62 +778,129 @@ async fn sync_workspace_inner(
     };
     new_queue.save(workspace_path)?;
 
-    // Update sync state timestamp
-    sync_state.last_sync = Some(Utc::now());
+    // Update sync state timestamp. A partial push only vouches for the files it sent, so
+    // it moves the timestamp only when every one of them made it
+    if since.is_none() || result.errors.is_empty() {
+        sync_state.last_sync = Some(Utc::now());
+    }
     sync_state.save(workspace_path)?;
 
     Ok(result)
 }
 
-/// Execute a single sync action.
+/// Local state of a file after a successful upload, recorded into the sync state.
+struct UploadedFile {
+    checksum: String,
+    modified_at: Option<String>,
+    size: u64,
+}
+
+impl UploadedFile {
+    fn record(&self, path: &str, sync_state: &mut SyncState) {
+        sync_state.record_file(path, &self.checksum, self.modified_at.as_deref(), self.size);
+    }
+}
+
+/// Run `upload` over `actions` with at most `concurrency` in flight. Every action runs to
+/// completion and gets an outcome, whether or not the others fail; outcomes come back in
+/// completion order.
+fn upload_concurrently<F, Fut>(
+    actions: Vec<SyncAction>,
+    concurrency: usize,
+    upload: F,
+) -> impl Stream<Item = (SyncAction, Result<UploadedFile>)>
+where
+    F: Fn(SyncAction) -> Fut,
+    Fut: Future<Output = Result<UploadedFile>>,
+{
+    stream::iter(actions)
+        .map(move |action| {
+            let fut = upload(action.clone());
+            async move { (action, fut.await) }
+        })
+        .buffer_unordered(concurrency.max(1))
+}
+
+/// Upload `actions` concurrently, recording each success in `sync_state` and saving it as
+/// soon as that upload finishes. A push that dies halfway (timeout, crash, Ctrl-C) then
+/// leaves the finished files in `.syncstate.json`, and the next run only uploads the rest.
+/// Successful outcomes carry the number of bytes uploaded.
+async fn run_uploads<F, Fut>(
+    workspace_path: &Path,
+    sync_state: &mut SyncState,
+    actions: Vec<SyncAction>,
+    concurrency: usize,
+    upload: F,
+) -> Result<Vec<(SyncAction, Result<u64>)>>
+where
+    F: Fn(SyncAction) -> Fut,
+    Fut: Future<Output = Result<UploadedFile>>,
+{
+    let mut outcomes = Vec::new();
+    let mut pending = std::pin::pin!(upload_concurrently(actions, concurrency, upload));
+    while let Some((action, outcome)) = pending.next().await {
+        let outcome = match outcome {
+            Ok(uploaded) => {
+                uploaded.record(action.path(), sync_state);
+                sync_state.save(workspace_path)?;
+                Ok(uploaded.size)
+            }
+            Err(e) => Err(e),
+        };
+        outcomes.push((action, outcome));
+    }
+    Ok(outcomes)
+}
+
+/// Upload the local copy of an `Upload` or `ConflictLocalWins` path. The parent folder must
+/// already exist remotely.
+async fn upload_file(
+    client: &WebDavClient,
+    workspace_path: &Path,
+    action: &SyncAction,
+    report: &(dyn Fn(&str) + Send + Sync),
+) -> Result<UploadedFile> {
+    let path = action.path();
+    let local_path = workspace_path.join(path.replace('/', std::path::MAIN_SEPARATOR_STR));
+    let data = std::fs::read(&local_path)?;
+    let checksum = compute_checksum(&data);
+    let size = data.len() as u64;
+
+    if matches!(action, SyncAction::ConflictLocalWins { .. }) {
+        report(&format!("  ^ Conflict: uploading local version of {} ({})", path, format_bytes(size)));
+    } else {
+        report(&format!("  ^ Uploading {} ({})", path, format_bytes(size)));
+    }
+    client.put_file(path, data).await?;
+
+    // Record in sync state using local file metadata
+    let modified_at = std::fs::metadata(&local_path).ok()
+        .and_then(|m| m.modified().ok())
+        .map(|t| { let dt: DateTime<Utc> = t.into(); dt.to_rfc3339() });
+    Ok(UploadedFile { checksum, modified_at, size })
+}
+
+/// Execute a single sync action. Returns the number of bytes transferred.
 async fn execute_action(
     client: &WebDavClient,
     workspace_path: &Path,
     action: &SyncAction,
     sync_state: &mut SyncState,
     report: &(dyn Fn(&str) + Send + Sync),
-) -> Result<()> {
+) -> Result<u64> {
     match action {
-        SyncAction::Upload { path } => {
-            let local_path = workspace_path.join(path.replace('/', std::path::MAIN_SEPARATOR_STR));
-            let data = std::fs::read(&local_path)?;
-            let checksum = compute_checksum(&data);
-
+        SyncAction::Upload { path } | SyncAction::ConflictLocalWins { path } => {
             if let Some(parent) = path_parent(path) {
                 client.ensure_dir(parent).await?;
             }
-
-            report(&format!("  ^ Uploading {}", path));
-            client.put_file(path, data.clone()).await?;
-
-            // Record in sync state using local file metadata
-            let modified = std::fs::metadata(&local_path).ok()
-                .and_then(|m| m.modified().ok())
-                .map(|t| { let dt: DateTime<Utc> = t.into(); dt.to_rfc3339() });
-            sync_state.record_file(path, &checksum, modified.as_deref(), data.len() as u64);
-        }
-
-        SyncAction::ConflictLocalWins { path } => {
-            let local_path = workspace_path.join(path.replace('/', std::path::MAIN_SEPARATOR_STR));
-            let data = std::fs::read(&local_path)?;
-            let checksum = compute_checksum(&data);
-
-            if let Some(parent) = path_parent(path) {
-                client.ensure_dir(parent).await?;
-            }
-
-            report(&format!("  ^ Conflict: uploading local version of {}", path));
-            client.put_file(path, data.clone()).await?;
-
-            let modified = std::fs::metadata(&local_path).ok()
-                .and_then(|m| m.modified().ok())
-                .map(|t| { let dt: DateTime<Utc> = t.into(); dt.to_rfc3339() });
-            sync_state.record_file(path, &checksum, modified.as_deref(), data.len() as u64);
+            let uploaded = upload_file(client, workspace_path, action, report).await?;
+            uploaded.record(path, sync_state);
+            Ok(uploaded.size)
         }
 
         SyncAction::Download { path } => {
-            report(&format!("  v Downloading {}", path));
             let data = client.get_file(path).await?;
+            report(&format!("  v Downloaded {} ({})", path, format_bytes(data.len() as u64)));
             let checksum = compute_checksum(&data);
 
             let local_path = workspace_path.join(path.replace('/', std::path::MAIN_SEPARATOR_STR));
@@ -675,18 +914,19 @@ async fn execute_action(
                 .and_then(|m| m.modified().ok())
                 .map(|t| { let dt: DateTime<Utc> = t.into(); dt.to_rfc3339() });
             sync_state.record_file(path, &checksum, modified.as_deref(), data.len() as u64);
+            Ok(data.len() as u64)
         }
 
         SyncAction::ConflictRemoteWins { path } => {
             let local_path = workspace_path.join(path.replace('/', std::path::MAIN_SEPARATOR_STR));
             // Back up local version before overwriting with remote
             if local_path.exists() {
-                let backup_path = local_path.with_extension("conflict-backup");
+                let backup_path = local_path.with_extension(CONFLICT_BACKUP_EXTENSION);
                 let _ = std::fs::copy(&local_path, &backup_path);
                 report(&format!("  ! Backed up local version to {}", backup_path.display()));
             }
-            report(&format!("  v Conflict: downloading remote version of {}", path));
             let data = client.get_file(path).await?;
+            report(&format!("  v Conflict: downloaded remote version of {} ({})", path, format_bytes(data.len() as u64)));
             let checksum = compute_checksum(&data);
 
             if let Some(parent) = local_path.parent() {
@@ -698,6 +938,7 @@ async fn execute_action(
                 .and_then(|m| m.modified().ok())
                 .map(|t| { let dt: DateTime<Utc> = t.into(); dt.to_rfc3339() });
             sync_state.record_file(path, &checksum, modified.as_deref(), data.len() as u64);
+            Ok(data.len() as u64)
         }
 
         SyncAction::DeleteLocal { path } => {
@@ -707,15 +948,31 @@ async fn execute_action(
                 std::fs::remove_file(&local_path)?;
             }
             sync_state.remove_file(path);
+            Ok(0)
         }
 
         SyncAction::DeleteRemote { path } => {
             report(&format!("  x Deleting remote {}", path));
             client.delete_file(path).await?;
             sync_state.remove_file(path);
+            Ok(0)
         }
     }
-    Ok(())
+}
+
+/// A byte count for people: "512 B", "4.2 KiB", "1.3 MiB", "2.0 GiB".
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
 }
 
 /// Get the parent path of a sync path (e.g., "My Tasks/file.md" -> "My Tasks").
@@ -723,6 +980,153 @@ fn path_parent(path: &str) -> Option<&str> {
     path.rfind('/').map(|i| &path[..i])
 }
 
+// --- Remote health check ---
+
+/// Name of the scratch file `check_remote` round-trips through the sync folder.
+const CHECK_FILE_NAME: &str = ".onyx-check.tmp";
+const CHECK_FILE_CONTENT: &[u8] = b"onyx sync check\n";
+
+/// Outcome of one `check_remote` step.
+#[derive(Debug)]
+pub struct CheckStep {
+    pub description: String,
+    pub outcome: std::result::Result<(), String>,
+}
+
+impl CheckStep {
+    fn new(description: &str, outcome: Result<()>) -> Self {
+        Self { description: description.to_string(), outcome: outcome.map_err(|e| e.to_string()) }
+    }
+}
+
+/// Probe a WebDAV server end to end with the same requests sync makes: connect, make sure
+/// the sync folder exists, then upload, download and delete a scratch file in it. Stops
+/// at the first failed step, except that an uploaded scratch file is always cleaned up.
+pub async fn check_remote(client: &WebDavClient) -> Vec<CheckStep> {
+    let mut steps = Vec::new();
+
+    let connected = client.test_connection().await;
+    let ok = connected.is_ok();
+    steps.push(CheckStep::new("Server reachable and credentials accepted", connected));
+    if !ok {
+        return steps;
+    }
+
+    let folder = async {
+        client.create_dir(REMOTE_FOLDER).await?;
+        client.subdir(REMOTE_FOLDER).list_files("").await.map(|_| ())
+    }.await;
+    let ok = folder.is_ok();
+    steps.push(CheckStep::new(&format!("Sync folder '{}' exists", REMOTE_FOLDER), folder));
+    if !ok {
+        return steps;
+    }
+
+    let remote = client.subdir(REMOTE_FOLDER);
+    let uploaded = remote.put_file(CHECK_FILE_NAME, CHECK_FILE_CONTENT.to_vec()).await;
+    let ok = uploaded.is_ok();
+    steps.push(CheckStep::new("Test file uploads", uploaded));
+    if !ok {
+        return steps;
+    }
+
+    let downloaded = remote.get_file(CHECK_FILE_NAME).await.and_then(|data| {
+        if data == CHECK_FILE_CONTENT {
+            Ok(())
+        } else {
+            Err(Error::WebDav(format!("downloaded {} bytes that don't match the upload", data.len())))
+        }
+    });
+    steps.push(CheckStep::new("Test file downloads intact", downloaded));
+    steps.push(CheckStep::new("Test file removed", remote.delete_file(CHECK_FILE_NAME).await));
+    steps
+}
+
+// --- Conflict backups ---
+
+/// Extension of the copy a local file is saved to when the remote version wins a conflict
+/// (`Buy milk.md` becomes `Buy milk.conflict-backup`).
+pub const CONFLICT_BACKUP_EXTENSION: &str = "conflict-backup";
+
+/// A local version set aside by a conflict the remote side won.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictFile {
+    /// The synced file, now holding the remote version (relative to the workspace root).
+    pub path: String,
+    /// The saved local version (relative to the workspace root).
+    pub backup_path: String,
+    /// When the backup was written.
+    pub backed_up_at: Option<DateTime<Utc>>,
+}
+
+/// Which version `resolve_conflict` keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictSide {
+    /// Restore the backed-up local version; the next sync uploads it.
+    Local,
+    /// Keep the downloaded remote version and discard the backup.
+    Remote,
+}
+
+/// Find every conflict backup in a workspace, sorted by path.
+pub fn list_conflicts(workspace_path: &Path) -> Result<Vec<ConflictFile>> {
+    let mut conflicts = Vec::new();
+    scan_conflicts(workspace_path, workspace_path, &mut conflicts)?;
+    conflicts.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(conflicts)
+}
+
+fn scan_conflicts(root: &Path, dir: &Path, conflicts: &mut Vec<ConflictFile>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            scan_conflicts(root, &path, conflicts)?;
+            continue;
+        }
+        if path.extension().and_then(|e| e.to_str()) != Some(CONFLICT_BACKUP_EXTENSION) {
+            continue;
+        }
+        // The backup replaced the synced file's extension; whichever of them exists is the one
+        let Some(original) = ["md", "json"].iter()
+            .map(|ext| path.with_extension(ext))
+            .find(|p| p.is_file())
+        else {
+            continue;
+        };
+        let relative = |p: &Path| -> Result<String> {
+            Ok(p.strip_prefix(root)
+                .map_err(|e| Error::InvalidPath(e.to_string()))?
+                .to_string_lossy()
+                .replace('\\', "/"))
+        };
+        conflicts.push(ConflictFile {
+            path: relative(&original)?,
+            backup_path: relative(&path)?,
+            backed_up_at: std::fs::metadata(&path).ok()
+                .and_then(|m| m.modified().ok())
+                .map(DateTime::<Utc>::from),
+        });
+    }
+    Ok(())
+}
+
+/// Settle a conflict by keeping one version and removing the backup. `file` may name either
+/// the synced file or its backup, relative to the workspace root.
+pub fn resolve_conflict(workspace_path: &Path, file: &str, keep: ConflictSide) -> Result<ConflictFile> {
+    let file = file.replace('\\', "/");
+    let conflict = list_conflicts(workspace_path)?
+        .into_iter()
+        .find(|c| c.path == file || c.backup_path == file)
+        .ok_or_else(|| Error::NotFound(format!("No conflict backup for {}", file)))?;
+
+    let local = |p: &str| workspace_path.join(p.replace('/', std::path::MAIN_SEPARATOR_STR));
+    match keep {
+        ConflictSide::Local => std::fs::rename(local(&conflict.backup_path), local(&conflict.path))?,
+        ConflictSide::Remote => std::fs::remove_file(local(&conflict.backup_path))?,
+    }
+    Ok(conflict)
+}
+
 /// Get sync status information for display.
 pub fn get_sync_status(workspace_path: &Path) -> Result<SyncStatusInfo> {
     let sync_state = SyncState::load(workspace_path);
@@ -768,6 +1172,7 @@ pub struct SyncStatusInfo {
 mod tests {
     use super::*;
     use tempfile::TempDir;
+    use futures::FutureExt;
 
     // --- compute_sync_actions tests ---
 
@@ -994,8 +1399,7 @@ mod tests {
     #[test]
     fn test_sync_state_save_load_roundtrip() {
         let temp_dir = TempDir::new().unwrap();
-        let mut state = SyncState::default();
-        state.last_sync = Some(Utc::now());
+        let mut state = SyncState { last_sync: Some(Utc::now()), ..Default::default() };
         state.record_file("test.md", "abc123", Some("2026-01-01T00:00:00Z"), 42);
 
         state.save(temp_dir.path()).unwrap();
@@ -1109,6 +1513,15 @@ mod tests {
 
     // --- File scanning ---
 
+    #[test]
+    fn test_ignore_set_matches_globs() {
+        let ignore = IgnoreSet::new(&["Scratch/*".to_string(), "*.draft.md".to_string()]).unwrap();
+        assert!(ignore.matches("Scratch/idea.md"));
+        assert!(ignore.matches("Work/plan.draft.md"));
+        assert!(!ignore.matches("Work/plan.md"));
+        assert!(IgnoreSet::new(&["[".to_string()]).is_err());
+    }
+
     #[test]
     fn test_is_syncable() {
         // .md files must be inside a list dir (depth 2)
@@ -1120,11 +1533,18 @@ mod tests {
         // .metadata.json only at root (depth 1)
         assert!(is_syncable(".metadata.json"));
         assert!(!is_syncable("My Tasks/.metadata.json")); // nested not valid
+        // .workspace.json only at root (depth 1)
+        assert!(is_syncable(".workspace.json"));
+        assert!(!is_syncable("My Tasks/.workspace.json"));
         // Non-syncable
         assert!(!is_syncable(".syncstate.json"));
         assert!(!is_syncable("random.txt"));
         assert!(!is_syncable("image.png"));
-        assert!(!is_syncable("a/b/c/deep.md")); // too deep
+        // Sublists
+        assert!(is_syncable("Work/Q3/plan.md"));
+        assert!(is_syncable("Work/Q3/.listdata.json"));
+        assert!(is_syncable("a/b/c/deep.md"));
+        assert!(!is_syncable("a/b/c/d/deeper.md")); // too deep
     }
 
     #[test]
@@ -1153,6 +1573,42 @@ mod tests {
         assert!(!files.iter().any(|f| f.path.contains(".syncstate.json")));
     }
 
+    #[test]
+    fn test_scan_local_files_since_skips_older_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("My Tasks")).unwrap();
+        std::fs::write(root.join("My Tasks").join("old.md"), "# Old").unwrap();
+        std::fs::write(root.join("My Tasks").join("new.md"), "# New").unwrap();
+        let two_hours_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(2 * 3600);
+        std::fs::File::options().write(true).open(root.join("My Tasks").join("old.md")).unwrap()
+            .set_modified(two_hours_ago).unwrap();
+
+        let cutoff = Utc::now() - chrono::Duration::hours(1);
+        let files = scan_local_files_since(root, cutoff).unwrap();
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["My Tasks/new.md"]);
+    }
+
+    #[test]
+    fn test_partial_scan_does_not_look_like_deletions() {
+        // "old.md" was skipped by a --since scan; "new.md" changed since the last sync
+        let local = vec![make_local("My Tasks/new.md", "new")];
+        let mut remote = vec![make_remote("My Tasks/old.md"), make_remote("My Tasks/new.md")];
+        let mut state = SyncState::default();
+        state.files.insert("My Tasks/old.md".into(), make_base("old"));
+        state.files.insert("My Tasks/new.md".into(), make_base("before"));
+
+        // Without narrowing, the skipped file would be deleted remotely
+        let full = compute_sync_actions(&local, &remote, &state);
+        assert!(full.iter().any(|a| matches!(a, SyncAction::DeleteRemote { .. })));
+
+        let recent = only_scanned(&local, &mut remote, &state);
+        let actions = compute_sync_actions(&local, &remote, &recent);
+        assert_eq!(actions.len(), 1);
+        assert!(matches!(&actions[0], SyncAction::Upload { path } if path == "My Tasks/new.md"));
+    }
+
     // --- Sync status ---
 
     #[test]
@@ -1168,6 +1624,40 @@ mod tests {
         assert_eq!(status.queued_operations, 0);
     }
 
+    // --- Sync result ---
+
+    #[test]
+    fn test_sync_result_json_report() {
+        let mut result = SyncResult::default();
+        result.record_success(&SyncAction::Upload { path: "Work/a.md".into() }, 120);
+        result.record_success(&SyncAction::ConflictRemoteWins { path: "Work/b.md".into() }, 80);
+        result.record_success(&SyncAction::DeleteRemote { path: "Work/c.md".into() }, 0);
+        result.record_failure(&SyncAction::Download { path: "Work/d.md".into() }, "Failed Work/d.md: 500".into());
+        result.record_failure(&SyncAction::DeleteLocal { path: "Work/e.md".into() }, "Failed Work/e.md: busy".into());
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["uploaded"], 1);
+        assert_eq!(json["bytes_uploaded"], 120);
+        assert_eq!(json["bytes_downloaded"], 80);
+        assert_eq!(json["uploaded_files"], serde_json::json!(["Work/a.md"]));
+        assert_eq!(json["downloaded_files"], serde_json::json!(["Work/b.md"]));
+        assert_eq!(json["deleted_remote_files"], serde_json::json!(["Work/c.md"]));
+        assert_eq!(json["deleted_local"], 0);
+        assert_eq!(json["conflicts"], 1);
+        assert_eq!(json["conflict_files"], serde_json::json!(["Work/d.md"]));
+        assert_eq!(json["errors"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KiB");
+        assert_eq!(format_bytes(1_363_149), "1.3 MiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GiB");
+        assert_eq!(format_bytes(5000 * 1024 * 1024 * 1024), "5000.0 GiB");
+    }
+
     // --- Timestamp parsing ---
 
     #[test]
@@ -1212,4 +1702,254 @@ mod tests {
         assert_eq!(path_parent("file.md"), None);
         assert_eq!(path_parent("a/b/c.md"), Some("a/b"));
     }
+
+    // --- upload_concurrently ---
+
+    #[tokio::test]
+    async fn test_upload_concurrently_bounds_in_flight_and_keeps_all_outcomes() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let in_flight = AtomicUsize::new(0);
+        let max_in_flight = AtomicUsize::new(0);
+        let actions: Vec<SyncAction> = (0..20)
+            .map(|i| SyncAction::Upload { path: format!("List/task-{:02}.md", i) })
+            .collect();
+
+        // Mock client: tracks requests in flight and fails one upload
+        let outcomes: Vec<_> = upload_concurrently(actions, 3, |action| {
+            let (in_flight, max_in_flight) = (&in_flight, &max_in_flight);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                if action.path() == "List/task-07.md" {
+                    return Err(Error::WebDav("PUT failed with status 507".into()));
+                }
+                Ok(UploadedFile { checksum: action.path().to_string(), modified_at: None, size: 1 })
+            }
+        }).collect().await;
+
+        let max = max_in_flight.load(Ordering::SeqCst);
+        assert!(max <= 3, "{} uploads in flight", max);
+        assert!(max > 1, "uploads never overlapped");
+        assert_eq!(outcomes.len(), 20);
+        let failed: Vec<&str> = outcomes.iter()
+            .filter(|(_, r)| r.is_err())
+            .map(|(a, _)| a.path())
+            .collect();
+        assert_eq!(failed, vec!["List/task-07.md"]);
+        assert!(outcomes.iter()
+            .filter_map(|(a, r)| r.as_ref().ok().map(|u| (a, u)))
+            .all(|(a, u)| u.checksum == a.path()));
+    }
+
+    #[test]
+    fn test_interrupted_push_resumes_with_remaining_uploads() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir(root.join("List")).unwrap();
+        for i in 0..6 {
+            std::fs::write(root.join(format!("List/task-{}.md", i)), format!("task {}", i)).unwrap();
+        }
+        let local_files = scan_local_files(root).unwrap();
+        let push_actions = |state: &SyncState, remote: &[RemoteFileSnapshot]| -> Vec<SyncAction> {
+            compute_sync_actions(&local_files, remote, state).into_iter()
+                .filter(|a| matches!(a, SyncAction::Upload { .. }))
+                .collect()
+        };
+        let uploader = |action: SyncAction| async move {
+            // The server stops answering partway through the run
+            if action.path() >= "List/task-3.md" {
+                std::future::pending::<()>().await;
+            }
+            let data = std::fs::read(root.join(action.path())).unwrap();
+            Ok(UploadedFile { checksum: compute_checksum(&data), modified_at: None, size: data.len() as u64 })
+        };
+
+        let actions = push_actions(&SyncState::default(), &[]);
+        assert_eq!(actions.len(), 6);
+        let mut state = SyncState::default();
+        // One poll runs the uploads until the server hangs; dropping the run then stands in
+        // for the process being killed
+        let run = run_uploads(root, &mut state, actions, 1, uploader);
+        assert!(run.now_or_never().is_none());
+
+        // The next run sees the finished uploads in the saved state and only pushes the rest
+        let saved = SyncState::load(root);
+        assert_eq!(saved.files.len(), 3);
+        let remote: Vec<RemoteFileSnapshot> = saved.files.iter()
+            .map(|(path, entry)| RemoteFileSnapshot { path: path.clone(), last_modified: None, size: entry.size })
+            .collect();
+        let remaining: Vec<String> = push_actions(&saved, &remote).iter().map(|a| a.path().to_string()).collect();
+        assert_eq!(remaining, vec!["List/task-3.md", "List/task-4.md", "List/task-5.md"]);
+    }
+
+    // --- conflict backups ---
+
+    #[test]
+    fn test_list_and_resolve_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir(root.join("List")).unwrap();
+        std::fs::write(root.join("List/Task.md"), "remote").unwrap();
+        std::fs::write(root.join("List/Task.conflict-backup"), "local").unwrap();
+        std::fs::write(root.join("List/.listdata.json"), "{}").unwrap();
+        std::fs::write(root.join("List/.listdata.conflict-backup"), "{\"local\": true}").unwrap();
+        // A stray backup whose file is gone isn't a conflict anymore
+        std::fs::write(root.join("List/Gone.conflict-backup"), "old").unwrap();
+
+        let conflicts = list_conflicts(root).unwrap();
+        let paths: Vec<(&str, &str)> = conflicts.iter()
+            .map(|c| (c.path.as_str(), c.backup_path.as_str()))
+            .collect();
+        assert_eq!(paths, vec![
+            ("List/.listdata.json", "List/.listdata.conflict-backup"),
+            ("List/Task.md", "List/Task.conflict-backup"),
+        ]);
+
+        resolve_conflict(root, "List/Task.md", ConflictSide::Local).unwrap();
+        assert_eq!(std::fs::read_to_string(root.join("List/Task.md")).unwrap(), "local");
+        assert!(!root.join("List/Task.conflict-backup").exists());
+
+        resolve_conflict(root, "List/.listdata.conflict-backup", ConflictSide::Remote).unwrap();
+        assert_eq!(std::fs::read_to_string(root.join("List/.listdata.json")).unwrap(), "{}");
+        assert!(list_conflicts(root).unwrap().is_empty());
+
+        assert!(matches!(
+            resolve_conflict(root, "List/Task.md", ConflictSide::Local),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    // --- check_remote ---
+
+    #[tokio::test]
+    async fn test_check_remote_round_trip() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let server = MockServer::start().await;
+        Mock::given(method("PROPFIND")).and(path("/dav"))
+            .respond_with(ResponseTemplate::new(207)).mount(&server).await;
+        Mock::given(method("MKCOL")).and(path("/dav/Onyx"))
+            .respond_with(ResponseTemplate::new(405)).mount(&server).await;
+        Mock::given(method("PROPFIND")).and(path("/dav/Onyx"))
+            .respond_with(ResponseTemplate::new(207).set_body_string(
+                r#"<d:multistatus xmlns:d="DAV:"></d:multistatus>"#,
+            )).mount(&server).await;
+        Mock::given(method("PUT")).and(path("/dav/Onyx/.onyx-check.tmp"))
+            .respond_with(ResponseTemplate::new(201)).expect(1).mount(&server).await;
+        Mock::given(method("GET")).and(path("/dav/Onyx/.onyx-check.tmp"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(CHECK_FILE_CONTENT))
+            .mount(&server).await;
+        Mock::given(method("DELETE")).and(path("/dav/Onyx/.onyx-check.tmp"))
+            .respond_with(ResponseTemplate::new(204)).expect(1).mount(&server).await;
+
+        let client = WebDavClient::new_unchecked(&format!("{}/dav", server.uri()), "user", "pass");
+        let steps = check_remote(&client).await;
+        assert_eq!(steps.len(), 5);
+        assert!(steps.iter().all(|s| s.outcome.is_ok()), "{:?}", steps);
+    }
+
+    #[tokio::test]
+    async fn test_push_reports_bytes_uploaded() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+        use wiremock::matchers::method;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(root.join(".metadata.json"), "{}").unwrap();
+        std::fs::create_dir(root.join("List")).unwrap();
+        std::fs::write(root.join("List/a.md"), "a".repeat(1500)).unwrap();
+        std::fs::write(root.join("List/b.md"), "short").unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("MKCOL")).respond_with(ResponseTemplate::new(201)).mount(&server).await;
+        Mock::given(method("PROPFIND")).respond_with(ResponseTemplate::new(207).set_body_string(
+            r#"<d:multistatus xmlns:d="DAV:"></d:multistatus>"#,
+        )).mount(&server).await;
+        Mock::given(method("PUT")).respond_with(ResponseTemplate::new(201)).expect(3).mount(&server).await;
+
+        let client = WebDavClient::new_unchecked(&format!("{}/dav", server.uri()), "user", "pass");
+        let result = sync_workspace_with(root, &WorkspaceSettings::default(), &client, SyncMode::Push, None).await.unwrap();
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.uploaded, 3);
+        assert_eq!(result.bytes_uploaded, 2 + 1500 + 5);
+        assert_eq!(result.bytes_downloaded, 0);
+    }
+
+    #[tokio::test]
+    async fn test_push_skips_globally_ignored_files() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(root.join(".metadata.json"), "{}").unwrap();
+        std::fs::create_dir(root.join("List")).unwrap();
+        std::fs::write(root.join("List/a.md"), "a").unwrap();
+        std::fs::write(root.join("List/scratch.md"), "b").unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("MKCOL")).respond_with(ResponseTemplate::new(201)).mount(&server).await;
+        Mock::given(method("PROPFIND")).respond_with(ResponseTemplate::new(207).set_body_string(
+            r#"<d:multistatus xmlns:d="DAV:"></d:multistatus>"#,
+        )).mount(&server).await;
+        Mock::given(method("PUT")).and(path("/dav/Onyx/List/scratch.md"))
+            .respond_with(ResponseTemplate::new(201)).expect(0).mount(&server).await;
+        Mock::given(method("PUT")).respond_with(ResponseTemplate::new(201)).expect(2).mount(&server).await;
+
+        // The workspace has no settings file, so the global sync_ignore applies
+        let defaults = WorkspaceSettings { sync_ignore: vec!["**/scratch.md".to_string()], ..Default::default() };
+        let client = WebDavClient::new_unchecked(&format!("{}/dav", server.uri()), "user", "pass");
+        let result = sync_workspace_with(root, &defaults, &client, SyncMode::Push, None).await.unwrap();
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.uploaded, 2);
+    }
+
+    #[tokio::test]
+    async fn test_push_since_keeps_last_sync_when_a_file_fails() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir(root.join("List")).unwrap();
+        std::fs::write(root.join("List/a.md"), "a").unwrap();
+        std::fs::write(root.join("List/b.md"), "b").unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("MKCOL")).respond_with(ResponseTemplate::new(201)).mount(&server).await;
+        Mock::given(method("PROPFIND")).respond_with(ResponseTemplate::new(207).set_body_string(
+            r#"<d:multistatus xmlns:d="DAV:"></d:multistatus>"#,
+        )).mount(&server).await;
+        Mock::given(method("PUT")).and(path("/dav/Onyx/List/b.md"))
+            .respond_with(ResponseTemplate::new(507)).mount(&server).await;
+        Mock::given(method("PUT")).respond_with(ResponseTemplate::new(201)).mount(&server).await;
+
+        let client = WebDavClient::new_unchecked(&format!("{}/dav", server.uri()), "user", "pass");
+        let since = Utc::now() - chrono::Duration::hours(1);
+        let result = push_changed_since(root, &WorkspaceSettings::default(), &client, since, None).await.unwrap();
+        assert_eq!((result.uploaded, result.errors.len()), (1, 1));
+
+        let state = SyncState::load(root);
+        assert!(state.last_sync.is_none());
+        assert!(state.files.contains_key("List/a.md") && !state.files.contains_key("List/b.md"));
+    }
+
+    #[tokio::test]
+    async fn test_check_remote_stops_on_auth_failure() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+        use wiremock::matchers::method;
+
+        let server = MockServer::start().await;
+        Mock::given(method("PROPFIND")).respond_with(ResponseTemplate::new(401)).mount(&server).await;
+        Mock::given(method("PUT")).respond_with(ResponseTemplate::new(201)).expect(0).mount(&server).await;
+
+        let client = WebDavClient::new_unchecked(&server.uri(), "user", "wrong");
+        let steps = check_remote(&client).await;
+        assert_eq!(steps.len(), 1);
+        assert!(steps[0].outcome.as_ref().unwrap_err().contains("Authentication failed"));
+    }
 }
@@ -0,0 +1,147 @@
+use chrono::{DateTime, Days, NaiveDate, TimeZone, Utc};
+use crate::models::{Task, TaskStatus};
+
+/// Heading of one [`UpcomingGroup`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UpcomingDay {
+    /// Open tasks whose due date has already passed.
+    Overdue,
+    /// Tasks due on this calendar day.
+    Day(NaiveDate),
+}
+
+/// Tasks due on one day (or overdue), each paired with the title of its list.
+#[derive(Debug, Clone)]
+pub struct UpcomingGroup {
+    pub day: UpcomingDay,
+    pub tasks: Vec<(String, Task)>,
+}
+
+/// Group the open tasks among `tasks` that are due within `days` calendar days of `now`
+/// in `tz` (today counts as the first, so `days = 7` runs through six days from now) by
+/// the day they are due. Overdue tasks come first, under [`UpcomingDay::Overdue`]; days
+/// with nothing due are left out. Tasks in a group are ordered by due time, ties keeping
+/// their order in `tasks`.
+pub fn group_upcoming<Tz: TimeZone>(
+    tasks: Vec<(String, Task)>,
+    now: DateTime<Utc>,
+    days: u32,
+    tz: &Tz,
+) -> Vec<UpcomingGroup> {
+    let today = now.with_timezone(tz).date_naive();
+    let last_day = today.checked_add_days(Days::new(days.into())).unwrap_or(NaiveDate::MAX);
+
+    let mut dated: Vec<(UpcomingDay, DateTime<Utc>, String, Task)> = tasks.into_iter()
+        .filter(|(_, task)| task.status != TaskStatus::Completed)
+        .filter_map(|(list, task)| {
+            let due = task.due_date?;
+            let day = if due < now {
+                UpcomingDay::Overdue
+            } else {
+                let due_day = due.with_timezone(tz).date_naive();
+                if due_day >= last_day {
+                    return None;
+                }
+                UpcomingDay::Day(due_day)
+            };
+            Some((day, due, list, task))
+        })
+        .collect();
+    dated.sort_by_key(|(day, due, _, _)| (*day, *due));
+
+    let mut groups: Vec<UpcomingGroup> = Vec::new();
+    for (day, _, list, task) in dated {
+        match groups.last_mut() {
+            Some(group) if group.day == day => group.tasks.push((list, task)),
+            _ => groups.push(UpcomingGroup { day, tasks: vec![(list, task)] }),
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    // Wednesday 2026-06-03, 10:00 UTC
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, 3, 10, 0, 0).unwrap()
+    }
+
+    fn due(title: &str, day: u32, hour: u32) -> (String, Task) {
+        let at = Utc.with_ymd_and_hms(2026, 6, day, hour, 0, 0).unwrap();
+        ("Work".to_string(), Task::new(title.to_string()).with_due_date(at))
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 6, day).unwrap()
+    }
+
+    fn titles(groups: &[UpcomingGroup]) -> Vec<(UpcomingDay, Vec<&str>)> {
+        groups.iter()
+            .map(|g| (g.day, g.tasks.iter().map(|(_, t)| t.title.as_str()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_buckets_a_week_by_day() {
+        let tasks = vec![
+            due("Friday late", 5, 18),
+            due("Today later", 3, 15),
+            due("Friday early", 5, 8),
+            due("Next Tuesday", 9, 9),
+            due("Thursday", 4, 12),
+            due("Next Wednesday", 10, 9),
+        ];
+        let groups = group_upcoming(tasks, now(), 7, &Utc);
+        assert_eq!(titles(&groups), vec![
+            (UpcomingDay::Day(date(3)), vec!["Today later"]),
+            (UpcomingDay::Day(date(4)), vec!["Thursday"]),
+            // Saturday to Monday have nothing due and are skipped
+            (UpcomingDay::Day(date(5)), vec!["Friday early", "Friday late"]),
+            (UpcomingDay::Day(date(9)), vec!["Next Tuesday"]),
+        ]);
+    }
+
+    #[test]
+    fn test_overdue_comes_first_and_completed_and_undated_are_left_out() {
+        let mut done = due("Done tomorrow", 4, 9);
+        done.1.complete();
+        let tasks = vec![
+            due("Tomorrow", 4, 9),
+            due("Earlier today", 3, 8),
+            ("Work".to_string(), Task::new("No due date".to_string())),
+            done,
+            due("Monday", 1, 9),
+        ];
+        let groups = group_upcoming(tasks, now(), 7, &Utc);
+        assert_eq!(titles(&groups), vec![
+            (UpcomingDay::Overdue, vec!["Monday", "Earlier today"]),
+            (UpcomingDay::Day(date(4)), vec!["Tomorrow"]),
+        ]);
+    }
+
+    #[test]
+    fn test_days_are_calendar_days_in_tz() {
+        // 23:00 UTC on Thursday is already Friday at UTC+2
+        let tasks = vec![due("Late Thursday", 4, 23)];
+        let groups = group_upcoming(tasks.clone(), now(), 7, &Utc);
+        assert_eq!(groups[0].day, UpcomingDay::Day(date(4)));
+        let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+        let groups = group_upcoming(tasks, now(), 7, &tz);
+        assert_eq!(groups[0].day, UpcomingDay::Day(date(5)));
+    }
+
+    #[test]
+    fn test_window_length() {
+        let tasks = || vec![due("Today", 3, 20), due("Tomorrow", 4, 9), due("Overdue", 2, 9)];
+        assert_eq!(titles(&group_upcoming(tasks(), now(), 1, &Utc)), vec![
+            (UpcomingDay::Overdue, vec!["Overdue"]),
+            (UpcomingDay::Day(date(3)), vec!["Today"]),
+        ]);
+        assert_eq!(titles(&group_upcoming(tasks(), now(), 0, &Utc)), vec![
+            (UpcomingDay::Overdue, vec!["Overdue"]),
+        ]);
+    }
+}
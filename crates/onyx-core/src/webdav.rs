@@ -1,5 +1,6 @@
 use reqwest::Client;
 use zeroize::Zeroizing;
+use std::path::PathBuf;
 use std::time::Duration;
 use crate::error::{Error, Result};
 
@@ -15,7 +16,22 @@ pub struct RemoteFileInfo {
     pub last_modified: Option<String>,
 }
 
+/// Network settings for a `WebDavClient`. The default (no proxy, system root certificates)
+/// is what `WebDavClient::new` uses.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WebDavOptions {
+    /// Proxy all requests through this URL (`http://` or `https://`).
+    pub proxy: Option<String>,
+    /// PEM file with an extra root certificate to trust, for servers signed by a private CA.
+    /// System roots stay trusted as well.
+    pub ca_cert: Option<PathBuf>,
+}
+
 /// WebDAV client wrapping reqwest with basic auth. Credentials are zeroized on drop.
+///
+/// Cloning is cheap: clones share the underlying connection pool, so one client can serve
+/// a connection test and the sync that follows.
+#[derive(Clone)]
 pub struct WebDavClient {
     _client: Client,
     _base_url: String,
@@ -26,26 +42,61 @@ pub struct WebDavClient {
 impl WebDavClient {
     /// Create a new WebDAV client. Rejects non-HTTPS URLs to prevent sending credentials in plaintext.
     pub fn new(base_url: &str, username: &str, password: &str) -> Result<Self> {
+        Self::with_options(base_url, username, password, &WebDavOptions::default())
+    }
+
+    /// Create a client that goes through a proxy and/or trusts a custom root certificate.
+    pub fn with_options(base_url: &str, username: &str, password: &str, options: &WebDavOptions) -> Result<Self> {
         if !base_url.starts_with("https://") {
             return Err(Error::WebDav("Refusing non-HTTPS URL: credentials would be sent in plaintext".into()));
         }
-        Ok(Self::new_unchecked(base_url, username, password))
+        let mut builder = Self::client_builder();
+        if let Some(proxy) = &options.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| Error::WebDav(format!("Invalid proxy URL '{}': {}", proxy, e)))?;
+            builder = builder.proxy(proxy);
+        }
+        if let Some(path) = &options.ca_cert {
+            let pem = std::fs::read(path)
+                .map_err(|e| Error::WebDav(format!("Failed to read CA certificate {}: {}", path.display(), e)))?;
+            let cert = reqwest::Certificate::from_pem(&pem)
+                .map_err(|e| Error::WebDav(format!("Invalid CA certificate {}: {}", path.display(), e)))?;
+            builder = builder.add_root_certificate(cert);
+        }
+        let client = builder.build()
+            .map_err(|e| Error::WebDav(format!("Failed to set up HTTP client: {}", e)))?;
+        Ok(Self::from_client(client, base_url, username, password))
+    }
+
+    #[cfg(test)]
+    pub(crate) fn new_unchecked(base_url: &str, username: &str, password: &str) -> Self {
+        let client = Self::client_builder().build().unwrap_or_else(|_| Client::new());
+        Self::from_client(client, base_url, username, password)
     }
 
-    fn new_unchecked(base_url: &str, username: &str, password: &str) -> Self {
-        let base_url = base_url.trim_end_matches('/').to_string();
+    fn client_builder() -> reqwest::ClientBuilder {
+        Client::builder()
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(10))
+    }
+
+    fn from_client(client: Client, base_url: &str, username: &str, password: &str) -> Self {
         Self {
-            _client: Client::builder()
-                .timeout(Duration::from_secs(30))
-                .connect_timeout(Duration::from_secs(10))
-                .build()
-                .unwrap_or_else(|_| Client::new()),
-            _base_url: base_url,
+            _client: client,
+            _base_url: base_url.trim_end_matches('/').to_string(),
             _username: Zeroizing::new(username.to_string()),
             _password: Zeroizing::new(password.to_string()),
         }
     }
 
+    /// A client for a folder below this one, sharing its connection pool and credentials.
+    pub fn subdir(&self, path: &str) -> Self {
+        Self {
+            _base_url: self.full_url(path),
+            ..self.clone()
+        }
+    }
+
     fn full_url(&self, path: &str) -> String {
         let path = path.trim_start_matches('/');
         if path.is_empty() {
@@ -436,9 +487,73 @@ pub fn store_credentials(_domain: &str, _username: &str, _password: &str) -> Res
     Err(Error::Credential("Credential storage not available on this platform".into()))
 }
 
+/// Environment variables that supply WebDAV credentials without a keychain (servers, CI).
+pub const USER_ENV: &str = "ONYX_WEBDAV_USER";
+pub const PASSWORD_ENV: &str = "ONYX_WEBDAV_PASS";
+
+type Credentials = (Zeroizing<String>, Zeroizing<String>);
+
+/// Load WebDAV credentials from the environment, falling back to the platform keychain.
+pub fn load_credentials(domain: &str) -> Result<Credentials> {
+    resolve_credentials(domain, None)
+}
+
+/// Resolve WebDAV credentials for headless use. The password comes from `password_file`,
+/// then `ONYX_WEBDAV_PASS`, then the keychain; the username from `ONYX_WEBDAV_USER`, then
+/// the keychain. The keychain is only consulted when something is still missing.
+pub fn resolve_credentials(domain: &str, password_file: Option<&std::path::Path>) -> Result<Credentials> {
+    let file_password = password_file.map(read_password_file).transpose()?;
+    resolve_credentials_from(
+        domain,
+        file_password,
+        std::env::var(USER_ENV).ok(),
+        std::env::var(PASSWORD_ENV).ok(),
+        || load_keychain_credentials(domain),
+    )
+}
+
+/// Read a password file, ignoring the trailing newline most editors and `echo` add.
+fn read_password_file(path: &std::path::Path) -> Result<Zeroizing<String>> {
+    let content = Zeroizing::new(std::fs::read_to_string(path)
+        .map_err(|e| Error::Credential(format!("Failed to read password file {}: {}", path.display(), e)))?);
+    let password = content.trim_end_matches(['\n', '\r']);
+    if password.is_empty() {
+        return Err(Error::Credential(format!("Password file {} is empty", path.display())));
+    }
+    Ok(Zeroizing::new(password.to_string()))
+}
+
+fn resolve_credentials_from(
+    domain: &str,
+    file_password: Option<Zeroizing<String>>,
+    env_user: Option<String>,
+    env_password: Option<String>,
+    keychain: impl FnOnce() -> Result<Credentials>,
+) -> Result<Credentials> {
+    let env_password = env_password.map(|p| {
+        log::warn!("Using {} for the WebDAV password — these are visible to other processes on this system", PASSWORD_ENV);
+        Zeroizing::new(p)
+    });
+    let password = file_password.or(env_password);
+    let user = env_user.map(Zeroizing::new);
+
+    match (user, password) {
+        (Some(user), Some(password)) => Ok((user, password)),
+        (user, password) => {
+            let (keychain_user, keychain_password) = keychain().map_err(|e| match &password {
+                Some(_) => Error::Credential(format!(
+                    "No username found for '{}'. Set {} or run setup. ({})", domain, USER_ENV, e
+                )),
+                None => e,
+            })?;
+            Ok((user.unwrap_or(keychain_user), password.unwrap_or(keychain_password)))
+        }
+    }
+}
+
 #[cfg(feature = "keyring-storage")]
-/// Load WebDAV credentials from the platform keychain, falling back to env vars.
-pub fn load_credentials(domain: &str) -> Result<(Zeroizing<String>, Zeroizing<String>)> {
+/// Load WebDAV credentials from the platform keychain.
+fn load_keychain_credentials(domain: &str) -> Result<Credentials> {
     let service = format!("com.onyx.webdav.{}", domain);
 
     let user_entry = keyring::Entry::new(&service, "username")
@@ -473,15 +588,6 @@ pub fn load_credentials(domain: &str) -> Result<(Zeroizing<String>, Zeroizing<St
         }
     }
 
-    // Fallback to env vars for headless/CI environments
-    if let (Ok(user), Ok(pass)) = (
-        std::env::var("ONYX_WEBDAV_USER"),
-        std::env::var("ONYX_WEBDAV_PASS"),
-    ) {
-        log::warn!("Using environment variables for WebDAV credentials — prefer keyring for better security");
-        return Ok((Zeroizing::new(user), Zeroizing::new(pass)));
-    }
-
     Err(Error::Credential(format!(
         "No credentials found for '{}'. Run setup or configure environment variables.",
         domain
@@ -489,16 +595,8 @@ pub fn load_credentials(domain: &str) -> Result<(Zeroizing<String>, Zeroizing<St
 }
 
 #[cfg(not(feature = "keyring-storage"))]
-/// Load WebDAV credentials from env vars only (keyring not available).
-pub fn load_credentials(domain: &str) -> Result<(Zeroizing<String>, Zeroizing<String>)> {
-    if let (Ok(user), Ok(pass)) = (
-        std::env::var("ONYX_WEBDAV_USER"),
-        std::env::var("ONYX_WEBDAV_PASS"),
-    ) {
-        log::warn!("Using environment variables for WebDAV credentials — these are visible to other processes on this system");
-        return Ok((Zeroizing::new(user), Zeroizing::new(pass)));
-    }
-
+/// No keychain without the keyring-storage feature; credentials must come from env vars.
+fn load_keychain_credentials(domain: &str) -> Result<Credentials> {
     Err(Error::Credential(format!(
         "No credentials found for '{}'. Configure environment variables.",
         domain
@@ -715,6 +813,29 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_with_options_proxy_and_ca() {
+        let url = "https://example.com/dav";
+        let proxied = WebDavOptions { proxy: Some("http://proxy.internal:3128".into()), ca_cert: None };
+        assert!(WebDavClient::with_options(url, "user", "pass", &proxied).is_ok());
+
+        let bad_proxy = WebDavOptions { proxy: Some("not a url".into()), ca_cert: None };
+        let err = WebDavClient::with_options(url, "user", "pass", &bad_proxy).err().unwrap();
+        assert!(err.to_string().contains("Invalid proxy URL"));
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let missing = WebDavOptions { proxy: None, ca_cert: Some(dir.path().join("ca.pem")) };
+        let err = WebDavClient::with_options(url, "user", "pass", &missing).err().unwrap();
+        assert!(err.to_string().contains("Failed to read CA certificate"));
+
+        std::fs::write(dir.path().join("ca.pem"), "-----BEGIN CERTIFICATE-----\ngarbage\n-----END CERTIFICATE-----\n").unwrap();
+        let garbage = WebDavOptions { proxy: None, ca_cert: Some(dir.path().join("ca.pem")) };
+        assert!(WebDavClient::with_options(url, "user", "pass", &garbage).is_err());
+
+        // Options don't bypass the HTTPS check
+        assert!(WebDavClient::with_options("http://example.com/dav", "user", "pass", &proxied).is_err());
+    }
+
     #[test]
     fn test_full_url_building() {
         let client = WebDavClient::new_unchecked("http://example.com/dav/", "user", "pass");
@@ -729,6 +850,73 @@ mod tests {
         assert_eq!(client.full_url("/file.md"), "http://example.com/dav/file.md");
     }
 
+    #[test]
+    fn test_subdir_scopes_paths() {
+        let client = WebDavClient::new_unchecked("http://example.com/dav", "user", "pass");
+        let onyx = client.subdir("Onyx");
+        assert_eq!(onyx.full_url(""), "http://example.com/dav/Onyx");
+        assert_eq!(onyx.full_url("My Tasks/file.md"), "http://example.com/dav/Onyx/My%20Tasks/file.md");
+        assert_eq!(client.subdir("My Tasks").full_url(""), "http://example.com/dav/My%20Tasks");
+    }
+
+    // --- credential precedence ---
+
+    fn keychain() -> Result<Credentials> {
+        Ok((Zeroizing::new("keychain-user".into()), Zeroizing::new("keychain-pass".into())))
+    }
+
+    fn no_keychain() -> Result<Credentials> {
+        Err(Error::Credential("No credentials found for 'dav.example.com'".into()))
+    }
+
+    fn resolve(
+        file: Option<&str>,
+        env_user: Option<&str>,
+        env_pass: Option<&str>,
+        keychain: impl FnOnce() -> Result<Credentials>,
+    ) -> Result<(String, String)> {
+        resolve_credentials_from(
+            "dav.example.com",
+            file.map(|p| Zeroizing::new(p.to_string())),
+            env_user.map(String::from),
+            env_pass.map(String::from),
+            keychain,
+        ).map(|(u, p)| (u.to_string(), p.to_string()))
+    }
+
+    #[test]
+    fn test_credential_precedence() {
+        let pair = |u: &str, p: &str| (u.to_string(), p.to_string());
+        // Nothing set: the keychain
+        assert_eq!(resolve(None, None, None, keychain).unwrap(), pair("keychain-user", "keychain-pass"));
+        // Env password beats the keychain; the username still comes from the keychain
+        assert_eq!(resolve(None, None, Some("env-pass"), keychain).unwrap(), pair("keychain-user", "env-pass"));
+        // Password file beats the env var
+        assert_eq!(resolve(Some("file-pass"), None, Some("env-pass"), keychain).unwrap(), pair("keychain-user", "file-pass"));
+        // Fully specified without the keychain, which is never touched
+        let untouched = || -> Result<Credentials> { panic!("keychain consulted") };
+        assert_eq!(resolve(Some("file-pass"), Some("env-user"), None, untouched).unwrap(), pair("env-user", "file-pass"));
+        assert_eq!(resolve(None, Some("env-user"), Some("env-pass"), untouched).unwrap(), pair("env-user", "env-pass"));
+    }
+
+    #[test]
+    fn test_credential_errors_without_keychain() {
+        let err = resolve(Some("file-pass"), None, None, no_keychain).unwrap_err();
+        assert!(err.to_string().contains(USER_ENV));
+        let err = resolve(None, Some("env-user"), None, no_keychain).unwrap_err();
+        assert!(err.to_string().contains("No credentials found"));
+    }
+
+    #[test]
+    fn test_read_password_file_strips_newline() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("pass");
+        std::fs::write(&path, "s3cret pass\n").unwrap();
+        assert_eq!(read_password_file(&path).unwrap().as_str(), "s3cret pass");
+        std::fs::write(&path, "\n").unwrap();
+        assert!(read_password_file(&path).is_err());
+    }
+
     // --- extract_relative_path ---
 
     #[test]